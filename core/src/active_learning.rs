@@ -0,0 +1,171 @@
+
+use crate::csv_ext::table;
+use crate::validation;
+use strategie::{Modele, Strategie};
+
+mod strategie;
+
+// Étiquette des tweets restant à annoter (comme dans wip_annoted_avengers_150.csv)
+const NON_ANNOTE: &str = "-1";
+
+// (ligne, texte, étiquette) des tweets annotés
+type Annotes<'a> = Vec<(usize, &'a str, i32)>;
+// (ligne, texte) des tweets à annoter
+type Candidats<'a> = Vec<(usize, &'a str)>;
+
+/// File d'annotation par apprentissage actif sur un CSV `label,texte` dont la première colonne est vide ou à -1
+/// pour les tweets restant à annoter. Chaque lot est choisi avec un modèle réentraîné sur les étiquettes
+/// connues à ce moment, si bien que les annotations reçues servent dès le lot suivant.
+pub struct FileAnnotation {
+    // Lignes du fichier telles quelles (numéro de ligne = indice), étiquette en première colonne
+    lignes: Vec<Vec<String>>,
+    modele: Modele,
+    strategie: Strategie,
+}
+
+// Tweets proposés à l'annotation, du plus au moins utile
+pub struct Lot {
+    // Numéro de ligne dans le fichier
+    pub lignes: Vec<usize>,
+    pub textes: Vec<String>,
+    // Score de la stratégie
+    pub scores: Vec<f64>,
+    // Étiquette prédite et sa probabilité, pour pré-remplir l'annotation
+    pub predites: Vec<i32>,
+    pub confiances: Vec<f64>,
+}
+
+impl Default for FileAnnotation {
+    fn default() -> Self {
+        Self { lignes: Vec::new(), modele: Modele::Bayes, strategie: Strategie::Entropie }
+    }
+}
+
+impl FileAnnotation {
+    /// Charge le fichier à annoter.
+    /// model : 0 = Bayes, 1 = KNN (`k` voisins). strategy : 0 = entropie, 1 = marge, 2 = diversité.
+    pub fn charger(&mut self, chemin: &str, model: i64, strategy: i64, k: i64) -> Result<(), csv::Error> {
+        self.lignes = table::read_rows(chemin)?;
+        self.modele = Modele::depuis_numero(model, k);
+        self.strategie = Strategie::from(strategy);
+        Ok(())
+    }
+
+    /// Les `taille` prochains tweets à annoter
+    pub fn prochain_lot(&self, taille: usize) -> Lot {
+        let (annotes, candidats) = self.separer();
+        let textes_annotes: Vec<&str> = annotes.iter().map(|&(_, texte, _)| texte).collect();
+        let paires: Vec<(&str, i32)> = annotes.iter().map(|&(_, texte, etiquette)| (texte, etiquette)).collect();
+        let textes_candidats: Vec<&str> = candidats.iter().map(|&(_, texte)| texte).collect();
+
+        let probabilites = self.modele.probabilites(&paires, &textes_candidats);
+        let selection = strategie::selectionner(self.strategie, &probabilites, &textes_annotes, &textes_candidats, taille);
+
+        let mut lot = Lot { lignes: Vec::new(), textes: Vec::new(), scores: Vec::new(), predites: Vec::new(), confiances: Vec::new() };
+        for (i, score) in selection {
+            // Égalité : la plus petite étiquette
+            let p = probabilites[i];
+            let meilleur = (0..3).rev().max_by(|&a, &b| p[a].total_cmp(&p[b])).unwrap_or(1);
+            lot.lignes.push(candidats[i].0);
+            lot.textes.push(candidats[i].1.to_string());
+            lot.scores.push(score);
+            lot.predites.push(validation::CLASSES[meilleur]);
+            lot.confiances.push(p[meilleur]);
+        }
+        lot
+    }
+
+    /// Annote la ligne `ligne` (numéro de ligne du fichier) ; une étiquette hors de 0, 2, 4 la remet à annoter.
+    /// La ligne 0, l'en-tête, n'est pas un tweet : elle est refusée.
+    pub fn etiqueter(&mut self, ligne: usize, etiquette: i32) -> bool {
+        if ligne == 0 {
+            return false;
+        }
+        let Some(champ) = self.lignes.get_mut(ligne).and_then(|ligne| ligne.first_mut()) else {
+            return false;
+        };
+        *champ = match validation::index_classe(etiquette) {
+            Some(_) => etiquette.to_string(),
+            None => NON_ANNOTE.to_string(),
+        };
+        true
+    }
+
+    /// Écrit le fichier avec les annotations reçues
+    pub fn enregistrer(&self, chemin: &str) -> Result<(), csv::Error> {
+        table::write_rows(chemin, &self.lignes)
+    }
+
+    /// Nombre de tweets annotés et restant à annoter
+    pub fn comptes(&self) -> (usize, usize) {
+        let (annotes, candidats) = self.separer();
+        (annotes.len(), candidats.len())
+    }
+
+    // Tweets annotés et tweets à annoter. L'en-tête (ligne 0, refusée aussi par `etiqueter`) et les lignes
+    // d'étiquette inconnue sont ignorés.
+    fn separer(&self) -> (Annotes<'_>, Candidats<'_>) {
+        let mut annotes = Vec::new();
+        let mut candidats = Vec::new();
+        for (idx, ligne) in self.lignes.iter().enumerate().skip(1) {
+            let (Some(etiquette), Some(texte)) = (ligne.first(), ligne.get(1)) else {
+                continue;
+            };
+            let etiquette = etiquette.trim();
+            if etiquette.is_empty() || etiquette == NON_ANNOTE {
+                candidats.push((idx, texte.as_str()));
+            } else if let Ok(e) = etiquette.parse::<i32>()
+                && validation::index_classe(e).is_some()
+            {
+                annotes.push((idx, texte.as_str(), e));
+            }
+        }
+        (annotes, candidats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fichier(nom: &str, contenu: &str) -> String {
+        let chemin = std::env::temp_dir().join(format!("{}_{}", std::process::id(), nom));
+        std::fs::write(&chemin, contenu).unwrap();
+        chemin.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn en_tete_a_premiere_cellule_vide_ignore() {
+        let chemin = fichier("active_en_tete_vide.csv", ",texte\n0,je deteste\n4,j adore\n,bof\n-1,super\n");
+        let mut file = FileAnnotation::default();
+        file.charger(&chemin, 0, 0, 1).unwrap();
+        std::fs::remove_file(&chemin).unwrap();
+
+        // Seules les lignes 3 et 4 sont à annoter, l'en-tête n'est jamais proposé
+        assert_eq!(file.comptes(), (2, 2));
+        let lot = file.prochain_lot(10);
+        let mut lignes = lot.lignes.clone();
+        lignes.sort_unstable();
+        assert_eq!(lignes, vec![3, 4]);
+        assert!(!lot.textes.contains(&"texte".to_string()));
+    }
+
+    #[test]
+    fn etiquetage_et_remise_a_annoter() {
+        let chemin = fichier("active_etiquetage.csv", "label,texte\n0,je deteste\n4,j adore\n-1,super\n");
+        let mut file = FileAnnotation::default();
+        file.charger(&chemin, 0, 0, 1).unwrap();
+
+        assert!(!file.etiqueter(0, 4));
+        assert!(!file.etiqueter(10, 4));
+        assert!(file.etiqueter(3, 4));
+        assert_eq!(file.comptes(), (3, 0));
+        assert!(file.etiqueter(1, 3));
+        assert_eq!(file.comptes(), (2, 1));
+
+        file.enregistrer(&chemin).unwrap();
+        let contenu = std::fs::read_to_string(&chemin).unwrap();
+        std::fs::remove_file(&chemin).unwrap();
+        assert_eq!(contenu, "label,texte\n-1,je deteste\n4,j adore\n4,super\n");
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::{bayes, knn, parallel};
+
+// Modèle qui estime les probabilités des classes des tweets à annoter
+#[derive(Debug, Clone, Copy)]
+pub enum Modele {
+    // Laplace, présence des mots, unigrammes (réglages par défaut de l'onglet Bayes)
+    Bayes,
+    // Vote pondéré par l'inverse de la distance
+    Knn { k: usize },
+}
+
+impl Modele {
+    // 0 = Bayes, 1 = KNN, même numérotation que active_load
+    pub fn depuis_numero(modele: i64, k: i64) -> Self {
+        match modele {
+            1 => Modele::Knn { k: k.max(1) as usize },
+            _ => Modele::Bayes,
+        }
+    }
+
+    pub fn probabilites(&self, annotes: &[(&str, i32)], textes: &[&str]) -> Vec<[f64; 3]> {
+        match self {
+            Modele::Bayes => bayes::probabilites(annotes, textes, 0, 0, 0),
+            Modele::Knn { k } => knn::probabilites(annotes, textes, *k, 1),
+        }
+    }
+}
+
+// Ordre de présentation des tweets à annoter
+#[derive(Debug, Clone, Copy)]
+pub enum Strategie {
+    // Entropie des probabilités la plus haute d'abord
+    Entropie,
+    // Écart le plus faible entre les deux classes les plus probables d'abord
+    Marge,
+    // Tweet le plus éloigné des tweets annotés et de ceux déjà retenus dans le lot d'abord
+    Diversite,
+}
+
+impl From<i64> for Strategie {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => Strategie::Marge,
+            2 => Strategie::Diversite,
+            _ => Strategie::Entropie,
+        }
+    }
+}
+
+/// Choisit au plus `taille` candidats à annoter, du plus au moins utile.
+/// Renvoie (indice dans `candidats`, score) ; plus le score est élevé, plus le tweet est utile.
+/// À score égal, l'ordre du fichier est conservé.
+pub fn selectionner(strategie: Strategie, probabilites: &[[f64; 3]], annotes: &[&str], candidats: &[&str], taille: usize) -> Vec<(usize, f64)> {
+    let mut scores: Vec<(usize, f64)> = match strategie {
+        Strategie::Entropie => probabilites.iter().map(entropie).enumerate().collect(),
+        Strategie::Marge => probabilites.iter().map(|p| 1.0 - marge(p)).enumerate().collect(),
+        Strategie::Diversite => return plus_eloignes(annotes, candidats, taille),
+    };
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scores.truncate(taille);
+    scores
+}
+
+fn entropie(probabilites: &[f64; 3]) -> f64 {
+    probabilites.iter().filter(|&&p| p > 0.0).map(|&p| -p * p.ln()).sum()
+}
+
+fn marge(probabilites: &[f64; 3]) -> f64 {
+    let mut triees = *probabilites;
+    triees.sort_by(|a, b| b.total_cmp(a));
+    triees[0] - triees[1]
+}
+
+// Parcours du plus éloigné (k-center glouton) : chaque candidat retenu devient une référence pour les suivants.
+// Le score est la distance au tweet de référence le plus proche au moment du choix.
+fn plus_eloignes(annotes: &[&str], candidats: &[&str], taille: usize) -> Vec<(usize, f64)> {
+    let mots_annotes: Vec<HashSet<String>> = annotes.iter().map(|t| knn::tokeniser_tweet(t)).collect();
+    let mots_candidats: Vec<HashSet<String>> = candidats.iter().map(|t| knn::tokeniser_tweet(t)).collect();
+
+    // Sans tweet annoté, tous les candidats sont à distance maximale
+    let mut minimums: Vec<f64> = parallel::executer(|| {
+        mots_candidats
+            .par_iter()
+            .map(|mots| mots_annotes.iter().map(|a| knn::distance_mots(mots, a)).fold(1.0, f64::min))
+            .collect()
+    });
+
+    let mut choisis = Vec::with_capacity(taille.min(candidats.len()));
+    let mut restants: Vec<bool> = vec![true; candidats.len()];
+    while choisis.len() < taille {
+        let Some((meilleur, score)) = minimums
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| restants[i])
+            .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(&a.0)))
+            .map(|(i, &d)| (i, d))
+        else {
+            break;
+        };
+        restants[meilleur] = false;
+        choisis.push((meilleur, score));
+
+        let reference = &mots_candidats[meilleur];
+        parallel::executer(|| {
+            minimums.par_iter_mut().zip(&mots_candidats).for_each(|(minimum, mots)| {
+                *minimum = minimum.min(knn::distance_mots(mots, reference));
+            })
+        });
+    }
+    choisis
+}
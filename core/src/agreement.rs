@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+
+use crate::csv_ext::table;
+use crate::validation;
+
+pub mod kappa;
+
+// Tweets de plusieurs fichiers annotés, alignés sur leur texte
+pub struct Alignement {
+    // Nom de chaque annotateur (nom du fichier sans extension)
+    pub annotateurs: Vec<String>,
+    // Texte de chaque tweet, dans l'ordre de première apparition
+    pub textes: Vec<String>,
+    // annotations[tweet][annotateur] : indice de classe (ordre de validation::CLASSES), None si non annoté
+    pub annotations: Vec<Vec<Option<usize>>>,
+}
+
+// Charge chaque fichier et regroupe les annotations d'un même texte.
+// Si un fichier contient plusieurs fois le même texte, sa première annotation est gardée.
+pub fn aligner(chemins: &[String]) -> Result<Alignement, Box<dyn Error>> {
+    if chemins.len() < 2 {
+        return Err("Au moins deux fichiers annotés sont nécessaires".into());
+    }
+    let nb_annotateurs = chemins.len();
+    let mut alignement = Alignement { annotateurs: Vec::new(), textes: Vec::new(), annotations: Vec::new() };
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (annotateur, chemin) in chemins.iter().enumerate() {
+        let nom = Path::new(chemin).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| chemin.clone());
+        alignement.annotateurs.push(nom);
+
+        for ligne in table::read_rows(chemin)? {
+            let (Some(etiquette), Some(texte)) = (ligne.first(), ligne.get(1)) else {
+                continue;
+            };
+            let cle = normaliser(texte);
+            if cle.is_empty() {
+                continue;
+            }
+            let idx = *index.entry(cle).or_insert_with(|| {
+                alignement.textes.push(texte.trim().to_string());
+                alignement.annotations.push(vec![None; nb_annotateurs]);
+                alignement.textes.len() - 1
+            });
+            let classe = etiquette.trim().parse::<i32>().ok().and_then(validation::index_classe);
+            let annotation = &mut alignement.annotations[idx][annotateur];
+            if annotation.is_none() {
+                *annotation = classe;
+            }
+        }
+    }
+    Ok(alignement)
+}
+
+fn normaliser(texte: &str) -> String {
+    texte.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Tweets annotés au moins deux fois avec des étiquettes différentes, avec leur étiquette majoritaire
+// (None en cas d'égalité)
+pub fn conflits(alignement: &Alignement) -> Vec<(usize, Option<i32>)> {
+    alignement
+        .annotations
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, ligne)| {
+            let mut comptes = [0usize; 3];
+            for c in ligne.iter().flatten() {
+                comptes[*c] += 1;
+            }
+            let utilisees = comptes.iter().filter(|&&n| n > 0).count();
+            if utilisees < 2 {
+                return None;
+            }
+            let max = *comptes.iter().max().unwrap();
+            let majoritaire = (comptes.iter().filter(|&&n| n == max).count() == 1)
+                .then(|| validation::CLASSES[comptes.iter().position(|&n| n == max).unwrap()]);
+            Some((idx, majoritaire))
+        })
+        .collect()
+}
+
+pub fn ecrire_conflits(chemin: &str, alignement: &Alignement, conflits: &[(usize, Option<i32>)]) -> Result<(), Box<dyn Error>> {
+    let mut lignes = Vec::with_capacity(conflits.len() + 1);
+    let mut entete = vec!["label".to_string(), "text".to_string()];
+    entete.extend(alignement.annotateurs.iter().cloned());
+    lignes.push(entete);
+
+    for &(idx, majoritaire) in conflits {
+        let mut ligne = vec![majoritaire.unwrap_or(-1).to_string(), alignement.textes[idx].clone()];
+        ligne.extend(
+            alignement.annotations[idx]
+                .iter()
+                .map(|c| c.map(|c| validation::CLASSES[c].to_string()).unwrap_or_default()),
+        );
+        lignes.push(ligne);
+    }
+    table::write_rows(chemin, &lignes)?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use crate::validation;
+
+// Accord entre deux annotateurs sur les tweets qu'ils ont tous deux annotés
+pub struct AccordPaire {
+    pub premier: usize,
+    pub second: usize,
+    pub n: usize,
+    // Part des tweets où les deux étiquettes sont identiques
+    pub observe: f64,
+    pub kappa: f64,
+}
+
+/// Kappa de Cohen de chaque paire d'annotateurs.
+/// `annotations[tweet][annotateur]` est l'indice de classe (ordre de validation::CLASSES) ou None.
+pub fn cohen(annotations: &[Vec<Option<usize>>], nb_annotateurs: usize) -> Vec<AccordPaire> {
+    let mut paires = Vec::new();
+    for premier in 0..nb_annotateurs {
+        for second in premier + 1..nb_annotateurs {
+            let mut table = [[0usize; 3]; 3];
+            for ligne in annotations {
+                if let (Some(a), Some(b)) = (ligne[premier], ligne[second]) {
+                    table[a][b] += 1;
+                }
+            }
+            let n: usize = table.iter().flatten().sum();
+            let (observe, kappa) = kappa_cohen(&table, n);
+            paires.push(AccordPaire { premier, second, n, observe, kappa });
+        }
+    }
+    paires
+}
+
+fn kappa_cohen(table: &[[usize; 3]; 3], n: usize) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+    let n = n as f64;
+    let observe = (0..3).map(|c| table[c][c]).sum::<usize>() as f64 / n;
+    let attendu: f64 = (0..3)
+        .map(|c| {
+            let ligne: usize = table[c].iter().sum();
+            let colonne: usize = table.iter().map(|l| l[c]).sum();
+            ligne as f64 / n * colonne as f64 / n
+        })
+        .sum();
+    (observe, kappa(observe, attendu))
+}
+
+// Accord corrigé du hasard. Si le hasard suffit à tout expliquer (une seule classe utilisée), 1 pour un accord parfait.
+fn kappa(observe: f64, attendu: f64) -> f64 {
+    if attendu >= 1.0 {
+        if observe >= 1.0 { 1.0 } else { 0.0 }
+    } else {
+        (observe - attendu) / (1.0 - attendu)
+    }
+}
+
+// Accord de l'ensemble des annotateurs
+pub struct AccordGlobal {
+    // Accord observé moyen par tweet (part des paires d'annotateurs d'accord)
+    pub observe: f64,
+    pub fleiss: f64,
+    // Accord spécifique de chaque classe, dans l'ordre de validation::CLASSES (None si la classe n'est jamais utilisée)
+    pub par_classe: [Option<f64>; 3],
+}
+
+/// Kappa de Fleiss, généralisé à un nombre d'annotateurs variable par tweet : seuls les tweets annotés au moins
+/// deux fois comptent, chacun pondéré également. L'accord spécifique d'une classe est la probabilité qu'un second
+/// annotateur choisisse cette classe sachant qu'un premier l'a choisie.
+pub fn fleiss(annotations: &[Vec<Option<usize>>]) -> AccordGlobal {
+    let mut somme_accords = 0.0;
+    let mut nb_tweets = 0usize;
+    let mut totaux = [0usize; 3];
+    let mut paires_accord = [0usize; 3];
+    let mut paires_possibles = [0usize; 3];
+
+    for ligne in annotations {
+        let mut comptes = [0usize; 3];
+        for c in ligne.iter().flatten() {
+            comptes[*c] += 1;
+        }
+        let n_i: usize = comptes.iter().sum();
+        if n_i < 2 {
+            continue;
+        }
+        nb_tweets += 1;
+        let accords: usize = comptes.iter().map(|&n_ij| n_ij * (n_ij.saturating_sub(1))).sum();
+        somme_accords += accords as f64 / (n_i * (n_i - 1)) as f64;
+        for c in 0..validation::CLASSES.len() {
+            totaux[c] += comptes[c];
+            paires_accord[c] += comptes[c] * comptes[c].saturating_sub(1);
+            paires_possibles[c] += comptes[c] * (n_i - 1);
+        }
+    }
+
+    if nb_tweets == 0 {
+        return AccordGlobal { observe: 0.0, fleiss: 0.0, par_classe: [None; 3] };
+    }
+    let observe = somme_accords / nb_tweets as f64;
+    let total: usize = totaux.iter().sum();
+    let attendu: f64 = totaux.iter().map(|&t| (t as f64 / total as f64).powi(2)).sum();
+    let par_classe = [0, 1, 2].map(|c| (paires_possibles[c] > 0).then(|| paires_accord[c] as f64 / paires_possibles[c] as f64));
+
+    AccordGlobal { observe, fleiss: kappa(observe, attendu), par_classe }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proche(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn cohen_table_connue() {
+        // Accord observé 0,7, attendu 0,6 × 0,5 + 0,4 × 0,5 = 0,5
+        let table = [[20, 5, 0], [10, 15, 0], [0, 0, 0]];
+        let (observe, kappa) = kappa_cohen(&table, 50);
+        assert!(proche(observe, 0.7));
+        assert!(proche(kappa, 0.4));
+    }
+
+    #[test]
+    fn cohen_par_paire() {
+        let annotations = vec![
+            vec![Some(0), Some(0), None],
+            vec![Some(1), Some(1), Some(1)],
+            vec![Some(2), Some(1), Some(2)],
+            vec![Some(2), Some(2), Some(2)],
+        ];
+        let paires = cohen(&annotations, 3);
+        assert_eq!(paires.len(), 3);
+        assert_eq!((paires[0].premier, paires[0].second, paires[0].n), (0, 1, 4));
+        assert!(proche(paires[0].observe, 0.75));
+        // Le troisième annotateur n'a pas vu le premier tweet
+        assert_eq!(paires[1].n, 3);
+        assert!(proche(paires[1].kappa, 1.0));
+    }
+
+    #[test]
+    fn cohen_une_seule_classe() {
+        let (_, kappa) = kappa_cohen(&[[0, 0, 0], [0, 8, 0], [0, 0, 0]], 8);
+        assert!(proche(kappa, 1.0));
+    }
+
+    #[test]
+    fn fleiss_table_connue() {
+        // Accords par tweet 1, 1, 1/3, 1/3 ; proportions 5/12, 5/12, 2/12 : kappa = (2/3 - 3/8) / (5/8) = 7/15
+        let annotations = vec![
+            vec![Some(0), Some(0), Some(0)],
+            vec![Some(1), Some(1), Some(1)],
+            vec![Some(0), Some(0), Some(1)],
+            vec![Some(2), Some(2), Some(1)],
+        ];
+        let accord = fleiss(&annotations);
+        assert!(proche(accord.observe, 2.0 / 3.0));
+        assert!(proche(accord.fleiss, 7.0 / 15.0));
+        assert!(proche(accord.par_classe[0].unwrap(), 0.8));
+    }
+
+    #[test]
+    fn fleiss_ignore_les_tweets_annotes_une_fois() {
+        let annotations = vec![vec![Some(0), None], vec![Some(1), Some(1)], vec![Some(2), Some(2)]];
+        let accord = fleiss(&annotations);
+        assert!(proche(accord.fleiss, 1.0));
+        assert_eq!(accord.par_classe[0], None);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bayes::ngram::NgramMode;
+use crate::bayes::representation::Representation;
+use crate::bayes::smoothing::VoteType;
+use crate::parallel;
+use crate::progress::Progress;
+use crate::validation;
+pub use crate::validation::{TweetEtiquete, charger_donnees};
+
+pub mod ngram;
+pub mod smoothing;
+pub mod representation;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BayesModel {
+    log_prior: HashMap<i32, f64>,
+    log_prob: HashMap<i32, HashMap<String, f64>>,
+    vocab: HashSet<String>,
+    vocab_taille: usize,
+    total_mots_par_classe: HashMap<i32, usize>,
+    representation: Representation,
+    ngram_mode: NgramMode,
+    alpha: f64,
+}
+
+impl BayesModel {
+    pub fn new(data: &[TweetEtiquete], vote: VoteType, representation: Representation, ngram_mode: NgramMode) -> Self {
+
+        // Comptage
+        let mut class_counts: HashMap<i32, usize> = HashMap::new();
+        for t in data {
+            *class_counts.entry(t.etiquette).or_insert(0) += 1;
+        }
+
+        let mut word_counts: HashMap<i32, HashMap<String, usize>> = HashMap::new();
+        let mut vocab: HashSet<String> = HashSet::new();
+
+        for t in data {
+            let tokens = ngram_mode.tokeniser_tweet(&t.contenu);
+            
+            // Selon le mode, on garde soit tous les mots (Fréquence), soit les uniques (Présence)
+            let tokens_to_count: Vec<String> = representation.tokens_to_count(tokens);
+            
+            // Remplissage de la matrice de comptage
+            let map = word_counts.entry(t.etiquette).or_default();
+            for w in tokens_to_count {
+                *map.entry(w.clone()).or_insert(0) += 1;
+                vocab.insert(w);
+            }
+        }
+
+        // Définition du paramètre de lissage
+        let alpha = f64::from(vote);
+        
+        // Calcul des Priors
+        let total_documents = class_counts.values().sum::<usize>() as f64;
+        let mut log_prior = HashMap::new();
+        for (&cls, &cnt) in &class_counts {
+            log_prior.insert(cls, (cnt as f64 / total_documents).ln());
+        }
+        
+        // Calcul des Likelihoods
+        let mut log_prob: HashMap<i32, HashMap<String, f64>> = HashMap::new();
+        let vocab_taille = vocab.len() as f64;
+        
+        for (&cls, map) in &word_counts {
+            let class_total: usize = map.values().sum();
+            let denom = class_total as f64 + alpha * vocab_taille;
+            let mut ll_map: HashMap<String, f64> = HashMap::new();
+            for (w, &cnt) in map {
+                let p = (cnt as f64 + alpha) / denom;
+                ll_map.insert(w.clone(), p.ln());
+            }
+            log_prob.insert(cls, ll_map);
+        }
+
+        let mut total_mots_par_classe = HashMap::new();
+        for (&cls, map) in &word_counts {
+            let total: usize = map.values().sum();
+            total_mots_par_classe.insert(cls, total);
+        }
+
+        Self {
+            log_prior,
+            log_prob,
+            vocab : vocab.clone(),
+            vocab_taille: vocab.len(),
+            total_mots_par_classe,
+            representation,
+            ngram_mode,
+            alpha,
+        }
+    }
+
+    /// Modèle entraîné sur les paires (texte, étiquette)
+    pub fn depuis_paires(entrainement: &[(&str, i32)], type_vote: i64, type_representation: i64, ngram_type: i64) -> Self {
+        let data: Vec<TweetEtiquete> = entrainement
+            .iter()
+            .map(|&(contenu, etiquette)| TweetEtiquete { contenu: contenu.to_string(), etiquette })
+            .collect();
+        Self::new(&data, VoteType::from(type_vote), Representation::from(type_representation), NgramMode::from(ngram_type))
+    }
+
+    /// Modèle entraîné sur tout le fichier `label,texte`, pour être enregistré
+    pub fn entrainer(path: &str, type_vote: i64, type_representation: i64, ngram_type: i64) -> Result<Self, String> {
+        let data = charger_donnees(path).map_err(|e| format!("Erreur chargement données : {}", e))?;
+        if data.is_empty() {
+            return Err("Base d'entraînement vide".to_string());
+        }
+        Ok(Self::new(&data, VoteType::from(type_vote), Representation::from(type_representation), NgramMode::from(ngram_type)))
+    }
+
+    // Retourne la classe ayant le score (log-probabilité) le plus élevé
+    pub fn classifier(&self, tweet: &str) -> Option<i32> {
+            // on renvoie une classe par défaut ou aléatoire si égalité parfaite
+            self.scores(tweet).into_iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(c,_)| c)
+    }
+
+    // Probabilités a posteriori dans l'ordre de validation::CLASSES (log-probabilités normalisées),
+    // uniformes si le modèle n'a vu aucune classe
+    pub fn probabilites(&self, tweet: &str) -> [f64; 3] {
+        let scores = self.scores(tweet);
+        let max = scores.values().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut probabilites = [0.0; 3];
+        for (cls, score) in scores {
+            if let Some(idx) = validation::index_classe(cls) {
+                probabilites[idx] = (score - max).exp();
+            }
+        }
+        let total: f64 = probabilites.iter().sum();
+        if total > 0.0 { probabilites.map(|p| p / total) } else { [1.0 / 3.0; 3] }
+    }
+
+    // Log-probabilité de chaque classe vue à l'entraînement
+    fn scores(&self, tweet: &str) -> HashMap<i32, f64> {
+        let tokens = self.ngram_mode.tokeniser_tweet(tweet);
+        
+        let tokens_to_score: Vec<String> = self.representation.tokens_to_count(tokens);
+
+        let mut scores: HashMap<i32, f64> = HashMap::new();
+
+        for (&cls, &prior) in &self.log_prior {
+            let mut score = prior;
+
+            // On récupère les infos de la classe
+            let ll_map = self.log_prob.get(&cls).unwrap();
+            let log_defaut = self.log_defaut(cls);
+
+            for w in &tokens_to_score {
+                // 1. Si le mot n'est pas dans le vocabulaire global d'entrainement, on l'ignore totalement
+                if !self.vocab.contains(w) {
+                    continue; 
+                }
+
+                // 2. On cherche la proba du mot dans la classe, sinon on utilise la proba lissée
+                let log_lk = ll_map.get(w).cloned().unwrap_or(log_defaut);
+                
+                score += log_lk;
+            }
+            scores.insert(cls, score);
+        }
+        scores
+    }
+
+    // Log-probabilité lissée d'un mot inconnu dans cette classe (mais connu du vocabulaire global)
+    fn log_defaut(&self, cls: i32) -> f64 {
+        let class_total = *self.total_mots_par_classe.get(&cls).unwrap_or(&0);
+        let denom = class_total as f64 + self.alpha * self.vocab_taille as f64;
+        (self.alpha / denom).ln()
+    }
+
+    /// Mots du tweet connus du vocabulaire, avec leur nombre d'occurrences comptées et leur log-vraisemblance
+    /// dans chaque classe (ordre de validation::CLASSES, None si la classe est absente de l'entraînement)
+    pub fn contributions(&self, tweet: &str) -> Vec<(String, usize, [Option<f64>; 3])> {
+        let tokens = self.representation.tokens_to_count(self.ngram_mode.tokeniser_tweet(tweet));
+
+        let mut mots: Vec<(String, usize, [Option<f64>; 3])> = Vec::new();
+        for w in tokens.into_iter().filter(|w| self.vocab.contains(w)) {
+            if let Some(mot) = mots.iter_mut().find(|(m, _, _)| *m == w) {
+                mot.1 += 1;
+                continue;
+            }
+            let log_lk = validation::CLASSES
+                .map(|cls| self.log_prob.get(&cls).map(|ll_map| ll_map.get(&w).copied().unwrap_or(self.log_defaut(cls))));
+            mots.push((w, 1, log_lk));
+        }
+        mots
+    }
+}
+
+/// Probabilités de chaque classe (ordre de validation::CLASSES) pour chaque texte, selon un modèle
+/// entraîné sur les paires (texte, étiquette) de `entrainement`. Utilisé par l'apprentissage actif.
+pub fn probabilites(entrainement: &[(&str, i32)], textes: &[&str], type_vote: i64, type_representation: i64, ngram_type: i64) -> Vec<[f64; 3]> {
+    let model = BayesModel::depuis_paires(entrainement, type_vote, type_representation, ngram_type);
+    parallel::executer(|| textes.par_iter().map(|texte| model.probabilites(texte)).collect())
+}
+
+// Évaluation sur une division stratifiée 2/3 - 1/3, renvoie le tableau de la matrice de confusion.
+// La progression compte les tweets de test classés.
+pub fn evaluer(path: &str, type_vote: i64, type_representation: i64, ngram_type: i64, graine: i64, progress: &Progress) -> Result<String, String> {
+    matrice_evaluation(path, type_vote, type_representation, ngram_type, graine, progress)
+        .map(|matrice| validation::format_matrice_confusion(&matrice))
+}
+
+// Matrice de confusion [réel][estimé] de la même évaluation
+pub fn matrice_evaluation(path: &str, type_vote: i64, type_representation: i64, ngram_type: i64, graine: i64, progress: &Progress) -> Result<[[i32; 3]; 3], String> {
+    let vote_type = VoteType::from(type_vote);
+
+    let representation = Representation::from(type_representation);
+
+    let ngram_mode = NgramMode::from(ngram_type);
+
+    let all = charger_donnees(path)
+        .map_err(|e| format!("Erreur chargement données : {}", e))?;
+
+    // Division du dataset : 2/3 entraînement, 1/3 test
+    let (train, test) = validation::diviser_donnees_stratifiee(&all, |t| t.etiquette, 2.0 / 3.0, graine);
+    if train.is_empty() || test.is_empty() {
+        return Err("Base d'entraînement ou test vide".to_string());
+    }
+
+    let model = BayesModel::new(&train, vote_type, representation, ngram_mode);
+    
+    let mut matrice_confusion = [[0; 3]; 3];
+    
+    // Les tweets de test sont classés en parallèle, le modèle n'étant que lu
+    progress.set_total(test.len());
+    let predictions: Option<Vec<(i32, Option<i32>)>> = parallel::executer(|| {
+        test.par_iter()
+            .map(|tweet| {
+                if progress.is_cancelled() {
+                    return None;
+                }
+                let pred = model.classifier(&tweet.contenu);
+                progress.advance(1);
+                Some((tweet.etiquette, pred))
+            })
+            .collect()
+    });
+    let predictions = predictions.ok_or("Évaluation annulée")?;
+
+    for (etiquette, pred) in predictions {
+        if let Some(pred) = pred {
+            validation::ajouter_prediction(&mut matrice_confusion, etiquette, pred);
+        }
+    }
+    Ok(matrice_confusion)
+}
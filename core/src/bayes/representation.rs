@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Representation {
     Presence,
     Frequence,
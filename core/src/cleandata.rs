@@ -0,0 +1,6 @@
+pub mod generic;
+pub mod entry;
+pub mod error;
+pub mod rule_filter;
+pub mod row_rule;
+pub mod rules_file;
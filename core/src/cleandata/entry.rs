@@ -0,0 +1,53 @@
+use crate::cleandata::error::CleanDataError;
+use crate::cleandata::generic::clean_data_generic;
+use crate::cleandata::row_rule::Rules;
+use crate::cleandata::rules_file;
+
+use crate::csv_ext::cols_sniffer::{self, ColsSniffer};
+use crate::progress::Progress;
+
+mod rules_regex;
+mod auto_rules;
+
+const DATA_COL: usize = 1;
+
+// `rules_path` is a rules file (see rules_file), the automatic filters are used without one
+pub fn clean_data_body(
+    data_path: &str,
+    rules_path: Option<&str>,
+    log: &mut dyn FnMut(String),
+    progress: &Progress,
+) -> Result<String, CleanDataError> {
+    clean_data_to(data_path, "clean_data_temp.csv", rules_path, log, progress)
+}
+
+// Same as clean_data_body, writing the cleaned `label,text` rows to `output_path`
+pub fn clean_data_to(
+    data_path: &str,
+    output_path: &str,
+    rules_path: Option<&str>,
+    log: &mut dyn FnMut(String),
+    progress: &Progress,
+) -> Result<String, CleanDataError> {
+    // FILTERS FROM THE RULES FILE, OR AUTO FILTERS GENERATION
+    let rules = match rules_path {
+        Some(path) => rules_file::load(path)?,
+        None => Rules::on_data(auto_rules::filters()?),
+    };
+
+    // Columns sniffing
+    // Warning here, rating and data cols might end up being the same
+    let auto_columns = ColsSniffer::sniff_columns(data_path);
+    let auto_columns = cols_sniffer::error::to_auto_columns(&auto_columns, DATA_COL);
+    
+    // CALL WITH THESE FILTERS AND STATIC COLUMNS
+    clean_data_generic(
+        data_path,
+        output_path,
+        auto_columns.data_column, 
+        auto_columns.rating_column, 
+        &rules,
+        log,
+        progress
+    )
+}
\ No newline at end of file
@@ -8,7 +8,7 @@ static NEGATIVES: [&str; 3] = ["(:\\()", "(:\\-\\()", "(D:)"];    // These shoul
 
 static ULR_HEADERS: [&str; 3] = ["http:", "https:", "www."];
 
-pub(super) fn filters() -> Result<Vec<RuleFilter>, CleanDataError> {
+pub fn filters() -> Result<Vec<RuleFilter>, CleanDataError> {
     let url: Regex = rules_regex::url(&ULR_HEADERS)?;
     let unvalid_emojis_re: Regex = rules_regex::unvalid_emojis(&POSITIVES, &NEGATIVES)?;
     let punctuation: Regex = Regex::new("[!\\?\\\"\\.;,\\:\\*]")?;
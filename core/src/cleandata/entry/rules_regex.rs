@@ -2,7 +2,7 @@ use regex::{Regex, Error};
 
 use crate::regex_ext::builder::RegexLogicalBuilder;
 
-pub(super) fn unvalid_emojis(positives: &[&str], negatives: &[&str]) -> Result<Regex, Error> {
+pub fn unvalid_emojis(positives: &[&str], negatives: &[&str]) -> Result<Regex, Error> {
     RegexLogicalBuilder::new()
         .contains(RegexLogicalBuilder::new()
             .any_of(RegexLogicalBuilder::strings_to_builders(positives))
@@ -13,15 +13,15 @@ pub(super) fn unvalid_emojis(positives: &[&str], negatives: &[&str]) -> Result<R
         .build()
 }
 
-pub(super) fn retweet() -> Result<Regex, Error> {
+pub fn retweet() -> Result<Regex, Error> {
     RegexLogicalBuilder::from("RT")
         .as_whole_word()
         .build()
 }
 
-pub(super) fn url(url_headers: &[&str]) -> Result<Regex, Error> {
+pub fn url(url_headers: &[&str]) -> Result<Regex, Error> {
     RegexLogicalBuilder::new()
-        .any_of(RegexLogicalBuilder::strings_to_builders(&url_headers))
+        .any_of(RegexLogicalBuilder::strings_to_builders(url_headers))
         .group()
         .plus_non_space()
         .any_times()
@@ -29,7 +29,7 @@ pub(super) fn url(url_headers: &[&str]) -> Result<Regex, Error> {
         .build()
 }
 
-pub(super) fn user() -> Result<Regex, Error> {
+pub fn user() -> Result<Regex, Error> {
     RegexLogicalBuilder::from("@")
         .plus_non_space()
         .any_times()
@@ -12,5 +12,15 @@ pub enum CleanDataError {
     #[error("Rating column not found in record `{0:?}`")]
     MissingRating(ByteRecord),
     #[error("Error while evaluating regex expression - `{0}`")]
-    RegexError(#[from] regex::Error)
+    RegexError(#[from] regex::Error),
+    #[error("Rules file `{0}` is invalid - {1}")]
+    RulesFormat(String, String),
+    #[error("Rule #{index} (`{name}`) is invalid - {reason}")]
+    InvalidRule { index: usize, name: String, reason: String },
+    #[error("Column {0} not found in record `{1:?}`")]
+    MissingColumn(usize, ByteRecord),
+    #[error("Column `{0}` not found - columns are {1:?}")]
+    UnknownColumn(String, Vec<String>),
+    #[error("Cleaning cancelled")]
+    Cancelled,
 }
\ No newline at end of file
@@ -0,0 +1,197 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+use csv::{ByteRecord, ReaderBuilder, Writer};
+
+use itertools::Itertools;
+
+use crate::cleandata::error::CleanDataError;
+use crate::cleandata::rule_filter::RuleFilter;
+use crate::cleandata::row_rule::{Rules, Test};
+
+use crate::csv_ext::encoding;
+use crate::progress::Progress;
+
+const BLANK_RATING: &str = "2";
+
+// A rule with its columns resolved against the names of the csv columns
+struct ResolvedRule<'a> {
+    column: usize,
+    filter: &'a RuleFilter,
+    conditions: Vec<(usize, &'a Test)>,
+}
+
+// Logs are handed to `log` as they come, progress is reported in bytes read from the input file.
+pub fn clean_data_generic(
+    input_path: &str,
+    output_path: &str,
+    data_col: usize,
+    rating_col: Option<usize>,
+    rules: &Rules,
+    log: &mut dyn FnMut(String),
+    progress: &Progress,
+) -> Result<String, CleanDataError>
+{   
+    let mut filter_counters: Vec<u32> = vec![0; rules.rules.len()];     // To track the number of tweets that pass through each filters
+
+    progress.set_total(fs::metadata(input_path)?.len() as usize);
+
+    // Columns named by the rules file are for csv files without header (Sentiment140) : the first row is data
+    let mut rdr = ReaderBuilder::new().has_headers(rules.columns.is_none()).from_path(input_path)?;
+    let names: Vec<String> = match &rules.columns {
+        Some(columns) => columns.clone(),
+        None => rdr.byte_headers()?.iter().map(|name| encoding::detect_and_decode(name).0).collect(),
+    };
+    let resolved_rules = rules.rules
+        .iter()
+        .map(|rule| Ok(ResolvedRule {
+            column: rule.column.resolve(&names, data_col)?,
+            filter: &rule.filter,
+            conditions: rule.conditions
+                .iter()
+                .map(|condition| Ok((condition.column.resolve(&names, data_col)?, &condition.test)))
+                .collect::<Result<_, CleanDataError>>()?,
+        }))
+        .collect::<Result<Vec<_>, CleanDataError>>()?;
+
+    let mut saved_records: Vec<[String; 2]> = vec![];
+    let mut record = ByteRecord::new();
+    
+    // Using byte records since it is not necessarily utf-8
+    // We want to be flexible over the encoding format of the csv entries, so we'll decode them manually.
+    while rdr.read_byte_record(&mut record)? {
+        if progress.is_cancelled() {
+            return Err(CleanDataError::Cancelled);
+        }
+        progress.set_done(rdr.position().byte() as usize);
+
+        let mut fields: Vec<String> = record
+            .iter()
+            .map(|field| encoding::detect_and_decode(field).0)
+            .collect();
+
+        if data_col >= fields.len() {
+            return Err(CleanDataError::MissingMessage(record.clone()));
+        }
+        if rating_col.is_some_and(|col| col >= fields.len()) {
+            return Err(CleanDataError::MissingRating(record.clone()));
+        }
+        
+        let mut dropped = false;
+        'rules: for (rule, counter) in resolved_rules.iter().zip(filter_counters.iter_mut()) {
+            // Rules only apply to the rows matching all their conditions
+            for (col, test) in &rule.conditions {
+                let value = fields.get(*col).ok_or_else(|| CleanDataError::MissingColumn(*col, record.clone()))?;
+                if !test.accepts(value) {
+                    continue 'rules;
+                }
+            }
+
+            let value = fields.get_mut(rule.column).ok_or_else(|| CleanDataError::MissingColumn(rule.column, record.clone()))?;
+            let mut logs = None;
+            let filtered_result = rule.filter.apply_with_logs(value.as_mut_str(), &mut logs);
+            
+            if let Some(log_msg) = logs {
+                log(log_msg);
+                *counter += 1; 
+            }
+                    
+            match filtered_result {
+                // Entering here means the filters dropped the row.
+                // We should thus move to the next record, and drop this one.
+                // - dropping means not recording it in the output csv.
+                None => {
+                    dropped = true;
+                    break;
+                },
+                Some(Cow::Owned(new_value)) => *value = new_value,
+                Some(Cow::Borrowed(_)) => {},
+            }
+        }
+
+        if !dropped {   // No filter left to apply, and some data is remaining
+            let rating = match rating_col {
+                Some(col) => fields[col].clone(),
+                None => String::from(BLANK_RATING),
+            };
+            saved_records.push([rating, std::mem::take(&mut fields[data_col])]);
+        }
+    }
+    
+    let prev_size = saved_records.len();
+    let uniqued_records = saved_records
+        .iter()
+        .unique_by(|entry| &entry[1])
+        .collect::<Vec<_>>();
+
+    log(format!("Removed {} dupplicates.", prev_size - uniqued_records.len()));
+
+    let mut wtr = Writer::from_path(output_path)?;
+    for record in uniqued_records {
+        wtr.write_record(record)?;
+    }
+
+    let final_logs = rules.rules
+        .iter()
+        .zip(filter_counters)
+        .map(|(rule, count)|{
+            format!("{}: {}\n", rule.filter.name(), count)
+        })
+        .collect::<Vec<String>>()
+        .concat();
+    
+    log(final_logs);
+    
+    let path = fs::canonicalize(PathBuf::from(output_path))?;
+    Ok(path.display().to_string())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleandata::rules_file;
+
+    fn path(file_name: &str) -> String {
+        std::env::temp_dir().join(format!("{}_{file_name}", std::process::id())).to_string_lossy().into_owned()
+    }
+
+    // Sentiment140 has no header : the rules file names its six columns
+    #[test]
+    fn named_columns_without_header() {
+        let (input, output, rules_path) = (path("generic_input.csv"), path("generic_output.csv"), path("generic_rules.toml"));
+        fs::write(
+            &input,
+            "0,1,Mon May 11 03:17:40 UTC 2009,NO_QUERY,alice,first tweet\n\
+             4,2,Mon Jun 01 10:00:00 UTC 2009,NO_QUERY,alice,too late\n\
+             4,3,Tue May 12 10:00:00 UTC 2009,NO_QUERY,spambot,buy now\n\
+             0,4,not a date,NO_QUERY,bob,no date\n\
+             4,5,Wed May 13 10:00:00 UTC 2009,news,bob,#breaking news\n",
+        )
+        .unwrap();
+        fs::write(
+            &rules_path,
+            "columns = [\"polarity\", \"id\", \"date\", \"query\", \"user\", \"text\"]\n\n\
+             [[rules]]\naction = \"delete\"\nname = \"out of may\"\ncolumn = \"date\"\ninside = { from = \"2009-05-01\", to = \"2009-05-31\" }\n\
+             when = [{ column = \"user\", values = [\"nobody\"] }]\n\n\
+             [[rules]]\naction = \"delete\"\nname = \"not may\"\ncolumn = \"date\"\noutside = { from = \"2009-05-01\", to = \"2009-05-31\" }\n\n\
+             [[rules]]\naction = \"delete\"\nname = \"bots\"\ncolumn = \"user\"\nvalues = [\"spambot\"]\n\n\
+             [[rules]]\naction = \"trim\"\nname = \"hashtags\"\npattern = \"#[^ ]* \"\nwhen = [{ column = \"query\", values = [\"news\"] }]\n",
+        )
+        .unwrap();
+
+        let rules = rules_file::load(&rules_path).unwrap();
+        let mut logs = Vec::new();
+        let result = clean_data_generic(&input, &output, 5, Some(0), &rules, &mut |message| logs.push(message), &Progress::default());
+        let written = fs::read_to_string(&output);
+        for file in [&input, &output, &rules_path] {
+            let _ = fs::remove_file(file);
+        }
+
+        result.unwrap();
+        // A value that is not a date matches no period : the row is kept
+        assert_eq!(written.unwrap(), "0,first tweet\n0,no date\n4,news\n");
+        // Deleting a row on its date logs the tweet, not the date
+        assert!(logs.contains(&"not may deleted : too late".to_string()), "{logs:?}");
+        assert!(logs.contains(&"bots deleted : spambot".to_string()), "{logs:?}");
+        assert!(logs.last().unwrap().contains("out of may deletion: 0\nnot may deletion: 1\nbots deletion: 1\nhashtags trim: 1\n"), "{logs:?}");
+    }
+}
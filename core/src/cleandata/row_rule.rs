@@ -0,0 +1,173 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::Regex;
+
+use crate::cleandata::error::CleanDataError;
+use crate::cleandata::rule_filter::RuleFilter;
+
+// Column of the csv, by index or by name (from the csv header or from the rules file)
+#[derive(Debug, Clone)]
+pub enum ColumnRef {
+    Data,           // the column of the tweets, sniffed
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Test {
+    Matches(Regex),
+    // Dates outside of the bounds (or inside if `inside` is false) fail, as well as values that are not dates
+    Period { from: Option<NaiveDateTime>, to: Option<NaiveDateTime>, inside: bool },
+}
+
+#[derive(Debug, Clone)]
+pub struct Condition {
+    pub column: ColumnRef,
+    pub test: Test,
+}
+
+// A filter applied to any column, only on the rows where every condition holds
+#[derive(Debug, Clone)]
+pub struct RowRule {
+    pub column: ColumnRef,
+    pub filter: RuleFilter,
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    pub columns: Option<Vec<String>>,   // names of the columns, replacing those of the csv header
+    pub rules: Vec<RowRule>,
+}
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%a %b %d %H:%M:%S %Y"];
+
+// Accepts ISO dates (with or without time) and the Sentiment140 format `Mon May 11 03:17:40 UTC 2009`.
+// Time zones are ignored. A date without time is the start of the day, or its end if `end_of_day`.
+pub fn parse_date(value: &str, end_of_day: bool) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return if end_of_day { date.and_hms_opt(23, 59, 59) } else { date.and_hms_opt(0, 0, 0) };
+    }
+
+    let mut words: Vec<&str> = value.split_whitespace().collect();
+    if words.len() == 6 {
+        words.remove(4);    // time zone
+    }
+    let value = words.join(" ");
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+}
+
+impl Test {
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            Test::Matches(re) => re.is_match(value),
+            Test::Period { from, to, inside } => match parse_date(value, false) {
+                Some(date) => {
+                    let within = from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to);
+                    within == *inside
+                },
+                None => false,
+            },
+        }
+    }
+}
+
+impl ColumnRef {
+    pub fn resolve(&self, names: &[String], data_col: usize) -> Result<usize, CleanDataError> {
+        match self {
+            ColumnRef::Data => Ok(data_col),
+            ColumnRef::Index(i) if *i < names.len() => Ok(*i),
+            ColumnRef::Index(i) => Err(CleanDataError::UnknownColumn(i.to_string(), names.to_vec())),
+            ColumnRef::Name(name) => names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| CleanDataError::UnknownColumn(name.clone(), names.to_vec())),
+        }
+    }
+}
+
+impl Rules {
+    // Filters applied to the column of the tweets only, as the automatic ones
+    pub fn on_data(filters: Vec<RuleFilter>) -> Self {
+        Rules {
+            columns: None,
+            rules: filters
+                .into_iter()
+                .map(|filter| RowRule { column: ColumnRef::Data, filter, conditions: vec![] })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        let cases = [
+            ("2009-05-11", false, Some("2009-05-11 00:00:00")),
+            ("2009-05-11", true, Some("2009-05-11 23:59:59")),
+            (" 2009-05-11 03:17:40 ", true, Some("2009-05-11 03:17:40")),
+            ("2009-05-11T03:17:40", false, Some("2009-05-11 03:17:40")),
+            ("Mon May 11 03:17:40 UTC 2009", false, Some("2009-05-11 03:17:40")),
+            ("Mon May 11 03:17:40 PDT 2009", false, Some("2009-05-11 03:17:40")),
+            ("2009-13-11", false, None),
+            ("11/05/2009", false, None),
+            ("NO_QUERY", false, None),
+            ("", false, None),
+        ];
+        for (value, end_of_day, expected) in cases {
+            assert_eq!(parse_date(value, end_of_day), expected.map(date), "{value}");
+        }
+    }
+
+    #[test]
+    fn periods() {
+        let may = |inside| Test::Period {
+            from: parse_date("2009-05-01", false),
+            to: parse_date("2009-05-31", true),
+            inside,
+        };
+        let since = Test::Period { from: parse_date("2009-05-01", false), to: None, inside: true };
+        let until = Test::Period { from: None, to: parse_date("2009-05-31", true), inside: true };
+
+        let cases = [
+            (may(true), "2009-05-01", true),
+            (may(true), "Sun May 31 23:59:59 UTC 2009", true),
+            (may(true), "2009-04-30 23:59:59", false),
+            (may(true), "2009-06-01", false),
+            (may(false), "2009-05-15", false),
+            (may(false), "2009-06-01", true),
+            (since, "2010-01-01", true),
+            (until, "2000-01-01", true),
+            // Values that are not dates fail either way
+            (may(true), "not a date", false),
+            (may(false), "not a date", false),
+        ];
+        for (test, value, expected) in cases {
+            assert_eq!(test.accepts(value), expected, "{test:?} {value}");
+        }
+    }
+
+    #[test]
+    fn columns() {
+        let names = ["polarity", "id", "date", "query", "user", "text"].map(String::from);
+        let cases = [
+            (ColumnRef::Data, Some(5)),
+            (ColumnRef::Index(2), Some(2)),
+            (ColumnRef::Index(6), None),
+            (ColumnRef::Name("user".to_string()), Some(4)),
+            (ColumnRef::Name("User".to_string()), None),
+        ];
+        for (column, expected) in cases {
+            assert_eq!(column.resolve(&names, 5).ok(), expected, "{column:?}");
+        }
+    }
+}
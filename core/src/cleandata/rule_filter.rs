@@ -12,7 +12,7 @@ pub enum RuleFilter {
     DELETE(String, Regex),          // delete entry if matching
 }
 
-// Maybe extend the rule filters so they can be applied to any column of the csv.
+// Applied to any column of the csv, under conditions on the other columns, through row_rule::RowRule.
 
 mod external;
 mod tools;
@@ -28,17 +28,17 @@ impl RuleFilter {
                 }
 
                 *logs = Some(log_msg.to_string() + " deleted : " + entry);
-                return None;
+                None
             },
             RuleFilter::REPLACE(log_msg, re, rep) => {
                 let replaced = re.replace_all(entry, rep);
                 if let Cow::Owned(ref passed) = replaced {*logs = Some(log_msg.to_string() + " replaced - remains : " + passed)};
-                return Some(replaced);
+                Some(replaced)
             },
             RuleFilter::TRIM(log_msg, re) => {
                 let replaced = re.replace_all(entry, "");
                 if let Cow::Owned(ref passed) = replaced {*logs = Some(log_msg.to_string() + " trimed - remains : " + passed)};
-                return Some(replaced);
+                Some(replaced)
             }
         }
     }
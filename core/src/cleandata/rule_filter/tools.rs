@@ -12,7 +12,7 @@ impl RuleFilter {
     }
     // Such as if there are multiple rules in a list with same pattern, delete is prioritary (since more restrictive)
     // It is a pure design choice though, not really necessary..
-    pub(super) fn rank(&self) -> u8 {
+    pub fn rank(&self) -> u8 {
         match self {
             RuleFilter::DELETE(_, _) => 0,
             RuleFilter::REPLACE(_, _, _) => 1,
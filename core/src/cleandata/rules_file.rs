@@ -0,0 +1,419 @@
+use std::fs;
+use std::path::Path;
+
+use itertools::Itertools;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::cleandata::error::CleanDataError;
+use crate::cleandata::row_rule::{self, ColumnRef, Condition, RowRule, Rules, Test};
+use crate::cleandata::rule_filter::RuleFilter;
+use crate::regex_ext::builder::RegexLogicalBuilder;
+
+// Rules file, TOML or JSON depending on the extension. Rules are applied in the order of the file :
+//
+// [[rules]]
+// action = "delete"                  # trim | replace | delete
+// name = "retweet"
+// expression = { whole_word = { text = "RT" } }
+//
+// [[rules]]
+// action = "replace"
+// name = "ampersand"
+// pattern = "&amp;"                  # raw regex, instead of an expression
+// replacement = "&"
+//
+// Rules apply to the column of the tweets unless they name another `column` (header name or index),
+// and only to the rows meeting all their `when` conditions. Besides `pattern` and `expression`,
+// `values` / `values_file` (one per line) match whole values, `inside` / `outside` compare dates :
+//
+// columns = ["polarity", "id", "date", "query", "user", "text"]   # names for files without header
+//
+// [[rules]]
+// action = "delete"
+// name = "bots"
+// column = "user"
+// values_file = "bots.txt"           # relative to the rules file
+//
+// [[rules]]
+// action = "delete"
+// name = "out of period"
+// column = "date"
+// outside = { from = "2009-05-11", to = "2009-06-01" }
+//
+// [[rules]]
+// action = "trim"
+// name = "hashtags of the news"
+// pattern = "#[^ ]*"
+// when = [{ column = "query", values = ["news"] }]
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    columns: Option<Vec<String>>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    action: Action,
+    name: String,
+    column: Option<ColumnSpec>,
+    pattern: Option<String>,
+    expression: Option<Expression>,
+    values: Option<Vec<String>>,
+    values_file: Option<String>,
+    inside: Option<Period>,
+    outside: Option<Period>,
+    replacement: Option<String>,
+    #[serde(default)]
+    when: Vec<RuleCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleCondition {
+    column: Option<ColumnSpec>,
+    pattern: Option<String>,
+    expression: Option<Expression>,
+    values: Option<Vec<String>>,
+    values_file: Option<String>,
+    inside: Option<Period>,
+    outside: Option<Period>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ColumnSpec {
+    Index(usize),
+    Name(String),
+}
+
+// Dates as `2009-05-11`, `2009-05-11 03:17:40` or `Mon May 11 03:17:40 UTC 2009`, bounds included
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Period {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+// What a rule or a condition looks for in its column, one of the fields at most
+struct Matcher<'a> {
+    pattern: &'a Option<String>,
+    expression: &'a Option<Expression>,
+    values: &'a Option<Vec<String>>,
+    values_file: &'a Option<String>,
+    inside: &'a Option<Period>,
+    outside: &'a Option<Period>,
+}
+
+const MATCHERS: &str = "`pattern`, `expression`, `values`, `values_file`, `inside` or `outside`";
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    Trim,
+    Replace,
+    Delete,
+}
+
+/// Regex written with the RegexLogicalBuilder operations, one variant per operation.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expression {
+    Regex(String),                  // raw regex fragment
+    Text(String),                   // literal text, special characters are escaped
+    NonSpace,                       // [^ ]
+    Anything,                       // .*
+    Sequence(Vec<Expression>),      // one after the other
+    AnyOf(Vec<Expression>),
+    AllOf(Vec<Expression>),         // all of them, in any order
+    Contains(Box<Expression>),
+    Group(Box<Expression>),
+    OneOrMore(Box<Expression>),
+    AnyTimes(Box<Expression>),
+    WholeWord(Box<Expression>),
+    WordEnd(Box<Expression>),
+}
+
+impl Expression {
+    pub fn to_builder(&self) -> Result<RegexLogicalBuilder, String> {
+        Ok(match self {
+            Expression::Regex(re) => RegexLogicalBuilder::from(re.as_str()),
+            Expression::Text(text) => RegexLogicalBuilder::from(RegexLogicalBuilder::protect_string(text)),
+            Expression::NonSpace => RegexLogicalBuilder::new().plus_non_space(),
+            Expression::Anything => RegexLogicalBuilder::new().plus_anything(),
+            Expression::Sequence(exprs) => exprs
+                .iter()
+                .try_fold(RegexLogicalBuilder::new(), |acc, expr| Ok::<_, String>(acc.plus(expr.to_builder()?)))?,
+            Expression::AnyOf(exprs) => {
+                if exprs.is_empty() {
+                    return Err("`any_of` needs at least one expression".to_string());
+                }
+                RegexLogicalBuilder::new().any_of(exprs.iter().map(Expression::to_builder).collect::<Result<_, _>>()?)
+            },
+            Expression::AllOf(exprs) => {
+                let mut builders = exprs.iter().map(Expression::to_builder);
+                let first = builders.next().ok_or("`all_of` needs at least one expression")??;
+                builders.try_fold(first, |acc, builder| Ok::<_, String>(acc.and(builder?)))?
+            },
+            Expression::Contains(expr) => RegexLogicalBuilder::new().contains(expr.to_builder()?),
+            Expression::Group(expr) => expr.to_builder()?.group(),
+            Expression::OneOrMore(expr) => expr.to_builder()?.one_or_more(),
+            Expression::AnyTimes(expr) => expr.to_builder()?.any_times(),
+            Expression::WholeWord(expr) => expr.to_builder()?.as_whole_word(),
+            Expression::WordEnd(expr) => expr.to_builder()?.as_word_end(),
+        })
+    }
+}
+
+impl Matcher<'_> {
+    // `base_dir` is the directory of the rules file, for `values_file`
+    fn test(&self, base_dir: &Path) -> Result<Option<Test>, String> {
+        let given = [
+            self.pattern.is_some(),
+            self.expression.is_some(),
+            self.values.is_some(),
+            self.values_file.is_some(),
+            self.inside.is_some(),
+            self.outside.is_some(),
+        ];
+        if given.iter().filter(|g| **g).count() > 1 {
+            return Err(format!("only one of {MATCHERS} is allowed"));
+        }
+
+        if let Some(pattern) = self.pattern {
+            return Regex::new(pattern).map(|re| Some(Test::Matches(re))).map_err(|e| format!("invalid pattern - {e}"));
+        }
+        if let Some(expression) = self.expression {
+            return expression
+                .to_builder()?
+                .build()
+                .map(|re| Some(Test::Matches(re)))
+                .map_err(|e| format!("the expression gives an invalid regex - {e}"));
+        }
+        if let Some(values) = self.values {
+            return values_regex(values).map(|re| Some(Test::Matches(re)));
+        }
+        if let Some(values_file) = self.values_file {
+            let content = fs::read_to_string(base_dir.join(values_file))
+                .map_err(|e| format!("cannot read `{values_file}` - {e}"))?;
+            let values: Vec<String> = content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect();
+            return values_regex(&values).map(|re| Some(Test::Matches(re)));
+        }
+        if let Some(period) = self.inside {
+            return period.to_test(true).map(Some);
+        }
+        if let Some(period) = self.outside {
+            return period.to_test(false).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+// Whole values, compared literally
+fn values_regex(values: &[String]) -> Result<Regex, String> {
+    if values.is_empty() {
+        return Err("the list of values is empty".to_string());
+    }
+    let alternatives = values.iter().map(|value| regex::escape(value)).join("|");
+    Regex::new(&format!("^(?:{alternatives})$")).map_err(|e| e.to_string())
+}
+
+impl Period {
+    fn to_test(&self, inside: bool) -> Result<Test, String> {
+        if self.from.is_none() && self.to.is_none() {
+            return Err("a period needs `from` or `to`".to_string());
+        }
+        let bound = |date: &Option<String>, end_of_day: bool| {
+            date.as_ref()
+                .map(|date| row_rule::parse_date(date, end_of_day).ok_or(format!("`{date}` is not a date")))
+                .transpose()
+        };
+        Ok(Test::Period { from: bound(&self.from, false)?, to: bound(&self.to, true)?, inside })
+    }
+}
+
+fn column_ref(column: &Option<ColumnSpec>) -> ColumnRef {
+    match column {
+        None => ColumnRef::Data,
+        Some(ColumnSpec::Index(i)) => ColumnRef::Index(*i),
+        Some(ColumnSpec::Name(name)) => ColumnRef::Name(name.clone()),
+    }
+}
+
+impl RuleCondition {
+    fn to_condition(&self, base_dir: &Path) -> Result<Condition, String> {
+        let matcher = Matcher {
+            pattern: &self.pattern,
+            expression: &self.expression,
+            values: &self.values,
+            values_file: &self.values_file,
+            inside: &self.inside,
+            outside: &self.outside,
+        };
+        let test = matcher.test(base_dir)?.ok_or(format!("one of {MATCHERS} is required"))?;
+        Ok(Condition { column: column_ref(&self.column), test })
+    }
+}
+
+impl Rule {
+    fn to_row_rule(&self, base_dir: &Path) -> Result<RowRule, String> {
+        if self.name.trim().is_empty() {
+            return Err("the name is empty".to_string());
+        }
+
+        let column = column_ref(&self.column);
+        let mut conditions = self.when
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| condition.to_condition(base_dir).map_err(|e| format!("condition #{} - {e}", i + 1)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let matcher = Matcher {
+            pattern: &self.pattern,
+            expression: &self.expression,
+            values: &self.values,
+            values_file: &self.values_file,
+            inside: &self.inside,
+            outside: &self.outside,
+        };
+        let is_delete = matches!(self.action, Action::Delete);
+        // A delete rule may only rely on dates or on its conditions : it then matches any value of the column
+        // of the tweets, so that its log shows the deleted tweet as the other delete rules do
+        let (column, re) = match matcher.test(base_dir)? {
+            Some(Test::Matches(re)) => (column, re),
+            Some(period) if is_delete => {
+                conditions.insert(0, Condition { column, test: period });
+                (ColumnRef::Data, Regex::new("").map_err(|e| e.to_string())?)
+            },
+            Some(_) => return Err("`inside` and `outside` only apply to delete rules".to_string()),
+            None if is_delete && !conditions.is_empty() => (ColumnRef::Data, Regex::new("").map_err(|e| e.to_string())?),
+            None => return Err(format!("one of {MATCHERS} is required")),
+        };
+
+        let name = self.name.clone();
+        let filter = match (self.action, &self.replacement) {
+            (Action::Replace, Some(replacement)) => RuleFilter::REPLACE(name, re, replacement.clone()),
+            (Action::Replace, None) => return Err("a replace rule needs a `replacement`".to_string()),
+            (_, Some(_)) => return Err("only replace rules take a `replacement`".to_string()),
+            (Action::Trim, None) => RuleFilter::TRIM(name, re),
+            (Action::Delete, None) => RuleFilter::DELETE(name, re),
+        };
+        Ok(RowRule { column, filter, conditions })
+    }
+}
+
+/// Parses a rules file into the ordered rules used by clean_data_generic.
+pub fn load(path: &str) -> Result<Rules, CleanDataError> {
+    let content = fs::read_to_string(path)?;
+    let format_error = |message: String| CleanDataError::RulesFormat(path.to_string(), message);
+
+    let file: RulesFile = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&content).map_err(|e| format_error(e.to_string()))?,
+        Some("json") => serde_json::from_str(&content).map_err(|e| format_error(e.to_string()))?,
+        _ => return Err(format_error("expected a .toml or .json file".to_string())),
+    };
+
+    let base_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let rules = file.rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            rule.to_row_rule(base_dir).map_err(|reason| CleanDataError::InvalidRule { index: i + 1, name: rule.name.clone(), reason })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Rules { columns: file.columns, rules })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Writes the rules in the temporary directory, the extension choosing the format
+    fn load_str(file_name: &str, content: &str) -> Result<Rules, CleanDataError> {
+        let path = std::env::temp_dir().join(format!("{}_{file_name}", std::process::id()));
+        fs::write(&path, content).unwrap();
+        let result = load(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let cases = [
+            (
+                "multiple_matchers.toml",
+                "[[rules]]\naction = \"delete\"\nname = \"rt\"\npattern = \"RT\"\nvalues = [\"RT\"]\n",
+                format!("Rule #1 (`rt`) is invalid - only one of {MATCHERS} is allowed"),
+            ),
+            (
+                "no_replacement.toml",
+                "[[rules]]\naction = \"trim\"\nname = \"ok\"\npattern = \"a\"\n\n[[rules]]\naction = \"replace\"\nname = \"amp\"\npattern = \"&amp;\"\n",
+                "Rule #2 (`amp`) is invalid - a replace rule needs a `replacement`".to_string(),
+            ),
+            (
+                "inside_trim.toml",
+                "[[rules]]\naction = \"trim\"\nname = \"may\"\ninside = { from = \"2009-05-01\" }\n",
+                "Rule #1 (`may`) is invalid - `inside` and `outside` only apply to delete rules".to_string(),
+            ),
+            (
+                "empty_any_of.json",
+                r#"{"rules": [{"action": "delete", "name": "empty", "expression": {"any_of": []}}]}"#,
+                "Rule #1 (`empty`) is invalid - `any_of` needs at least one expression".to_string(),
+            ),
+            (
+                "empty_all_of.json",
+                r#"{"rules": [{"action": "trim", "name": "empty", "expression": {"whole_word": {"all_of": []}}}]}"#,
+                "Rule #1 (`empty`) is invalid - `all_of` needs at least one expression".to_string(),
+            ),
+            (
+                "no_matcher.toml",
+                "[[rules]]\naction = \"delete\"\nname = \"nothing\"\n",
+                format!("Rule #1 (`nothing`) is invalid - one of {MATCHERS} is required"),
+            ),
+        ];
+        for (file_name, content, expected) in cases {
+            match load_str(file_name, content) {
+                Err(e @ CleanDataError::InvalidRule { .. }) => assert_eq!(e.to_string(), expected, "{file_name}"),
+                other => panic!("{file_name}: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let cases = [
+            ("unknown_rule_field.toml", "[[rules]]\naction = \"delete\"\nname = \"rt\"\npatern = \"RT\"\n", "patern"),
+            ("unknown_top_field.json", r#"{"rules": [], "colums": ["text"]}"#, "colums"),
+            ("unknown_period_field.toml", "[[rules]]\naction = \"delete\"\nname = \"may\"\ninside = { since = \"2009-05-01\" }\n", "since"),
+        ];
+        for (file_name, content, field) in cases {
+            match load_str(file_name, content) {
+                Err(e @ CleanDataError::RulesFormat(..)) => {
+                    let message = e.to_string();
+                    assert!(message.contains(&format!("unknown field `{field}`")), "{file_name}: {message}");
+                },
+                other => panic!("{file_name}: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn valid_rules() {
+        let rules = load_str(
+            "valid.toml",
+            "columns = [\"date\", \"text\"]\n\n[[rules]]\naction = \"replace\"\nname = \"amp\"\npattern = \"&amp;\"\nreplacement = \"&\"\n\n\
+             [[rules]]\naction = \"delete\"\nname = \"may\"\ncolumn = \"date\"\noutside = { from = \"2009-05-01\", to = \"2009-05-31\" }\n",
+        )
+        .unwrap();
+        assert_eq!(rules.columns, Some(vec!["date".to_string(), "text".to_string()]));
+        assert_eq!(rules.rules.len(), 2);
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+use rayon::prelude::*;
+
+use kodama::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::parallel;
+use crate::progress::Progress;
+use crate::validation;
+use cache::{CleModele, ModeleClustering};
+use condensed::MatriceCondensee;
+use cut::{Coupe, ResumeCluster};
+use metrics::Metriques;
+use partition::Partition;
+
+pub mod cache;
+pub mod condensed;
+pub mod cut;
+pub mod dendrogram_svg;
+pub mod metrics;
+pub mod partition;
+pub mod propagation;
+pub mod selection;
+pub mod summary;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tweet {
+    id: usize,
+    pub contenu: String,
+    mots: HashSet<String>,
+    pub label: i32, // 4=positif, 2=neutre, 0=négatif
+}
+
+impl Tweet {
+    fn new(id: usize, contenu: String, label: i32) -> Self {
+        Self { id, mots: tokeniser_tweet(&contenu), contenu, label }
+    }
+
+    // Tweets d'un fichier chargé, numérotés dans l'ordre du fichier
+    fn numeroter(donnees: Vec<validation::TweetEtiquete>) -> Vec<Tweet> {
+        donnees.into_iter().enumerate().map(|(id, t)| Tweet::new(id, t.contenu, t.etiquette)).collect()
+    }
+}
+
+// Mots-clés par cluster dans les synthèses dessinées par l'évaluation
+const NB_MOTS_CLES: usize = 8;
+
+// Matrice de confusion (Réel vs Estimé), indices 0=négatif, 1=neutre, 2=positif
+pub type Matrice = [[i32; 3]; 3];
+
+/// Modèle de la dernière classification, réutilisé tant que le fichier et les paramètres ne changent pas
+#[derive(Default)]
+pub struct CacheClustering {
+    modele: Option<ModeleClustering>,
+}
+
+impl CacheClustering {
+    pub fn predict_tweet_class(&mut self, path: &str, input_tweet: &str, k: usize, method: usize) -> Result<i32, Box<dyn Error>> {
+        Ok(self.modele(path, Some(k), method)?.predire(input_tweet))
+    }
+
+    // Coupe du modèle (en cache si possible) selon le critère de clustering_clusters :
+    // groupe de chaque tweet renuméroté par taille décroissante et résumé de chaque cluster
+    pub fn decouper(&mut self, path: &str, method: i64, criterion: i64, value: f64, depth: i64) -> Result<(Vec<usize>, Vec<ResumeCluster>), Box<dyn Error>> {
+        let coupe = Coupe::depuis_critere(criterion, value, depth)?;
+        let method = method as usize;
+        let k = match coupe {
+            Coupe::NbClusters(k) => Some(k),
+            _ if partition::est_partition(method) => return Err("Coupe par seuil réservée aux méthodes hiérarchiques".into()),
+            _ => None,
+        };
+        let modele = self.modele(path, k, method)?;
+        let groupes = modele.groupes(coupe)?;
+        Ok(cut::resumer(&groupes, modele.tweets()))
+    }
+
+    // Modèle en cache s'il correspond au fichier et aux paramètres, reconstruit sinon.
+    // Sans K imposé n'importe quel modèle du même fichier et de la même méthode convient.
+    fn modele(&mut self, path: &str, k: Option<usize>, method: usize) -> Result<&mut ModeleClustering, Box<dyn Error>> {
+        let cle = CleModele::new(path, method)?;
+
+        // Même fichier et même méthode : seul un changement de K peut demander de refaire la coupe
+        let reutilisable = match (&mut self.modele, k) {
+            (Some(modele), Some(k)) if *modele.cle() == cle => modele.k() == k || modele.recouper(k),
+            (Some(modele), None) => *modele.cle() == cle,
+            _ => false,
+        };
+        if !reutilisable {
+            self.modele = Some(ModeleClustering::construire(cle, k.unwrap_or(1))?);
+        }
+
+        self.modele.as_mut().ok_or_else(|| "Aucun modèle".into())
+    }
+
+    /// Modèle en mémoire, s'il y en a un
+    pub fn modele_courant(&self) -> Option<&ModeleClustering> {
+        self.modele.as_ref()
+    }
+
+    /// Remplace le modèle en mémoire par un modèle enregistré
+    pub fn charger(&mut self, chemin: &str) -> Result<(), Box<dyn Error>> {
+        self.modele = Some(ModeleClustering::charger(chemin)?);
+        Ok(())
+    }
+
+    pub fn vider(&mut self) {
+        self.modele = None;
+    }
+}
+
+// Résultat de run_evaluation_pipeline : le dessin, la matrice de confusion (formatée et brute) et la qualité du découpage
+pub struct ResultatEvaluation {
+    pub svg: String,
+    pub matrice: String,
+    pub confusion: Matrice,
+    pub metriques: Metriques,
+}
+
+// Méthodes 0 à 2 : linkage hiérarchique, 3 : k-means (TF-IDF), 4 : k-médoïdes CLARA (Jaccard)
+pub fn run_evaluation_pipeline(csv_path: &str, k: usize, method: usize, graine: i64, progress: &Progress) -> Result<ResultatEvaluation, Box<dyn Error>> {
+    // Chargement des données
+    let tweets = charger_tweets_annotes(csv_path)?;
+    let n = tweets.len();
+    if k == 0 { return Err("K doit valoir au moins 1".into()); }
+    if n == 0 { return Err("Aucun tweet trouvé.".into()); }
+    if n < k { return Err(format!("Pas assez de tweets ({}) pour K={}", n, k).into()); }
+
+    // Division du dataset : 2/3 entraînement, 1/3 test
+    let (mut train, test) = validation::diviser_donnees_stratifiee(&tweets, |t| t.label, 2.0 / 3.0, graine);
+
+    let n_train = train.len();
+    if n_train < k { return Err(format!("Pas assez de tweets d'entrainement ({}) pour K={}", n_train, k).into()); }
+
+    // Ré-indexer les ID du train pour correspondre à leur position dans le vecteur (pour Kodama)
+    for (idx, t) in train.iter_mut().enumerate() {
+        t.id = idx;
+    }
+
+    let (svg, matrice, groupes) = if partition::est_partition(method) {
+        evaluer_partition(&train, &test, k, method, graine, progress)?
+    } else {
+        evaluer_hierarchique(&train, &test, k, method, progress)?
+    };
+
+    // Qualité du découpage de l'entraînement, indépendante du vote
+    let metriques = metrics::calculer(&train, &groupes, graine);
+
+    Ok(ResultatEvaluation { svg, matrice: validation::format_matrice_confusion(&matrice), confusion: matrice, metriques })
+}
+
+// La progression compte les étapes du partitionnement puis les tweets de test.
+// Renvoie aussi le groupe de chaque tweet d'entraînement.
+fn evaluer_partition(train: &[Tweet], test: &[Tweet], k: usize, method: usize, graine: i64, progress: &Progress) -> Result<(String, Matrice, Vec<usize>), Box<dyn Error>> {
+    progress.set_total(partition::nb_etapes(method) + test.len());
+    let (modele, assignations) = Partition::entrainer(method, train, k, graine, progress)?;
+    if progress.is_cancelled() { return Err("Évaluation annulée".into()); }
+
+    // Pas de dendrogramme pour un partitionnement : on dessine la synthèse des clusters
+    let (groupes, resumes) = cut::resumer(&assignations, train);
+    let syntheses = summary::synthetiser(train, &groupes, resumes.len(), NB_MOTS_CLES);
+    let svg = summary::generate_summary_svg(&resumes, &syntheses, train);
+    let cluster_labels = partition::etiquettes_partition(&assignations, train, modele.nb_clusters());
+
+    // Chaque tweet de test prend l'étiquette du groupe dont le centre est le plus proche
+    let estimes: Vec<i32> = parallel::executer(|| {
+        test.par_iter()
+            .map(|t_test| {
+                let label = cluster_labels[modele.assigner(t_test)];
+                progress.advance(1);
+                label
+            })
+            .collect()
+    });
+
+    let mut matrice = [[0; 3]; 3];
+    for (t_test, label_estime) in test.iter().zip(estimes) {
+        validation::ajouter_prediction(&mut matrice, t_test.label, label_estime);
+    }
+    Ok((svg, matrice, assignations))
+}
+
+// La progression compte les lignes de la matrice de distance puis les tweets de test.
+// Renvoie aussi le groupe de chaque tweet d'entraînement.
+fn evaluer_hierarchique(train: &[Tweet], test: &[Tweet], k: usize, method: usize, progress: &Progress) -> Result<(String, Matrice, Vec<usize>), Box<dyn Error>> {
+    let n_train = train.len();
+
+    // Calcul de la matrice de distance en comparant chaque tweet avec les autres
+    progress.set_total(n_train - 1 + test.len());
+    let condensed = MatriceCondensee::calculer(train, progress)?;
+    if progress.is_cancelled() { return Err("Évaluation annulée".into()); }
+
+    // Clustering hiérarchique
+    let dendrogram = condensed.linkage(n_train, methode_linkage(method));
+
+    // Génération du SVG
+    let svg = dendrogram_svg::generate_dendrogram_svg(&dendrogram, train, k, dendrogram_svg::MAX_FEUILLES);
+
+    // Découpage de l'arbre pour obtenir K clusters avec UnionFind pour fusionner les groupes jusqu'à avoir K groupes
+    let mut uf = couper_arbre(&dendrogram, n_train, k);
+
+    // Vote majoritaire et Matrice de confusion en regardant quel est le sentiment dominant dans chaque cluster formé.
+    let cluster_labels = etiquettes_clusters(&mut uf, train);
+
+    // Construction de la matrice de confusion (Réel vs Estimé)
+    let mut matrice = [[0; 3]; 3];
+
+    // Trouver le voisin le plus proche dans le train de chaque tweet de test
+    let voisins: Vec<usize> = parallel::executer(|| {
+        test.par_iter()
+            .map(|t_test| {
+                let voisin = plus_proche_voisin(t_test, train);
+                progress.advance(1);
+                voisin
+            })
+            .collect()
+    });
+
+    for (t_test, best_neighbor_idx) in test.iter().zip(voisins) {
+        // Trouver le cluster du voisin
+        let root = uf.find(best_neighbor_idx);
+        // Prédire le label du cluster
+        let label_estime = *cluster_labels.get(&root).unwrap_or(&2);
+
+        // Mettre à jour la matrice
+        validation::ajouter_prediction(&mut matrice, t_test.label, label_estime);
+    }
+
+    let groupes = train.iter().map(|t| uf.find(t.id)).collect();
+    Ok((svg, matrice, groupes))
+}
+
+fn methode_linkage(method: usize) -> Method {
+    match method { 0 => Method::Average, 1 => Method::Complete, 2 => Method::Ward, _ => Method::Average }
+}
+
+// Applique les n - k premières fusions du dendrogramme pour obtenir k groupes
+fn couper_arbre(dendrogram: &kodama::Dendrogram<f64>, n: usize, k: usize) -> UnionFind {
+    cut::couper(dendrogram, n, Coupe::NbClusters(k))
+}
+
+// On assigne à chaque cluster l'étiquette majoritaire de ses tweets
+fn etiquettes_clusters(uf: &mut UnionFind, tweets: &[Tweet]) -> HashMap<usize, i32> {
+    let mut cluster_votes: HashMap<usize, HashMap<i32, usize>> = HashMap::new();
+    for tweet in tweets {
+        let root = uf.find(tweet.id);
+        *cluster_votes.entry(root).or_default().entry(tweet.label).or_default() += 1;
+    }
+
+    let mut cluster_labels: HashMap<usize, i32> = HashMap::new();
+    for (root, votes) in cluster_votes {
+        // Égalité : la plus petite étiquette, comme cut::resumer (l'ordre d'un HashMap varie d'une exécution à l'autre)
+        let best_label = votes
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(l, _)| l)
+            .unwrap_or(2);
+        cluster_labels.insert(root, best_label);
+    }
+    cluster_labels
+}
+
+// Renvoie l'id du tweet de la base le plus proche de `tweet`
+fn plus_proche_voisin(tweet: &Tweet, base: &[Tweet]) -> usize {
+    let mut best_dist = f64::MAX;
+    let mut best_neighbor_idx = 0;
+
+    for candidat in base {
+        let d = distance(tweet, candidat);
+        if d < best_dist {
+            best_dist = d;
+            best_neighbor_idx = candidat.id;
+        }
+    }
+    best_neighbor_idx
+}
+
+// Structure pour gérer les ensembles disjoints pour couper l'arbre
+#[derive(Serialize, Deserialize)]
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+
+    fn new(size: usize) -> Self {
+        Self { parent: (0..size + 1).collect() }
+    }
+    
+    // Trouve la racine d'un élément
+    fn find(&mut self, i: usize) -> usize {
+        if i >= self.parent.len() {
+            return i; 
+        }
+        if self.parent[i] == i {
+            i
+        } else {
+            let root = self.find(self.parent[i]);
+            self.parent[i] = root;
+            root
+        }
+    }
+
+    // Fusionne deux ensembles
+    fn union(&mut self, i: usize, j: usize) {
+        if i >= self.parent.len() || j >= self.parent.len() { return; }
+        let root_i = self.find(i);
+        let root_j = self.find(j);
+        if root_i != root_j {
+            self.parent[root_i] = root_j;
+        }
+    }
+}
+
+/// Probabilités de chaque classe (ordre de validation::CLASSES) pour chaque texte : répartition des étiquettes
+/// du cluster où il tombe, les paires (texte, étiquette) de `entrainement` étant découpées en k clusters.
+/// Utilisé par l'ensemble de classifieurs.
+pub fn probabilites(entrainement: &[(&str, i32)], textes: &[&str], k: usize, method: usize) -> Result<Vec<[f64; 3]>, String> {
+    let tweets: Vec<Tweet> = entrainement
+        .iter()
+        .enumerate()
+        .map(|(id, &(contenu, label))| Tweet::new(id, contenu.to_string(), label))
+        .collect();
+    cache::repartitions(&tweets, k, method, textes).map_err(|e| e.to_string())
+}
+
+pub fn charger_tweets_annotes(chemin: &str) -> Result<Vec<Tweet>, Box<dyn Error>> {
+    Ok(Tweet::numeroter(validation::charger_donnees(chemin)?))
+}
+
+fn tokeniser_tweet(t: &str) -> HashSet<String> {
+    t.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_ascii_lowercase())
+        .collect()
+}
+
+fn distance(t1: &Tweet, t2: &Tweet) -> f64 {
+    let len1 = t1.mots.len() as f64;
+    let len2 = t2.mots.len() as f64;
+    let common = t1.mots.intersection(&t2.mots).count() as f64;
+    let union = len1 + len2 - common;
+    if union == 0.0 { 1.0 } else { (union - common) / union }
+}
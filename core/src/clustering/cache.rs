@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::time::UNIX_EPOCH;
+
+use kodama::Dendrogram;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::clustering::condensed::MatriceCondensee;
+use crate::clustering::cut::{self, Coupe};
+use crate::clustering::partition::{self, Partition};
+use crate::clustering::{
+    Tweet, UnionFind, charger_tweets_annotes, couper_arbre, etiquettes_clusters, methode_linkage,
+    plus_proche_voisin,
+};
+use crate::progress::Progress;
+use crate::{parallel, validation};
+
+// Identifie le jeu de données et la méthode d'un modèle : un fichier modifié (taille ou date) l'invalide
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CleModele {
+    chemin: String,
+    taille: u64,
+    modification_ms: u64,
+    method: usize,
+}
+
+impl CleModele {
+    pub fn new(chemin: &str, method: usize) -> Result<Self, Box<dyn Error>> {
+        let metadata = fs::metadata(chemin)?;
+        let modification_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|date| date.duration_since(UNIX_EPOCH).ok())
+            .map(|duree| duree.as_millis() as u64)
+            .unwrap_or(0);
+        Ok(Self { chemin: chemin.to_string(), taille: metadata.len(), modification_ms, method })
+    }
+}
+
+/// Modèle de clustering gardé par le noeud `Clustering` entre deux classifications
+/// et qui peut être enregistré sur disque (JSON).
+#[derive(Serialize, Deserialize)]
+pub struct ModeleClustering {
+    cle: CleModele,
+    k: usize,
+    tweets: Vec<Tweet>,
+    decoupage: Decoupage,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Decoupage {
+    // Le dendrogramme ne dépend pas de K : changer K ne refait que la coupe
+    Hierarchique {
+        #[serde(with = "etapes")]
+        dendrogram: Dendrogram<f64>,
+        uf: UnionFind,
+        cluster_labels: HashMap<usize, i32>,
+    },
+    Partition {
+        modele: Partition,
+        cluster_labels: Vec<i32>,
+    },
+}
+
+impl ModeleClustering {
+    pub fn construire(cle: CleModele, k: usize) -> Result<Self, Box<dyn Error>> {
+        let tweets = charger_tweets_annotes(&cle.chemin)?;
+        let decoupage = Decoupage::construire(&tweets, k, cle.method)?;
+        Ok(Self { cle, k, tweets, decoupage })
+    }
+
+    pub fn cle(&self) -> &CleModele {
+        &self.cle
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    // Adapte le modèle à un nouveau K sans tout recalculer, false si ce n'est pas possible (partitionnement)
+    pub fn recouper(&mut self, k: usize) -> bool {
+        let n = self.tweets.len();
+        match &mut self.decoupage {
+            Decoupage::Hierarchique { dendrogram, uf, cluster_labels } if k <= n => {
+                *uf = couper_arbre(dendrogram, n, k);
+                *cluster_labels = etiquettes_clusters(uf, &self.tweets);
+                self.k = k;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    pub fn tweets(&self) -> &[Tweet] {
+        &self.tweets
+    }
+
+    /// Groupe (identifiant brut) de chaque tweet du jeu de données selon le critère de coupe.
+    /// Un partitionnement n'a pas de dendrogramme : seul son propre K est accepté.
+    pub fn groupes(&self, coupe: Coupe) -> Result<Vec<usize>, String> {
+        match &self.decoupage {
+            Decoupage::Hierarchique { dendrogram, .. } => {
+                let mut uf = cut::couper(dendrogram, self.tweets.len(), coupe);
+                Ok(self.tweets.iter().map(|t| uf.find(t.id)).collect())
+            },
+            Decoupage::Partition { modele, .. } => match coupe {
+                Coupe::NbClusters(k) if k == self.k => Ok(self.tweets.iter().map(|t| modele.assigner(t)).collect()),
+                _ => Err("Le partitionnement ne se coupe qu'au K avec lequel il a été construit".to_string()),
+            },
+        }
+    }
+
+    pub fn predire(&mut self, texte: &str) -> i32 {
+        self.situer(texte).1
+    }
+
+    /// Cluster (identifiant brut) du texte et son étiquette. Pour une méthode hiérarchique,
+    /// indique aussi le tweet le plus proche, dont le texte prend le cluster.
+    pub fn situer(&mut self, texte: &str) -> (usize, i32, Option<usize>) {
+        let tweet = Tweet::new(0, texte.to_string(), validation::NON_ANNOTE);
+        match &mut self.decoupage {
+            Decoupage::Hierarchique { uf, cluster_labels, .. } => {
+                // On retourne le label du cluster auquel appartient le voisin le plus proche
+                let voisin = plus_proche_voisin(&tweet, &self.tweets);
+                let neighbor_cluster = uf.find(voisin);
+                (neighbor_cluster, *cluster_labels.get(&neighbor_cluster).unwrap_or(&2), Some(voisin))
+            },
+            Decoupage::Partition { modele, cluster_labels } => {
+                let cluster = modele.assigner(&tweet);
+                (cluster, cluster_labels[cluster], None)
+            },
+        }
+    }
+
+    pub fn enregistrer(&self, chemin: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(BufWriter::new(File::create(chemin)?), self)?;
+        Ok(())
+    }
+
+    pub fn charger(chemin: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(chemin)?))?)
+    }
+}
+
+impl Decoupage {
+    // Les identifiants des tweets doivent être leurs indices
+    fn construire(tweets: &[Tweet], k: usize, method: usize) -> Result<Self, Box<dyn Error>> {
+        let n = tweets.len();
+        if k == 0 { return Err("K doit valoir au moins 1".into()); }
+        if n < k { return Err("K est trop grand".into()); }
+
+        if partition::est_partition(method) {
+            // Graine fixe : deux constructions sur les mêmes données donnent les mêmes groupes
+            let (modele, assignations) = Partition::entrainer(method, tweets, k, 0, &Progress::default())?;
+            let cluster_labels = partition::etiquettes_partition(&assignations, tweets, modele.nb_clusters());
+            Ok(Decoupage::Partition { modele, cluster_labels })
+        } else {
+            let condensed = MatriceCondensee::calculer(tweets, &Progress::default())?;
+            let dendrogram = condensed.linkage(n, methode_linkage(method));
+            let mut uf = couper_arbre(&dendrogram, n, k);
+            let cluster_labels = etiquettes_clusters(&mut uf, tweets);
+            Ok(Decoupage::Hierarchique { dendrogram, uf, cluster_labels })
+        }
+    }
+}
+
+/// Découpe `tweets` en k groupes puis donne à chaque texte la répartition des étiquettes de son groupe
+/// (ordre de validation::CLASSES) : celui du plus proche voisin pour une méthode hiérarchique,
+/// du centre le plus proche pour un partitionnement.
+pub fn repartitions(tweets: &[Tweet], k: usize, method: usize, textes: &[&str]) -> Result<Vec<[f64; 3]>, Box<dyn Error>> {
+    let mut decoupage = Decoupage::construire(tweets, k, method)?;
+    let requetes: Vec<Tweet> = textes
+        .iter()
+        .map(|texte| Tweet::new(0, texte.to_string(), validation::NON_ANNOTE))
+        .collect();
+
+    let (groupes, groupes_requetes): (Vec<usize>, Vec<usize>) = match &mut decoupage {
+        Decoupage::Hierarchique { uf, .. } => {
+            let groupes: Vec<usize> = tweets.iter().map(|t| uf.find(t.id)).collect();
+            let requetes = parallel::executer(|| requetes.par_iter().map(|t| groupes[plus_proche_voisin(t, tweets)]).collect());
+            (groupes, requetes)
+        },
+        Decoupage::Partition { modele, .. } => {
+            let modele = &*modele;
+            parallel::executer(|| (tweets.par_iter().map(|t| modele.assigner(t)).collect(), requetes.par_iter().map(|t| modele.assigner(t)).collect()))
+        },
+    };
+
+    let mut comptes: HashMap<usize, [usize; 3]> = HashMap::new();
+    for (tweet, groupe) in tweets.iter().zip(groupes) {
+        if let Some(c) = validation::index_classe(tweet.label) {
+            comptes.entry(groupe).or_default()[c] += 1;
+        }
+    }
+    Ok(groupes_requetes
+        .iter()
+        .map(|groupe| {
+            let compte = comptes.get(groupe).copied().unwrap_or_default();
+            let total: usize = compte.iter().sum();
+            if total == 0 { [1.0 / 3.0; 3] } else { compte.map(|c| c as f64 / total as f64) }
+        })
+        .collect())
+}
+
+// kodama ne sérialise pas ses dendrogrammes : on enregistre la liste des fusions
+mod etapes {
+    use kodama::{Dendrogram, Step};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(dendrogram: &Dendrogram<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        let etapes: Vec<(usize, usize, f64, usize)> = dendrogram
+            .steps()
+            .iter()
+            .map(|s| (s.cluster1, s.cluster2, s.dissimilarity, s.size))
+            .collect();
+        (dendrogram.observations(), etapes).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Dendrogram<f64>, D::Error> {
+        let (observations, etapes) = <(usize, Vec<(usize, usize, f64, usize)>)>::deserialize(deserializer)?;
+        let mut dendrogram = Dendrogram::new(observations);
+        for (cluster1, cluster2, dissimilarity, size) in etapes {
+            dendrogram.push(Step::new(cluster1, cluster2, dissimilarity, size));
+        }
+        Ok(dendrogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::clustering::CacheClustering;
+
+    const CONTENU: &str = "label,texte\n0,triste pluie\n0,triste nul\n4,joie soleil\n4,joie bravo\n2,train gare\n2,train horaire\n";
+
+    fn fichier(nom: &str) -> String {
+        let chemin = std::env::temp_dir().join(format!("{}_{}.csv", std::process::id(), nom));
+        fs::write(&chemin, CONTENU).unwrap();
+        chemin.to_str().unwrap().to_string()
+    }
+
+    fn dater(chemin: &str, secondes: u64) {
+        let fichier = File::options().write(true).open(chemin).unwrap();
+        fichier.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secondes)).unwrap();
+    }
+
+    #[test]
+    fn cle_change_avec_le_fichier() {
+        let chemin = fichier("cle_modele");
+        dater(&chemin, 1_000_000);
+        let cle = CleModele::new(&chemin, 0).unwrap();
+        assert_eq!(CleModele::new(&chemin, 0).unwrap(), cle);
+        assert_ne!(CleModele::new(&chemin, 1).unwrap(), cle);
+
+        // Même taille, autre date
+        dater(&chemin, 2_000_000);
+        let cle_datee = CleModele::new(&chemin, 0).unwrap();
+        assert_ne!(cle_datee, cle);
+
+        // Même date, autre taille
+        fs::write(&chemin, format!("{CONTENU}0,encore triste\n")).unwrap();
+        dater(&chemin, 2_000_000);
+        assert_ne!(CleModele::new(&chemin, 0).unwrap(), cle_datee);
+        fs::remove_file(&chemin).unwrap();
+    }
+
+    #[test]
+    fn cache_reconstruit_apres_modification() {
+        let chemin = fichier("cache_modele");
+        dater(&chemin, 1_000_000);
+        let mut cache = CacheClustering::default();
+        cache.predict_tweet_class(&chemin, "joie", 3, 0).unwrap();
+        let cle = cache.modele_courant().unwrap().cle().clone();
+        assert_eq!(cache.modele_courant().unwrap().tweets().len(), 6);
+
+        // Fichier inchangé : le modèle est réutilisé
+        cache.predict_tweet_class(&chemin, "triste", 3, 0).unwrap();
+        assert_eq!(*cache.modele_courant().unwrap().cle(), cle);
+
+        fs::write(&chemin, format!("{CONTENU}0,encore triste\n")).unwrap();
+        cache.predict_tweet_class(&chemin, "joie", 3, 0).unwrap();
+        assert_ne!(*cache.modele_courant().unwrap().cle(), cle);
+        assert_eq!(cache.modele_courant().unwrap().tweets().len(), 7);
+        fs::remove_file(&chemin).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use kodama::{Dendrogram, Method, Step, linkage};
+use rayon::prelude::*;
+
+use crate::clustering::Tweet;
+use crate::parallel;
+use crate::progress::Progress;
+
+// Taille maximale de la matrice de distance, vérifiée avant de l'allouer
+static BUDGET_MO: AtomicUsize = AtomicUsize::new(2048);
+// Distances en f32 : deux fois moins de mémoire pour une précision largement suffisante pour Jaccard
+static SIMPLE_PRECISION: AtomicBool = AtomicBool::new(false);
+
+pub fn set_budget_mo(mo: usize) {
+    BUDGET_MO.store(mo, Ordering::Relaxed);
+}
+
+pub fn budget_mo() -> usize {
+    BUDGET_MO.load(Ordering::Relaxed)
+}
+
+pub fn set_simple_precision(active: bool) {
+    SIMPLE_PRECISION.store(active, Ordering::Relaxed);
+}
+
+pub fn simple_precision() -> bool {
+    SIMPLE_PRECISION.load(Ordering::Relaxed)
+}
+
+// Refuse une matrice de n tweets qui dépasserait le budget, avant toute allocation
+pub fn verifier_budget(n: usize, octets_par_distance: usize) -> Result<(), String> {
+    let octets = (n as u128) * (n.saturating_sub(1) as u128) / 2 * octets_par_distance as u128;
+    let budget = budget_mo() as u128 * 1024 * 1024;
+    if octets > budget {
+        return Err(format!(
+            "Matrice de distance trop grande : {} tweets demandent {} Mo, le budget est de {} Mo \
+             (augmenter le budget, passer en simple précision ou utiliser k-means / CLARA)",
+            n,
+            octets / (1024 * 1024),
+            budget_mo()
+        ));
+    }
+    Ok(())
+}
+
+/// Matrice de distance condensée (triangle supérieur, ligne par ligne) attendue par kodama,
+/// en double ou simple précision selon le réglage courant.
+#[derive(Clone)]
+pub enum MatriceCondensee {
+    Double(Vec<f64>),
+    Simple(Vec<f32>),
+}
+
+impl MatriceCondensee {
+    /// Les lignes sont calculées en parallèle sur les identifiants de mots triés, chacune écrite à sa place.
+    /// En cas d'annulation les lignes restantes sont sautées : à l'appelant de vérifier `progress`.
+    pub fn calculer(tweets: &[Tweet], progress: &Progress) -> Result<Self, String> {
+        let jetons = identifiants(tweets);
+        if simple_precision() {
+            verifier_budget(tweets.len(), size_of::<f32>())?;
+            Ok(MatriceCondensee::Simple(lignes(&jetons, progress, |d| d as f32)))
+        } else {
+            verifier_budget(tweets.len(), size_of::<f64>())?;
+            Ok(MatriceCondensee::Double(lignes(&jetons, progress, |d| d)))
+        }
+    }
+
+    // Recopie `source` dans l'allocation existante quand les précisions sont les mêmes
+    pub fn recopier(&mut self, source: &Self) {
+        match (self, source) {
+            (MatriceCondensee::Double(a), MatriceCondensee::Double(b)) => a.clone_from(b),
+            (MatriceCondensee::Simple(a), MatriceCondensee::Simple(b)) => a.clone_from(b),
+            (matrice, source) => *matrice = source.clone(),
+        }
+    }
+
+    // Le linkage modifie la matrice en place, d'où la consommation
+    pub fn linkage(mut self, n: usize, method: Method) -> Dendrogram<f64> {
+        self.linkage_en_place(n, method)
+    }
+
+    // Linkage qui laisse la matrice inutilisable, pour réutiliser son allocation avec `recopier`
+    pub fn linkage_en_place(&mut self, n: usize, method: Method) -> Dendrogram<f64> {
+        match self {
+            MatriceCondensee::Double(condensed) => linkage(condensed, n, method),
+            MatriceCondensee::Simple(condensed) => {
+                let dendrogram = linkage(condensed, n, method);
+                let mut double = Dendrogram::new(n);
+                for s in dendrogram.steps() {
+                    double.push(Step::new(s.cluster1, s.cluster2, s.dissimilarity as f64, s.size));
+                }
+                double
+            },
+        }
+    }
+}
+
+// Octets d'une distance selon la précision courante
+pub fn octets_par_distance() -> usize {
+    if simple_precision() { size_of::<f32>() } else { size_of::<f64>() }
+}
+
+fn lignes<T: Send + Copy + Default>(jetons: &[Vec<u32>], progress: &Progress, convertir: impl Fn(f64) -> T + Sync) -> Vec<T> {
+    let n = jetons.len();
+    // Allouée une seule fois à sa taille finale : le budget vérifié est bien la mémoire utilisée
+    let mut condensee = vec![T::default(); n * n.saturating_sub(1) / 2];
+
+    // La ligne i contient les distances de i à i + 1..n, les lignes sont des tranches disjointes
+    let mut reste = condensee.as_mut_slice();
+    let mut lignes = Vec::with_capacity(n.saturating_sub(1));
+    for i in 0..n.saturating_sub(1) {
+        let (ligne, suite) = std::mem::take(&mut reste).split_at_mut(n - 1 - i);
+        lignes.push(ligne);
+        reste = suite;
+    }
+
+    let convertir = &convertir;
+    parallel::executer(|| {
+        lignes.into_par_iter().enumerate().for_each(|(i, ligne)| {
+            let annule = progress.is_cancelled();
+            progress.advance(1);
+            if annule {
+                return;
+            }
+            for (decalage, distance) in ligne.iter_mut().enumerate() {
+                *distance = convertir(jaccard(&jetons[i], &jetons[i + 1 + decalage]));
+            }
+        })
+    });
+    condensee
+}
+
+// Chaque mot du corpus reçoit un identifiant, chaque tweet devient la liste triée de ses identifiants
+pub fn identifiants(tweets: &[Tweet]) -> Vec<Vec<u32>> {
+    let mut vocabulaire: HashMap<&str, u32> = HashMap::new();
+    tweets
+        .iter()
+        .map(|tweet| {
+            let mut ids: Vec<u32> = tweet
+                .mots
+                .iter()
+                .map(|mot| {
+                    let suivant = vocabulaire.len() as u32;
+                    *vocabulaire.entry(mot.as_str()).or_insert(suivant)
+                })
+                .collect();
+            ids.sort_unstable();
+            ids
+        })
+        .collect()
+}
+
+// Même distance que clustering::distance, par fusion des deux listes triées (sans table de hachage)
+pub fn jaccard(a: &[u32], b: &[u32]) -> f64 {
+    let (mut i, mut j, mut communs) = (0, 0, 0usize);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                communs += 1;
+                i += 1;
+                j += 1;
+            },
+        }
+    }
+    let union = (a.len() + b.len() - communs) as f64;
+    if union == 0.0 { 1.0 } else { (union - communs as f64) / union }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweets(textes: &[&str]) -> Vec<Tweet> {
+        textes.iter().enumerate().map(|(id, t)| Tweet::new(id, t.to_string(), 0)).collect()
+    }
+
+    // Position de la paire (i, j), i < j, dans le triangle supérieur rangé ligne par ligne
+    fn indice(n: usize, i: usize, j: usize) -> usize {
+        n * i - i * (i + 1) / 2 + j - i - 1
+    }
+
+    #[test]
+    fn identifiants_partages_et_tries() {
+        let jetons = identifiants(&tweets(&["chat chien", "chien oiseau chat", ""]));
+        assert_eq!(jetons[0], vec![0, 1]);
+        assert_eq!(jetons[1], vec![0, 1, 2]);
+        assert!(jetons[2].is_empty());
+    }
+
+    #[test]
+    fn jaccard_comme_la_distance_des_ensembles() {
+        let tweets = tweets(&["a b c", "b c d", "e f", "a b c", "", ""]);
+        let jetons = identifiants(&tweets);
+        for i in 0..tweets.len() {
+            for j in 0..tweets.len() {
+                assert_eq!(jaccard(&jetons[i], &jetons[j]), crate::clustering::distance(&tweets[i], &tweets[j]));
+            }
+        }
+        assert_eq!(jaccard(&jetons[0], &jetons[1]), 0.5);
+        assert_eq!(jaccard(&jetons[0], &jetons[2]), 1.0);
+        assert_eq!(jaccard(&jetons[0], &jetons[3]), 0.0);
+        // Deux tweets vides n'ont rien en commun
+        assert_eq!(jaccard(&jetons[4], &jetons[5]), 1.0);
+    }
+
+    #[test]
+    fn lignes_rangees_comme_kodama() {
+        let tweets = tweets(&["a b", "a c", "b c d", "d", "a b c d"]);
+        let jetons = identifiants(&tweets);
+        let n = tweets.len();
+        let condensee = lignes(&jetons, &Progress::default(), |d| d);
+        assert_eq!(condensee.len(), n * (n - 1) / 2);
+        for i in 0..n {
+            for j in i + 1..n {
+                assert_eq!(condensee[indice(n, i, j)], jaccard(&jetons[i], &jetons[j]));
+            }
+        }
+        assert!(lignes(&jetons[..1], &Progress::default(), |d| d).is_empty());
+        assert!(lignes(&[], &Progress::default(), |d| d).is_empty());
+    }
+
+    #[test]
+    fn annulation_saute_les_lignes() {
+        let jetons = identifiants(&tweets(&["a", "b", "c", "d"]));
+        let progress = Progress::default();
+        progress.cancel();
+        let condensee = lignes(&jetons, &progress, |d| d);
+        assert!(condensee.iter().all(|&d| d == 0.0));
+        assert_eq!(progress.state().0, 3);
+    }
+
+    #[test]
+    fn simple_et_double_precision_meme_dendrogramme() {
+        let jetons = identifiants(&tweets(&["a b", "a b c", "d e", "d e f", "a f"]));
+        let double = MatriceCondensee::Double(lignes(&jetons, &Progress::default(), |d| d));
+        let simple = MatriceCondensee::Simple(lignes(&jetons, &Progress::default(), |d| d as f32));
+        let (d, s) = (double.linkage(5, Method::Average), simple.linkage(5, Method::Average));
+        assert_eq!(d.len(), s.len());
+        for (a, b) in d.steps().iter().zip(s.steps()) {
+            assert_eq!((a.cluster1, a.cluster2, a.size), (b.cluster1, b.cluster2, b.size));
+            assert!((a.dissimilarity - b.dissimilarity).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn budget_verifie_avant_allocation() {
+        assert!(verifier_budget(0, 8).is_ok());
+        assert!(verifier_budget(1, 8).is_ok());
+        assert!(verifier_budget(1000, 8).is_ok());
+        // 100 000 tweets en double précision : environ 38 Go
+        let erreur = verifier_budget(100_000, 8).unwrap_err();
+        assert!(erreur.contains("100000 tweets"), "{}", erreur);
+    }
+}
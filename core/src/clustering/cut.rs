@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use kodama::Dendrogram;
+
+use crate::clustering::{Tweet, UnionFind};
+use crate::validation;
+
+// Critère de coupe du dendrogramme
+#[derive(Debug, Clone, Copy)]
+pub enum Coupe {
+    // Exactement K groupes (les n - K premières fusions)
+    NbClusters(usize),
+    // Fusions dont la dissimilarité ne dépasse pas le seuil
+    Distance(f64),
+    // Fusions dont le coefficient d'incohérence (calculé sur `profondeur` niveaux) ne dépasse pas le seuil
+    Incoherence { seuil: f64, profondeur: usize },
+}
+
+impl Coupe {
+    // 0 = nombre de clusters, 1 = distance, 2 = incohérence (même numérotation que clustering_clusters)
+    pub fn depuis_critere(critere: i64, valeur: f64, profondeur: i64) -> Result<Self, String> {
+        match critere {
+            0 if valeur >= 1.0 => Ok(Coupe::NbClusters(valeur as usize)),
+            1 => Ok(Coupe::Distance(valeur)),
+            2 => Ok(Coupe::Incoherence { seuil: valeur, profondeur: profondeur.max(1) as usize }),
+            0 => Err(format!("Nombre de clusters invalide ({})", valeur)),
+            _ => Err(format!("Critère de coupe inconnu ({})", critere)),
+        }
+    }
+}
+
+/// Applique les fusions retenues par le critère : le noeud `n + i` créé à l'étape i est relié à ses deux enfants,
+/// si bien que `find` sur un tweet donne son groupe. Pour les seuils, une fusion n'est gardée que si toutes
+/// celles de son sous-arbre le sont aussi (maximum sur le sous-arbre), comme le fcluster de scipy.
+pub(super) fn couper(dendrogram: &Dendrogram<f64>, n: usize, coupe: Coupe) -> UnionFind {
+    let steps = dendrogram.steps();
+    let garder: Vec<bool> = match coupe {
+        Coupe::NbClusters(k) => {
+            let steps_to_process = n.saturating_sub(k);
+            (0..steps.len()).map(|i| i < steps_to_process).collect()
+        },
+        Coupe::Distance(seuil) => {
+            let hauteurs: Vec<f64> = steps.iter().map(|s| s.dissimilarity).collect();
+            maximum_sous_arbre(dendrogram, n, &hauteurs).iter().map(|&h| h <= seuil).collect()
+        },
+        Coupe::Incoherence { seuil, profondeur } => {
+            let coefficients = coefficients_incoherence(dendrogram, n, profondeur);
+            maximum_sous_arbre(dendrogram, n, &coefficients).iter().map(|&c| c <= seuil).collect()
+        },
+    };
+
+    let mut uf = UnionFind::new(n + steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        if garder[i] {
+            uf.union(step.cluster1, n + i);
+            uf.union(step.cluster2, n + i);
+        }
+    }
+    uf
+}
+
+// Pour chaque fusion, le maximum de `valeurs` sur elle-même et les fusions de son sous-arbre.
+// Les enfants d'une fusion sont toujours créés avant elle, un seul parcours suffit.
+fn maximum_sous_arbre(dendrogram: &Dendrogram<f64>, n: usize, valeurs: &[f64]) -> Vec<f64> {
+    let mut maximums: Vec<f64> = Vec::with_capacity(valeurs.len());
+    for (i, step) in dendrogram.steps().iter().enumerate() {
+        let mut maximum = valeurs[i];
+        for enfant in [step.cluster1, step.cluster2] {
+            if enfant >= n {
+                maximum = maximum.max(maximums[enfant - n]);
+            }
+        }
+        maximums.push(maximum);
+    }
+    maximums
+}
+
+/// Coefficient d'incohérence de chaque fusion : (hauteur - moyenne) / écart-type,
+/// moyenne et écart-type pris sur les hauteurs des fusions à moins de `profondeur` niveaux en dessous (elle comprise).
+/// Une fusion sans dispersion (feuilles seules ou hauteurs égales) a un coefficient nul.
+fn coefficients_incoherence(dendrogram: &Dendrogram<f64>, n: usize, profondeur: usize) -> Vec<f64> {
+    let steps = dendrogram.steps();
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let mut hauteurs = Vec::new();
+            let mut a_visiter = vec![(i, 1)];
+            while let Some((fusion, niveau)) = a_visiter.pop() {
+                let courant = &steps[fusion];
+                hauteurs.push(courant.dissimilarity);
+                if niveau < profondeur {
+                    for enfant in [courant.cluster1, courant.cluster2] {
+                        if enfant >= n {
+                            a_visiter.push((enfant - n, niveau + 1));
+                        }
+                    }
+                }
+            }
+
+            let nb = hauteurs.len() as f64;
+            let moyenne = hauteurs.iter().sum::<f64>() / nb;
+            let variance = if hauteurs.len() > 1 {
+                hauteurs.iter().map(|h| (h - moyenne).powi(2)).sum::<f64>() / (nb - 1.0)
+            } else {
+                0.0
+            };
+            let ecart_type = variance.sqrt();
+            if ecart_type > 0.0 { (step.dissimilarity - moyenne) / ecart_type } else { 0.0 }
+        })
+        .collect()
+}
+
+// Description d'un groupe pour l'inspection depuis Godot
+pub struct ResumeCluster {
+    pub id: usize,
+    pub taille: usize,
+    pub label: i32,
+    // Part des tweets du groupe qui ont l'étiquette majoritaire
+    pub purete: f64,
+    // [négatif, neutre, positif]
+    pub comptes: [usize; 3],
+}
+
+/// Renumérote les groupes de 0 à m - 1 par taille décroissante (puis par ordre d'apparition)
+/// et résume chacun. Renvoie le groupe de chaque tweet dans la nouvelle numérotation.
+pub fn resumer(groupes: &[usize], tweets: &[Tweet]) -> (Vec<usize>, Vec<ResumeCluster>) {
+    let mut ordre: Vec<usize> = Vec::new();
+    let mut membres: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, &groupe) in groupes.iter().enumerate() {
+        membres.entry(groupe).or_insert_with(|| {
+            ordre.push(groupe);
+            Vec::new()
+        }).push(idx);
+    }
+    ordre.sort_by_key(|groupe| std::cmp::Reverse(membres[groupe].len()));
+
+    let mut nouveaux = vec![0; groupes.len()];
+    let mut resumes = Vec::with_capacity(ordre.len());
+    for (id, groupe) in ordre.iter().enumerate() {
+        let mut comptes = [0usize; 3];
+        for &idx in &membres[groupe] {
+            nouveaux[idx] = id;
+            if let Some(c) = validation::index_classe(tweets[idx].label) {
+                comptes[c] += 1;
+            }
+        }
+
+        // Égalité : la plus petite étiquette, comme etiquettes_partition
+        let (meilleur, &effectif) = comptes.iter().enumerate().rev().max_by_key(|&(_, c)| c).unwrap_or((1, &0));
+        let taille = membres[groupe].len();
+        resumes.push(ResumeCluster {
+            id,
+            taille,
+            label: validation::CLASSES[meilleur],
+            purete: if taille == 0 { 0.0 } else { effectif as f64 / taille as f64 },
+            comptes,
+        });
+    }
+    (nouveaux, resumes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodama::Step;
+
+    // Lien simple sur les points 0, 1, 5, 6 et 20 d'une droite
+    fn dendrogramme() -> Dendrogram<f64> {
+        let mut dendrogram = Dendrogram::new(5);
+        dendrogram.push(Step::new(0, 1, 1.0, 2));
+        dendrogram.push(Step::new(2, 3, 1.0, 2));
+        dendrogram.push(Step::new(5, 6, 4.0, 4));
+        dendrogram.push(Step::new(7, 4, 14.0, 5));
+        dendrogram
+    }
+
+    // Groupe de chaque point, numérotés par ordre d'apparition
+    fn groupes(coupe: Coupe) -> Vec<usize> {
+        let mut uf = couper(&dendrogramme(), 5, coupe);
+        let mut racines: Vec<usize> = Vec::new();
+        (0..5)
+            .map(|i| {
+                let racine = uf.find(i);
+                racines.iter().position(|&r| r == racine).unwrap_or_else(|| {
+                    racines.push(racine);
+                    racines.len() - 1
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn coupe_distance() {
+        // fcluster(Z, t, criterion='distance')
+        assert_eq!(groupes(Coupe::Distance(0.5)), vec![0, 1, 2, 3, 4]);
+        assert_eq!(groupes(Coupe::Distance(3.9)), vec![0, 0, 1, 1, 2]);
+        assert_eq!(groupes(Coupe::Distance(4.0)), vec![0, 0, 0, 0, 1]);
+        assert_eq!(groupes(Coupe::Distance(14.0)), vec![0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn coefficients_comme_scipy() {
+        // Quatrième colonne de inconsistent(Z, d=2)
+        let coefficients = coefficients_incoherence(&dendrogramme(), 5, 2);
+        let attendus = [0.0, 0.0, 2.0 / 3f64.sqrt(), 1.0 / 2f64.sqrt()];
+        for (c, a) in coefficients.iter().zip(attendus) {
+            assert!((c - a).abs() < 1e-9, "{:?}", coefficients);
+        }
+    }
+
+    #[test]
+    fn coupe_incoherence() {
+        // fcluster(Z, t, criterion='inconsistent', depth=2) : la dernière fusion, peu incohérente,
+        // est rejetée car son sous-arbre contient la fusion à 1,15
+        assert_eq!(groupes(Coupe::Incoherence { seuil: 1.0, profondeur: 2 }), vec![0, 0, 1, 1, 2]);
+        assert_eq!(groupes(Coupe::Incoherence { seuil: 1.2, profondeur: 2 }), vec![0, 0, 0, 0, 0]);
+        assert_eq!(groupes(Coupe::Incoherence { seuil: 0.0, profondeur: 2 }), vec![0, 0, 1, 1, 2]);
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt::Write;
+
+use kodama::Dendrogram;
+
+use crate::clustering::Tweet;
+use crate::clustering::cut::{self, Coupe};
+use crate::validation;
+
+// Au-delà, les sous-arbres les plus profonds sont repliés en une feuille (comme le truncate_mode="lastp" de scipy)
+pub const MAX_FEUILLES: usize = 80;
+
+const LARGEUR_FEUILLE: f64 = 18.0;
+const HAUTEUR_ARBRE: f64 = 480.0;
+const MARGE_GAUCHE: f64 = 80.0;
+const MARGE_DROITE: f64 = 20.0;
+const MARGE_HAUT: f64 = 30.0;
+const ZONE_ETIQUETTES: f64 = 190.0;
+const LONGUEUR_EXTRAIT: usize = 28;
+
+// Une couleur par cluster de la coupe en K, les fusions au-dessus de la coupe restent grises
+const PALETTE: [&str; 10] = ["#4a90e2", "#e2a64a", "#9b59b6", "#1abc9c", "#e74c3c", "#f1c40f", "#2ecc71", "#e67e22", "#3498db", "#fd79a8"];
+const COULEUR_HAUT: &str = "#9e9e9e";
+
+// Couleur des feuilles selon l'étiquette (Négatif=Rouge, Neutre=Gris, Positif=Vert), dans l'ordre de validation::CLASSES
+const COULEURS_LABELS: [&str; 3] = ["#f44336", "#9e9e9e", "#4caf50"];
+const NOMS_LABELS: [&str; 3] = ["négatif", "neutre", "positif"];
+
+/// Dessine le dendrogramme : axe des dissimilarités à gauche, branches colorées selon les K clusters de la coupe,
+/// au plus `max_feuilles` feuilles. Une feuille est un tweet (extrait en étiquette, texte complet en infobulle)
+/// ou un sous-arbre replié (taille et pureté en étiquette, répartition des étiquettes en infobulle).
+/// La largeur suit le nombre de feuilles affichées pour rester lisible une fois zoomé.
+pub fn generate_dendrogram_svg(dendrogram: &Dendrogram<f64>, tweets: &[Tweet], k: usize, max_feuilles: usize) -> String {
+    let n = tweets.len();
+    let steps = dendrogram.steps();
+    // Moins de deux tweets : aucune fusion à dessiner
+    if steps.is_empty() {
+        return r##"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="100" viewBox="0 0 600 100"><rect width="100%" height="100%" fill="#202020"/></svg>"##.to_string();
+    }
+    let total_nodes = n + steps.len();
+
+    // Seules les p - 1 dernières fusions sont dessinées, les noeuds en dessous deviennent des feuilles
+    let p = max_feuilles.clamp(1, n.max(1));
+    let premiere_visible = n.saturating_sub(p);
+    let enfants = |noeud: usize| -> Option<(usize, usize)> {
+        if noeud >= n && noeud - n >= premiere_visible {
+            let step = &steps[noeud - n];
+            Some((step.cluster1, step.cluster2))
+        } else {
+            None
+        }
+    };
+
+    // Répartition des étiquettes et tweet représentatif de chaque noeud (les enfants précèdent toujours leur parent)
+    let mut comptes = vec![[0usize; 3]; total_nodes];
+    let mut representant: Vec<usize> = (0..total_nodes).collect();
+    for (i, tweet) in tweets.iter().enumerate() {
+        if let Some(c) = validation::index_classe(tweet.label) {
+            comptes[i][c] += 1;
+        }
+    }
+    for (i, step) in steps.iter().enumerate() {
+        let (gauche, droite) = (comptes[step.cluster1], comptes[step.cluster2]);
+        comptes[n + i] = [gauche[0] + droite[0], gauche[1] + droite[1], gauche[2] + droite[2]];
+        representant[n + i] = representant[step.cluster1];
+    }
+
+    // Ordre des feuilles par parcours en profondeur pour éviter de croiser les lignes
+    let racine = total_nodes - 1;
+    let mut feuilles = Vec::with_capacity(p);
+    let mut a_visiter = vec![racine];
+    while let Some(noeud) = a_visiter.pop() {
+        match enfants(noeud) {
+            Some((gauche, droite)) => {
+                a_visiter.push(droite);
+                a_visiter.push(gauche);
+            },
+            None => feuilles.push(noeud),
+        }
+    }
+
+    // Cluster de la coupe en K pour chaque noeud sous la coupe, couleurs attribuées dans l'ordre des feuilles
+    let mut uf = cut::couper(dendrogram, n, Coupe::NbClusters(k));
+    let derniere_sous_coupe = n.saturating_sub(k);
+    let mut racines_couleurs: Vec<usize> = Vec::new();
+    let mut couleur = |noeud: usize| -> &'static str {
+        if noeud >= n && noeud - n >= derniere_sous_coupe {
+            return COULEUR_HAUT;
+        }
+        let racine = uf.find(noeud);
+        let idx = racines_couleurs.iter().position(|&r| r == racine).unwrap_or_else(|| {
+            racines_couleurs.push(racine);
+            racines_couleurs.len() - 1
+        });
+        PALETTE[idx % PALETTE.len()]
+    };
+
+    let width = (MARGE_GAUCHE + feuilles.len() as f64 * LARGEUR_FEUILLE + MARGE_DROITE).max(600.0);
+    let height = MARGE_HAUT + HAUTEUR_ARBRE + ZONE_ETIQUETTES;
+    let base_y = MARGE_HAUT + HAUTEUR_ARBRE;
+    let max_dissim = steps.last().map(|s| s.dissimilarity).unwrap_or(1.0).max(0.0001);
+    let y_de = |dissimilarite: f64| base_y - dissimilarite / max_dissim * HAUTEUR_ARBRE;
+
+    let mut svg = String::with_capacity(1024 * (feuilles.len() + 8));
+    let _ = write!(
+        &mut svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}"><rect width="100%" height="100%" fill="#202020"/><style>.link {{ stroke-width: 2; fill: none; }} text {{ fill: #e0e0e0; font-family: sans-serif; font-size: 11px; }}</style>"##,
+        width, height, width, height
+    );
+    dessiner_axe(&mut svg, max_dissim, y_de);
+
+    // Feuilles : point coloré par étiquette majoritaire, étiquette inclinée, infobulle
+    let mut positions: Vec<Option<(f64, f64)>> = vec![None; total_nodes];
+    for (rang, &noeud) in feuilles.iter().enumerate() {
+        let x = MARGE_GAUCHE + (rang as f64 + 0.5) * LARGEUR_FEUILLE;
+        positions[noeud] = Some((x, base_y));
+
+        let compte = comptes[noeud];
+        let taille: usize = compte.iter().sum();
+        let majoritaire = (0..3).rev().max_by_key(|&c| compte[c]).unwrap_or(1);
+        let (etiquette, infobulle) = if noeud < n {
+            let tweet = &tweets[noeud];
+            (extrait(&tweet.contenu), format!("[{}] {}", tweet.label, tweet.contenu))
+        } else {
+            let purete = if taille == 0 { 0.0 } else { compte[majoritaire] as f64 / taille as f64 };
+            (
+                format!("({}) {:.0}% {}", taille, purete * 100.0, NOMS_LABELS[majoritaire]),
+                format!(
+                    "{} tweets - négatif {} / neutre {} / positif {} - ex : {}",
+                    taille, compte[0], compte[1], compte[2], tweets[representant[noeud]].contenu
+                ),
+            )
+        };
+        let rayon = if noeud < n { 4.0 } else { 4.0 + (taille as f64).log10() * 2.0 };
+
+        let _ = write!(
+            &mut svg,
+            r#"<g><title>{}</title><circle cx="{}" cy="{}" r="{}" fill="{}" /><text transform="translate({} {}) rotate(60)">{}</text></g>"#,
+            echapper(&infobulle),
+            x,
+            base_y,
+            rayon,
+            COULEURS_LABELS[majoritaire],
+            x - 3.0,
+            base_y + 10.0,
+            echapper(&etiquette)
+        );
+    }
+
+    // Branches : chaque fusion visible relie ses deux enfants à sa hauteur
+    for (i, step) in steps.iter().enumerate().skip(premiere_visible) {
+        let noeud = n + i;
+        let merge_y = y_de(step.dissimilarity);
+        if let (Some((x1, y1)), Some((x2, y2))) = (positions[step.cluster1], positions[step.cluster2]) {
+            let new_x = (x1 + x2) / 2.0;
+            positions[noeud] = Some((new_x, merge_y));
+            let _ = write!(
+                &mut svg,
+                r#"<path d="M{} {} V{} H{} V{}" class="link" stroke="{}"><title>dissimilarité {:.3} - {} tweets</title></path>"#,
+                x1,
+                y1,
+                merge_y,
+                x2,
+                y2,
+                couleur(noeud),
+                step.dissimilarity,
+                step.size
+            );
+        }
+    }
+
+    let _ = write!(&mut svg, "</svg>");
+    svg
+}
+
+// Axe vertical des dissimilarités avec 5 graduations
+fn dessiner_axe(svg: &mut String, max_dissim: f64, y_de: impl Fn(f64) -> f64) {
+    let x = MARGE_GAUCHE - 10.0;
+    let _ = write!(svg, r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#e0e0e0" />"##, x, y_de(max_dissim), x, y_de(0.0));
+    for graduation in 0..=5 {
+        let valeur = max_dissim * graduation as f64 / 5.0;
+        let y = y_de(valeur);
+        let _ = write!(
+            svg,
+            r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#e0e0e0" /><text x="{}" y="{}" text-anchor="end">{:.2}</text>"##,
+            x - 5.0,
+            y,
+            x,
+            y,
+            x - 8.0,
+            y + 4.0,
+            valeur
+        );
+    }
+    let _ = write!(
+        svg,
+        r#"<text transform="translate(14 {}) rotate(-90)" text-anchor="middle">Dissimilarité</text>"#,
+        y_de(max_dissim / 2.0)
+    );
+}
+
+fn extrait(texte: &str) -> String {
+    let mut extrait: String = texte.chars().take(LONGUEUR_EXTRAIT).collect();
+    if texte.chars().count() > LONGUEUR_EXTRAIT {
+        extrait.push('…');
+    }
+    extrait
+}
+
+pub fn echapper(texte: &str) -> String {
+    texte
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodama::Step;
+
+    // Lien simple sur les points 0, 1, 5, 6 et 20 d'une droite
+    fn arbre() -> (Dendrogram<f64>, Vec<Tweet>) {
+        let mut dendrogram = Dendrogram::new(5);
+        dendrogram.push(Step::new(0, 1, 1.0, 2));
+        dendrogram.push(Step::new(2, 3, 1.0, 2));
+        dendrogram.push(Step::new(5, 6, 4.0, 4));
+        dendrogram.push(Step::new(7, 4, 14.0, 5));
+        let tweets = [(0, "triste"), (0, "nul"), (4, "joie"), (4, "bravo <3"), (2, "train")]
+            .iter()
+            .enumerate()
+            .map(|(id, &(label, texte))| Tweet::new(id, texte.to_string(), label))
+            .collect();
+        (dendrogram, tweets)
+    }
+
+    #[test]
+    fn toutes_les_feuilles() {
+        let (dendrogram, tweets) = arbre();
+        let svg = generate_dendrogram_svg(&dendrogram, &tweets, 2, MAX_FEUILLES);
+        assert_eq!(svg.matches("<circle").count(), 5);
+        assert_eq!(svg.matches(r#"class="link""#).count(), 4);
+        assert!(svg.contains("bravo &lt;3"));
+        assert!(!svg.contains("bravo <3"));
+    }
+
+    #[test]
+    fn sous_arbres_replies() {
+        let (dendrogram, tweets) = arbre();
+        // Deux feuilles : la dernière fusion seule, son enfant de 4 tweets replié
+        let svg = generate_dendrogram_svg(&dendrogram, &tweets, 2, 2);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert_eq!(svg.matches(r#"class="link""#).count(), 1);
+        assert!(svg.contains("(4) 50% négatif"), "{}", svg);
+        assert!(svg.contains("4 tweets - négatif 2 / neutre 0 / positif 2"));
+
+        // Trois feuilles : les deux paires repliées et le tweet isolé
+        let svg = generate_dendrogram_svg(&dendrogram, &tweets, 2, 3);
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("(2) 100%").count(), 2);
+    }
+
+    #[test]
+    fn sans_fusion() {
+        let tweets = vec![Tweet::new(0, "seul".to_string(), 2)];
+        let svg = generate_dendrogram_svg(&Dendrogram::new(1), &tweets, 1, MAX_FEUILLES);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert!(!svg.contains("<circle"));
+    }
+}
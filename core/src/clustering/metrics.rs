@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use rand::seq::index;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::clustering::{Tweet, distance};
+use crate::{parallel, validation};
+
+// Au-delà, silhouette et Davies-Bouldin sont estimés sur un échantillon (coût quadratique)
+const TAILLE_ECHANTILLON: usize = 2000;
+
+/// Qualité d'un découpage du jeu d'entraînement.
+/// Intrinsèques (distance de Jaccard, quelle que soit la méthode) : silhouette et Davies-Bouldin.
+/// Extrinsèques (par rapport aux étiquettes de sentiment) : pureté, information mutuelle normalisée, indice de Rand ajusté.
+#[derive(Debug, Clone, Serialize)]
+pub struct Metriques {
+    pub silhouette: f64,
+    pub davies_bouldin: f64,
+    pub purete: f64,
+    pub nmi: f64,
+    pub ari: f64,
+}
+
+// `groupes[i]` est le groupe (identifiant quelconque) de `tweets[i]`
+pub fn calculer(tweets: &[Tweet], groupes: &[usize], graine: i64) -> Metriques {
+    // Groupes renumérotés de 0 à m - 1
+    let mut numeros: HashMap<usize, usize> = HashMap::new();
+    let groupes: Vec<usize> = groupes
+        .iter()
+        .map(|g| {
+            let suivant = numeros.len();
+            *numeros.entry(*g).or_insert(suivant)
+        })
+        .collect();
+    let nb_groupes = numeros.len();
+
+    let n = tweets.len();
+    let echantillon: Vec<usize> = if n > TAILLE_ECHANTILLON {
+        let mut rng = validation::generateur(graine);
+        let mut echantillon = index::sample(&mut rng, n, TAILLE_ECHANTILLON).into_vec();
+        echantillon.sort_unstable();
+        echantillon
+    } else {
+        (0..n).collect()
+    };
+    let distances: Vec<Vec<f64>> = parallel::executer(|| {
+        echantillon
+            .par_iter()
+            .map(|&i| echantillon.iter().map(|&j| distance(&tweets[i], &tweets[j])).collect())
+            .collect()
+    });
+    let groupes_echantillon: Vec<usize> = echantillon.iter().map(|&i| groupes[i]).collect();
+
+    let etiquettes: Vec<usize> = tweets.iter().map(|t| validation::index_classe(t.label).unwrap_or(1)).collect();
+    let contingence = table_contingence(&groupes, &etiquettes, nb_groupes);
+
+    Metriques {
+        silhouette: silhouette(&distances, &groupes_echantillon, nb_groupes),
+        davies_bouldin: davies_bouldin(&distances, &groupes_echantillon, nb_groupes),
+        purete: purete(&contingence, n),
+        nmi: information_mutuelle_normalisee(&contingence, n),
+        ari: rand_ajuste(&contingence, n),
+    }
+}
+
+// Silhouette moyenne, un tweet seul dans son groupe compte pour 0. Nulle s'il n'y a qu'un groupe.
+fn silhouette(distances: &[Vec<f64>], groupes: &[usize], nb_groupes: usize) -> f64 {
+    let n = groupes.len();
+    if n == 0 || nb_groupes < 2 {
+        return 0.0;
+    }
+    let mut tailles = vec![0usize; nb_groupes];
+    for &g in groupes {
+        tailles[g] += 1;
+    }
+
+    let somme: f64 = parallel::executer(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                if tailles[groupes[i]] < 2 {
+                    return 0.0;
+                }
+                let mut sommes = vec![0.0; nb_groupes];
+                for (&g, &d) in groupes.iter().zip(&distances[i]) {
+                    sommes[g] += d;
+                }
+
+                let a = sommes[groupes[i]] / (tailles[groupes[i]] - 1) as f64;
+                let b = (0..nb_groupes)
+                    .filter(|&g| g != groupes[i] && tailles[g] > 0)
+                    .map(|g| sommes[g] / tailles[g] as f64)
+                    .fold(f64::MAX, f64::min);
+                if b == f64::MAX || a.max(b) == 0.0 { 0.0 } else { (b - a) / a.max(b) }
+            })
+            .sum()
+    });
+    somme / n as f64
+}
+
+/// Davies-Bouldin avec des médoïdes à la place des centroïdes (pas de moyenne pour des ensembles de mots) :
+/// dispersion = distance moyenne au médoïde, séparation = distance entre médoïdes. Plus bas = mieux séparé.
+fn davies_bouldin(distances: &[Vec<f64>], groupes: &[usize], nb_groupes: usize) -> f64 {
+    let mut membres: Vec<Vec<usize>> = vec![Vec::new(); nb_groupes];
+    for (i, &g) in groupes.iter().enumerate() {
+        membres[g].push(i);
+    }
+    membres.retain(|m| !m.is_empty());
+    if membres.len() < 2 {
+        return 0.0;
+    }
+
+    // (médoïde, dispersion) de chaque groupe
+    let centres: Vec<(usize, f64)> = membres
+        .iter()
+        .map(|m| {
+            m.iter()
+                .map(|&i| (i, m.iter().map(|&j| distances[i][j]).sum::<f64>() / m.len() as f64))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap()
+        })
+        .collect();
+
+    let somme: f64 = centres
+        .iter()
+        .enumerate()
+        .map(|(a, &(medoide_a, dispersion_a))| {
+            centres
+                .iter()
+                .enumerate()
+                .filter(|&(b, &(medoide_b, _))| b != a && distances[medoide_a][medoide_b] > 0.0)
+                .map(|(_, &(medoide_b, dispersion_b))| (dispersion_a + dispersion_b) / distances[medoide_a][medoide_b])
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    somme / centres.len() as f64
+}
+
+// contingence[groupe][classe], classes dans l'ordre de validation::CLASSES
+fn table_contingence(groupes: &[usize], etiquettes: &[usize], nb_groupes: usize) -> Vec<[usize; 3]> {
+    let mut table = vec![[0usize; 3]; nb_groupes];
+    for (&g, &c) in groupes.iter().zip(etiquettes) {
+        table[g][c] += 1;
+    }
+    table
+}
+
+// Part des tweets qui ont l'étiquette majoritaire de leur groupe
+fn purete(contingence: &[[usize; 3]], n: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    contingence.iter().map(|ligne| *ligne.iter().max().unwrap()).sum::<usize>() as f64 / n as f64
+}
+
+fn entropie(effectifs: impl Iterator<Item = usize>, n: f64) -> f64 {
+    effectifs
+        .filter(|&e| e > 0)
+        .map(|e| {
+            let p = e as f64 / n;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+// NMI normalisée par la moyenne arithmétique des entropies. Vaut 1 si les deux partitions sont triviales.
+fn information_mutuelle_normalisee(contingence: &[[usize; 3]], n: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    let n = n as f64;
+    let tailles_groupes: Vec<usize> = contingence.iter().map(|ligne| ligne.iter().sum()).collect();
+    let tailles_classes: Vec<usize> = (0..3).map(|c| contingence.iter().map(|ligne| ligne[c]).sum()).collect();
+
+    let mut information = 0.0;
+    for (g, ligne) in contingence.iter().enumerate() {
+        for (c, &nij) in ligne.iter().enumerate() {
+            if nij > 0 {
+                let nij = nij as f64;
+                information += nij / n * (n * nij / (tailles_groupes[g] as f64 * tailles_classes[c] as f64)).ln();
+            }
+        }
+    }
+
+    let h_groupes = entropie(tailles_groupes.into_iter(), n);
+    let h_classes = entropie(tailles_classes.into_iter(), n);
+    let moyenne = (h_groupes + h_classes) / 2.0;
+    if moyenne == 0.0 { 1.0 } else { (information / moyenne).max(0.0) }
+}
+
+fn paires(x: usize) -> f64 {
+    let x = x as f64;
+    x * (x - 1.0) / 2.0
+}
+
+// Indice de Rand ajusté : 0 pour un découpage au hasard, 1 pour un accord parfait avec les étiquettes
+fn rand_ajuste(contingence: &[[usize; 3]], n: usize) -> f64 {
+    let somme_cellules: f64 = contingence.iter().flatten().map(|&nij| paires(nij)).sum();
+    let somme_groupes: f64 = contingence.iter().map(|ligne| paires(ligne.iter().sum())).sum();
+    let somme_classes: f64 = (0..3).map(|c| paires(contingence.iter().map(|ligne| ligne[c]).sum())).sum();
+
+    let attendu = somme_groupes * somme_classes / paires(n).max(1.0);
+    let maximum = (somme_groupes + somme_classes) / 2.0;
+    if maximum == attendu { 1.0 } else { (somme_cellules - attendu) / (maximum - attendu) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proche(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn indices(groupes: &[usize], etiquettes: &[usize]) -> (f64, f64) {
+        let nb_groupes = groupes.iter().max().map_or(0, |&g| g + 1);
+        let contingence = table_contingence(groupes, etiquettes, nb_groupes);
+        (rand_ajuste(&contingence, groupes.len()), information_mutuelle_normalisee(&contingence, groupes.len()))
+    }
+
+    #[test]
+    fn partitions_identiques() {
+        let (ari, nmi) = indices(&[0, 0, 1, 1, 2, 2], &[0, 0, 1, 1, 2, 2]);
+        assert!(proche(ari, 1.0));
+        assert!(proche(nmi, 1.0));
+    }
+
+    #[test]
+    fn numerotation_permutee() {
+        let (ari, nmi) = indices(&[2, 2, 0, 0, 1, 1], &[0, 0, 1, 1, 2, 2]);
+        assert!(proche(ari, 1.0));
+        assert!(proche(nmi, 1.0));
+    }
+
+    #[test]
+    fn valeurs_connues() {
+        // Mêmes valeurs que adjusted_rand_score et normalized_mutual_info_score de scikit-learn
+        let (ari, nmi) = indices(&[0, 0, 1, 1, 2, 2], &[0, 0, 0, 1, 1, 1]);
+        assert!(proche(ari, 8.0 / 33.0));
+        let ln2 = 2f64.ln();
+        assert!(proche(nmi, (2.0 / 3.0 * ln2) / ((3f64.ln() + ln2) / 2.0)));
+    }
+
+    #[test]
+    fn un_seul_groupe() {
+        let (ari, nmi) = indices(&[0, 0, 0, 0], &[0, 0, 1, 1]);
+        assert!(proche(ari, 0.0));
+        assert!(proche(nmi, 0.0));
+    }
+}
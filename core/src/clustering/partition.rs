@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::clustering::{Tweet, distance};
+use crate::progress::Progress;
+use crate::{parallel, validation};
+
+// Méthodes de partitionnement, à la suite des méthodes de linkage (0=Average, 1=Complete, 2=Ward)
+pub const METHODE_KMEANS: usize = 3;
+pub const METHODE_CLARA: usize = 4;
+
+// Étapes comptées dans la progression de l'entraînement (itérations du k-means, échantillons de CLARA)
+const MAX_ITERATIONS: usize = 50;
+const N_ECHANTILLONS: usize = 5;
+
+pub fn est_partition(method: usize) -> bool {
+    method == METHODE_KMEANS || method == METHODE_CLARA
+}
+
+pub fn nb_etapes(method: usize) -> usize {
+    if method == METHODE_KMEANS { MAX_ITERATIONS } else { N_ECHANTILLONS }
+}
+
+/// Modèle issu d'un partitionnement en K groupes.
+/// Contrairement au dendrogramme il ne demande pas la matrice de distance complète :
+/// un nouveau tweet est rattaché au centre le plus proche, en O(K) comparaisons.
+#[derive(Serialize, Deserialize)]
+pub enum Partition {
+    // k-means sphérique sur la représentation TF-IDF (distance cosinus)
+    KMeans { espace: EspaceTfIdf, centroides: Vec<Vec<f32>> },
+    // k-médoïdes (CLARA) sur les ensembles de mots (distance de Jaccard)
+    KMedoides { medoides: Vec<Tweet> },
+}
+
+impl Partition {
+    /// Partitionne `tweets` en `k` groupes et renvoie le modèle avec le groupe de chaque tweet.
+    /// La graine fixe l'initialisation (négative = aléatoire).
+    pub fn entrainer(method: usize, tweets: &[Tweet], k: usize, graine: i64, progress: &Progress) -> Result<(Self, Vec<usize>), String> {
+        if k == 0 || tweets.len() < k {
+            return Err(format!("Pas assez de tweets ({}) pour K={}", tweets.len(), k));
+        }
+        let mut rng = validation::generateur(graine);
+
+        match method {
+            METHODE_KMEANS => {
+                let espace = EspaceTfIdf::new(tweets);
+                let vecteurs: Vec<Vec<(usize, f32)>> = tweets.iter().map(|t| espace.vecteur(&t.mots)).collect();
+                let (centroides, assignations) = kmeans(&vecteurs, espace.idf.len(), k, &mut rng, progress);
+                Ok((Partition::KMeans { espace, centroides }, assignations))
+            },
+            METHODE_CLARA => {
+                let (medoides, assignations) = clara(tweets, k, &mut rng, progress);
+                let medoides = medoides.iter().map(|&i| tweets[i].clone()).collect();
+                Ok((Partition::KMedoides { medoides }, assignations))
+            },
+            _ => Err(format!("Méthode de partitionnement inconnue ({})", method)),
+        }
+    }
+
+    pub fn nb_clusters(&self) -> usize {
+        match self {
+            Partition::KMeans { centroides, .. } => centroides.len(),
+            Partition::KMedoides { medoides } => medoides.len(),
+        }
+    }
+
+    // Groupe dont le centre est le plus proche du tweet
+    pub fn assigner(&self, tweet: &Tweet) -> usize {
+        match self {
+            Partition::KMeans { espace, centroides } => plus_proche_centroide(&espace.vecteur(&tweet.mots), centroides).0,
+            Partition::KMedoides { medoides } => plus_proche_medoide(tweet, medoides).0,
+        }
+    }
+}
+
+// Vocabulaire et poids IDF appris sur l'entraînement, les mots inconnus sont ignorés
+#[derive(Serialize, Deserialize)]
+pub struct EspaceTfIdf {
+    vocabulaire: HashMap<String, usize>,
+    idf: Vec<f32>,
+}
+
+impl EspaceTfIdf {
+    fn new(tweets: &[Tweet]) -> Self {
+        let mut vocabulaire = HashMap::new();
+        let mut frequences: Vec<usize> = Vec::new();
+        for tweet in tweets {
+            for mot in &tweet.mots {
+                let idx = *vocabulaire.entry(mot.clone()).or_insert_with(|| {
+                    frequences.push(0);
+                    frequences.len() - 1
+                });
+                frequences[idx] += 1;
+            }
+        }
+
+        // IDF lissé : un mot présent partout garde un poids de 1
+        let n = tweets.len() as f32;
+        let idf = frequences.iter().map(|&df| ((1.0 + n) / (1.0 + df as f32)).ln() + 1.0).collect();
+        Self { vocabulaire, idf }
+    }
+
+    // Vecteur creux normalisé (TF binaire, les mots d'un tweet sont un ensemble), trié par indice
+    fn vecteur(&self, mots: &HashSet<String>) -> Vec<(usize, f32)> {
+        let mut vecteur: Vec<(usize, f32)> = mots
+            .iter()
+            .filter_map(|mot| self.vocabulaire.get(mot).map(|&idx| (idx, self.idf[idx])))
+            .collect();
+        vecteur.sort_unstable_by_key(|&(idx, _)| idx);
+
+        let norme = vecteur.iter().map(|&(_, poids)| poids * poids).sum::<f32>().sqrt();
+        if norme > 0.0 {
+            for (_, poids) in vecteur.iter_mut() {
+                *poids /= norme;
+            }
+        }
+        vecteur
+    }
+}
+
+fn produit_scalaire(vecteur: &[(usize, f32)], centroide: &[f32]) -> f32 {
+    vecteur.iter().map(|&(idx, poids)| poids * centroide[idx]).sum()
+}
+
+// (groupe, similarité cosinus), le premier groupe l'emporte en cas d'égalité
+fn plus_proche_centroide(vecteur: &[(usize, f32)], centroides: &[Vec<f32>]) -> (usize, f32) {
+    let mut meilleur = (0, f32::MIN);
+    for (c, centroide) in centroides.iter().enumerate() {
+        let similarite = produit_scalaire(vecteur, centroide);
+        if similarite > meilleur.1 {
+            meilleur = (c, similarite);
+        }
+    }
+    meilleur
+}
+
+fn vers_dense(vecteur: &[(usize, f32)], dimension: usize) -> Vec<f32> {
+    let mut dense = vec![0.0; dimension];
+    for &(idx, poids) in vecteur {
+        dense[idx] = poids;
+    }
+    dense
+}
+
+/// k-means sphérique : les centroïdes sont renormalisés après chaque moyenne pour rester comparables par cosinus.
+/// Initialisation k-means++ puis itérations jusqu'à ce que plus aucun tweet ne change de groupe.
+/// Les centroïdes renvoyés sont ceux de la dernière assignation, même si MAX_ITERATIONS est atteint.
+fn kmeans(vecteurs: &[Vec<(usize, f32)>], dimension: usize, k: usize, rng: &mut StdRng, progress: &Progress) -> (Vec<Vec<f32>>, Vec<usize>) {
+    let n = vecteurs.len();
+
+    // k-means++ : chaque nouveau centre est tiré proportionnellement au carré de sa distance aux centres existants
+    let mut centroides = vec![vers_dense(&vecteurs[rng.random_range(0..n)], dimension)];
+    let mut distances = vec![f32::MAX; n];
+    while centroides.len() < k {
+        let dernier = centroides.last().unwrap();
+        parallel::executer(|| {
+            distances.par_iter_mut().zip(vecteurs.par_iter()).for_each(|(d, v)| {
+                *d = d.min((1.0 - produit_scalaire(v, dernier)).max(0.0));
+            });
+        });
+
+        let total: f64 = distances.iter().map(|&d| (d * d) as f64).sum();
+        let choisi = if total > 0.0 {
+            let mut seuil = rng.random::<f64>() * total;
+            distances
+                .iter()
+                .position(|&d| {
+                    seuil -= (d * d) as f64;
+                    seuil <= 0.0
+                })
+                .unwrap_or(n - 1)
+        } else {
+            rng.random_range(0..n)
+        };
+        centroides.push(vers_dense(&vecteurs[choisi], dimension));
+    }
+
+    let mut assignations = vec![usize::MAX; n];
+    for iteration in 0..MAX_ITERATIONS {
+        if progress.is_cancelled() {
+            break;
+        }
+
+        let plus_proches: Vec<(usize, f32)> = parallel::executer(|| {
+            vecteurs.par_iter().map(|v| plus_proche_centroide(v, &centroides)).collect()
+        });
+        let changements = plus_proches.iter().zip(&assignations).filter(|&(&(c, _), &a)| c != a).count();
+        for (a, &(c, _)) in assignations.iter_mut().zip(&plus_proches) {
+            *a = c;
+        }
+        progress.advance(1);
+        if changements == 0 || iteration + 1 == MAX_ITERATIONS {
+            break;
+        }
+
+        // Moyenne des vecteurs de chaque groupe
+        let mut sommes = vec![vec![0.0f32; dimension]; k];
+        let mut effectifs = vec![0usize; k];
+        for (v, &c) in vecteurs.iter().zip(&assignations) {
+            effectifs[c] += 1;
+            for &(idx, poids) in v {
+                sommes[c][idx] += poids;
+            }
+        }
+
+        // Un groupe vide repart du tweet le moins bien représenté par son centre
+        let mut mal_representes: Vec<usize> = (0..n).collect();
+        mal_representes.sort_by(|&a, &b| plus_proches[a].1.total_cmp(&plus_proches[b].1));
+        let mut candidats = mal_representes.into_iter();
+
+        for (c, somme) in sommes.iter_mut().enumerate() {
+            if effectifs[c] == 0 {
+                if let Some(i) = candidats.next() {
+                    *somme = vers_dense(&vecteurs[i], dimension);
+                }
+                continue;
+            }
+            let norme = somme.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norme > 0.0 {
+                for x in somme.iter_mut() {
+                    *x /= norme;
+                }
+            }
+        }
+        centroides = sommes;
+    }
+    if !progress.is_cancelled() {
+        progress.set_done(MAX_ITERATIONS);
+    }
+
+    (centroides, assignations)
+}
+
+// (indice du médoïde, distance)
+fn plus_proche_medoide(tweet: &Tweet, medoides: &[Tweet]) -> (usize, f64) {
+    let mut meilleur = (0, f64::MAX);
+    for (m, medoide) in medoides.iter().enumerate() {
+        let d = distance(tweet, medoide);
+        if d < meilleur.1 {
+            meilleur = (m, d);
+        }
+    }
+    meilleur
+}
+
+/// CLARA : PAM sur plusieurs échantillons, on garde les médoïdes de coût minimal sur l'ensemble des tweets.
+/// Le coût mémoire ne dépend que de la taille des échantillons (40 + 2K), pas du nombre de tweets.
+/// Renvoie les indices des médoïdes et le groupe de chaque tweet.
+fn clara(tweets: &[Tweet], k: usize, rng: &mut StdRng, progress: &Progress) -> (Vec<usize>, Vec<usize>) {
+    let n = tweets.len();
+    let taille = n.min(40 + 2 * k);
+
+    let mut meilleurs: Vec<usize> = Vec::new();
+    let mut meilleur_cout = f64::MAX;
+
+    for _ in 0..N_ECHANTILLONS {
+        if progress.is_cancelled() {
+            break;
+        }
+
+        // Les meilleurs médoïdes trouvés jusqu'ici font partie de l'échantillon suivant
+        let mut echantillon = meilleurs.clone();
+        for i in index::sample(rng, n, taille).into_iter() {
+            if echantillon.len() >= taille {
+                break;
+            }
+            if !echantillon.contains(&i) {
+                echantillon.push(i);
+            }
+        }
+
+        let membres: Vec<&Tweet> = echantillon.iter().map(|&i| &tweets[i]).collect();
+        let medoides: Vec<usize> = pam(&membres, k).into_iter().map(|m| echantillon[m]).collect();
+
+        let centres: Vec<Tweet> = medoides.iter().map(|&i| tweets[i].clone()).collect();
+        let cout: f64 = parallel::executer(|| tweets.par_iter().map(|t| plus_proche_medoide(t, &centres).1).sum());
+        if cout < meilleur_cout {
+            meilleur_cout = cout;
+            meilleurs = medoides;
+        }
+        progress.advance(1);
+
+        // Un seul échantillon suffit s'il couvre tous les tweets
+        if taille == n {
+            break;
+        }
+    }
+    if !progress.is_cancelled() {
+        progress.set_done(N_ECHANTILLONS);
+    }
+
+    let centres: Vec<Tweet> = meilleurs.iter().map(|&i| tweets[i].clone()).collect();
+    let assignations = parallel::executer(|| tweets.par_iter().map(|t| plus_proche_medoide(t, &centres).0).collect());
+    (meilleurs, assignations)
+}
+
+/// PAM (Partitioning Around Medoids) sur un échantillon : construction gloutonne puis échanges
+/// médoïde / non-médoïde tant que le coût total diminue. Renvoie les positions des médoïdes dans l'échantillon.
+fn pam(echantillon: &[&Tweet], k: usize) -> Vec<usize> {
+    let s = echantillon.len();
+    let distances: Vec<Vec<f64>> = parallel::executer(|| {
+        echantillon.par_iter().map(|a| echantillon.iter().map(|b| distance(a, b)).collect()).collect()
+    });
+
+    let cout = |medoides: &[usize]| -> f64 {
+        (0..s).map(|i| medoides.iter().map(|&m| distances[i][m]).fold(f64::MAX, f64::min)).sum()
+    };
+
+    // BUILD : on ajoute à chaque fois le tweet qui réduit le plus le coût
+    let mut medoides: Vec<usize> = Vec::with_capacity(k);
+    while medoides.len() < k {
+        let candidat = (0..s)
+            .filter(|c| !medoides.contains(c))
+            .map(|c| {
+                let mut essai = medoides.clone();
+                essai.push(c);
+                (c, cout(&essai))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match candidat {
+            Some((c, _)) => medoides.push(c),
+            None => break,
+        }
+    }
+
+    // SWAP : meilleur échange à chaque tour, arrêt quand plus rien n'améliore
+    let mut cout_actuel = cout(&medoides);
+    loop {
+        let mut meilleur_echange = None;
+        for position in 0..medoides.len() {
+            for candidat in (0..s).filter(|c| !medoides.contains(c)) {
+                let mut essai = medoides.clone();
+                essai[position] = candidat;
+                let cout_essai = cout(&essai);
+                if cout_essai < cout_actuel - 1e-12 {
+                    cout_actuel = cout_essai;
+                    meilleur_echange = Some((position, candidat));
+                }
+            }
+        }
+        match meilleur_echange {
+            Some((position, candidat)) => medoides[position] = candidat,
+            None => break,
+        }
+    }
+    medoides
+}
+
+// Étiquette majoritaire de chaque groupe, neutre pour un groupe vide (égalité : plus petite étiquette)
+pub fn etiquettes_partition(assignations: &[usize], tweets: &[Tweet], k: usize) -> Vec<i32> {
+    let mut votes: Vec<BTreeMap<i32, usize>> = vec![BTreeMap::new(); k];
+    for (&c, tweet) in assignations.iter().zip(tweets) {
+        *votes[c].entry(tweet.label).or_default() += 1;
+    }
+    votes
+        .iter()
+        .map(|v| {
+            v.iter()
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+                .map(|(&label, _)| label)
+                .unwrap_or(2)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trois groupes de 6 tweets identiques, sans aucun mot en commun d'un groupe à l'autre :
+    // k-means++ ne peut pas tirer deux centres dans le même groupe
+    fn tweets() -> Vec<Tweet> {
+        let groupes = [("triste nul pluie", 0), ("train gare horaire", 2), ("joie soleil bravo", 4)];
+        (0..18)
+            .map(|i| {
+                let (mots, label) = groupes[i % 3];
+                Tweet::new(i, mots.to_string(), label)
+            })
+            .collect()
+    }
+
+    // Les tweets d'un même groupe sont ensemble, ceux de groupes différents séparés
+    fn retrouve_les_groupes(assignations: &[usize]) -> bool {
+        (0..18).all(|i| (0..18).all(|j| (assignations[i] == assignations[j]) == (i % 3 == j % 3)))
+    }
+
+    #[test]
+    fn kmeans_separe_les_groupes() {
+        let tweets = tweets();
+        let (modele, assignations) = Partition::entrainer(METHODE_KMEANS, &tweets, 3, 5, &Progress::default()).unwrap();
+        assert!(retrouve_les_groupes(&assignations), "{:?}", assignations);
+        assert_eq!(modele.nb_clusters(), 3);
+        // Les centroïdes renvoyés redonnent les mêmes groupes
+        for (tweet, &groupe) in tweets.iter().zip(&assignations) {
+            assert_eq!(modele.assigner(tweet), groupe);
+        }
+        assert_eq!(etiquettes_partition(&assignations, &tweets, 3).iter().filter(|&&l| l == 2).count(), 1);
+    }
+
+    #[test]
+    fn clara_separe_les_groupes() {
+        let tweets = tweets();
+        let (modele, assignations) = Partition::entrainer(METHODE_CLARA, &tweets, 3, 5, &Progress::default()).unwrap();
+        assert!(retrouve_les_groupes(&assignations), "{:?}", assignations);
+        for (tweet, &groupe) in tweets.iter().zip(&assignations) {
+            assert_eq!(modele.assigner(tweet), groupe);
+        }
+    }
+
+    #[test]
+    fn meme_graine_meme_partition() {
+        let tweets = tweets();
+        let entrainer = |methode| Partition::entrainer(methode, &tweets, 2, 9, &Progress::default()).unwrap().1;
+        assert_eq!(entrainer(METHODE_KMEANS), entrainer(METHODE_KMEANS));
+        assert_eq!(entrainer(METHODE_CLARA), entrainer(METHODE_CLARA));
+    }
+
+    #[test]
+    fn annulation_ne_termine_pas_la_progression() {
+        let progress = Progress::default();
+        progress.set_total(MAX_ITERATIONS);
+        progress.cancel();
+        let _ = Partition::entrainer(METHODE_KMEANS, &tweets(), 3, 5, &progress);
+        assert_eq!(progress.state(), (0, MAX_ITERATIONS));
+    }
+
+    #[test]
+    fn k_invalide() {
+        assert!(Partition::entrainer(METHODE_KMEANS, &tweets(), 0, 5, &Progress::default()).is_err());
+        assert!(Partition::entrainer(METHODE_CLARA, &tweets(), 19, 5, &Progress::default()).is_err());
+    }
+}
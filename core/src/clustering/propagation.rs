@@ -0,0 +1,242 @@
+use std::error::Error;
+
+use rayon::prelude::*;
+
+use crate::clustering::condensed::{identifiants, jaccard};
+use crate::clustering::Tweet;
+use crate::parallel;
+use crate::validation;
+
+pub use crate::validation::NON_ANNOTE;
+
+// Variation maximale des scores en dessous de laquelle la propagation est considérée comme convergée
+const TOLERANCE: f64 = 1e-6;
+
+// 0 = propagation (Zhu & Ghahramani, 2002), 1 = diffusion (Zhou et al., 2004), même numérotation que clustering_propagate
+#[derive(Debug, Clone, Copy)]
+pub enum Mode {
+    // Les tweets annotés gardent leur étiquette à chaque itération
+    Propagation,
+    // Les tweets annotés ne sont qu'un a priori de poids 1 - alpha, leur étiquette peut être corrigée
+    Diffusion { alpha: f64 },
+}
+
+impl Mode {
+    pub fn depuis_numero(mode: i64, alpha: f64) -> Result<Self, String> {
+        match mode {
+            0 => Ok(Mode::Propagation),
+            1 if alpha > 0.0 && alpha < 1.0 => Ok(Mode::Diffusion { alpha }),
+            1 => Err(format!("Alpha doit être strictement entre 0 et 1 ({})", alpha)),
+            _ => Err(format!("Mode de propagation inconnu ({})", mode)),
+        }
+    }
+}
+
+pub struct ResultatPropagation {
+    // Étiquette propagée de chaque tweet, dans l'ordre du fichier
+    pub labels: Vec<i32>,
+    // Part du score de l'étiquette retenue dans le score total du tweet (0 si aucun tweet annoté n'est atteint)
+    pub confiances: Vec<f64>,
+    pub nb_annotes: usize,
+    pub iterations: usize,
+    pub convergence: bool,
+}
+
+/// Charge un CSV `label,texte` dont seuls quelques tweets sont annotés (validation::charger_donnees_partielles),
+/// comme wip_annoted_avengers_150.csv
+pub fn charger_tweets_partiels(chemin: &str) -> Result<Vec<Tweet>, Box<dyn Error>> {
+    Ok(Tweet::numeroter(validation::charger_donnees_partielles(chemin)?))
+}
+
+// Charge le fichier puis propage ses étiquettes, renvoie les tweets avec le résultat
+pub fn propager_fichier(chemin: &str, mode: Mode, k: usize, max_iterations: usize) -> Result<(Vec<Tweet>, ResultatPropagation), Box<dyn Error>> {
+    let tweets = charger_tweets_partiels(chemin)?;
+    let resultat = propager(&tweets, mode, k, max_iterations)?;
+    Ok((tweets, resultat))
+}
+
+/// Graphe des `k` plus proches voisins (distance de Jaccard du clustering), symétrisé :
+/// i et j sont reliés si l'un est parmi les voisins de l'autre, avec pour poids la similarité 1 - distance.
+/// Deux tweets sans mot commun ne sont jamais reliés.
+fn graphe_voisins(tweets: &[Tweet], k: usize) -> Vec<Vec<(usize, f64)>> {
+    let jetons = identifiants(tweets);
+    let n = tweets.len();
+    let voisins: Vec<Vec<(usize, f64)>> = parallel::executer(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let mut candidats: Vec<(usize, f64)> = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| (j, 1.0 - jaccard(&jetons[i], &jetons[j])))
+                    .filter(|&(_, s)| s > 0.0)
+                    .collect();
+                if candidats.len() > k {
+                    candidats.select_nth_unstable_by(k, |a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+                    candidats.truncate(k);
+                }
+                candidats
+            })
+            .collect()
+    });
+
+    let mut graphe = voisins.clone();
+    for (i, liste) in voisins.iter().enumerate() {
+        for &(j, s) in liste {
+            graphe[j].push((i, s));
+        }
+    }
+    for liste in &mut graphe {
+        liste.sort_unstable_by_key(|&(j, _)| j);
+        liste.dedup_by_key(|&mut (j, _)| j);
+    }
+    graphe
+}
+
+/// Propage les étiquettes des tweets annotés sur le graphe des `k` plus proches voisins jusqu'à convergence
+/// ou `max_iterations` itérations. Les scores par classe suivent l'ordre de validation::CLASSES ;
+/// à égalité, la plus petite étiquette l'emporte. Un tweet qu'aucun tweet annoté n'atteint reçoit
+/// l'étiquette majoritaire des tweets annotés avec une confiance nulle.
+pub fn propager(tweets: &[Tweet], mode: Mode, k: usize, max_iterations: usize) -> Result<ResultatPropagation, String> {
+    let n = tweets.len();
+    let a_priori: Vec<[f64; 3]> = tweets
+        .iter()
+        .map(|t| {
+            let mut ligne = [0.0; 3];
+            if let Some(c) = validation::index_classe(t.label) {
+                ligne[c] = 1.0;
+            }
+            ligne
+        })
+        .collect();
+    let nb_annotes = tweets.iter().filter(|t| t.label != NON_ANNOTE).count();
+    if nb_annotes == 0 {
+        return Err("Aucun tweet annoté à propager".into());
+    }
+
+    let graphe = graphe_voisins(tweets, k.max(1));
+    let degres: Vec<f64> = graphe.iter().map(|l| l.iter().map(|&(_, s)| s).sum()).collect();
+
+    let mut scores = a_priori.clone();
+    let mut iterations = 0;
+    let mut convergence = false;
+    while iterations < max_iterations && !convergence {
+        iterations += 1;
+        let suivants: Vec<[f64; 3]> = parallel::executer(|| {
+            (0..n)
+                .into_par_iter()
+                .map(|i| match mode {
+                    Mode::Propagation if tweets[i].label != NON_ANNOTE => a_priori[i],
+                    // F = D^-1 W F
+                    Mode::Propagation => {
+                        let mut ligne = [0.0; 3];
+                        if degres[i] > 0.0 {
+                            for &(j, s) in &graphe[i] {
+                                for c in 0..3 {
+                                    ligne[c] += s / degres[i] * scores[j][c];
+                                }
+                            }
+                        }
+                        ligne
+                    },
+                    // F = alpha D^-1/2 W D^-1/2 F + (1 - alpha) Y
+                    Mode::Diffusion { alpha } => {
+                        let mut ligne = a_priori[i].map(|y| (1.0 - alpha) * y);
+                        for &(j, s) in &graphe[i] {
+                            let poids = alpha * s / (degres[i] * degres[j]).sqrt();
+                            for c in 0..3 {
+                                ligne[c] += poids * scores[j][c];
+                            }
+                        }
+                        ligne
+                    },
+                })
+                .collect()
+        });
+
+        let variation = scores
+            .iter()
+            .zip(&suivants)
+            .flat_map(|(a, b)| a.iter().zip(b).map(|(x, y)| (x - y).abs()))
+            .fold(0.0, f64::max);
+        convergence = variation < TOLERANCE;
+        scores = suivants;
+    }
+
+    // Égalité : la plus petite étiquette, comme cut::resumer
+    let mut comptes = [0usize; 3];
+    for ligne in &a_priori {
+        if let Some(c) = ligne.iter().position(|&y| y > 0.0) {
+            comptes[c] += 1;
+        }
+    }
+    let majoritaire = (0..3).rev().max_by_key(|&c| comptes[c]).unwrap_or(1);
+
+    let (labels, confiances) = scores
+        .iter()
+        .map(|ligne| {
+            let total: f64 = ligne.iter().sum();
+            if total <= 0.0 {
+                return (validation::CLASSES[majoritaire], 0.0);
+            }
+            let meilleur = (0..3).rev().max_by(|&a, &b| ligne[a].total_cmp(&ligne[b])).unwrap_or(majoritaire);
+            (validation::CLASSES[meilleur], ligne[meilleur] / total)
+        })
+        .unzip();
+
+    Ok(ResultatPropagation { labels, confiances, nb_annotes, iterations, convergence })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deux composantes sans mot commun, chacune avec un seul tweet annoté, et un tweet isolé
+    fn deux_composantes() -> Vec<Tweet> {
+        [
+            (0, "chat chien maison"),
+            (NON_ANNOTE, "chat chien"),
+            (NON_ANNOTE, "chien maison jardin"),
+            (NON_ANNOTE, "jardin maison"),
+            (4, "soleil plage mer"),
+            (NON_ANNOTE, "plage mer"),
+            (NON_ANNOTE, "soleil sable plage"),
+            (NON_ANNOTE, "isole"),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(id, &(label, texte))| Tweet::new(id, texte.to_string(), label))
+        .collect()
+    }
+
+    #[test]
+    fn chaque_composante_prend_son_etiquette() {
+        let tweets = deux_composantes();
+        for mode in [Mode::Propagation, Mode::Diffusion { alpha: 0.9 }] {
+            let resultat = propager(&tweets, mode, 3, 100).unwrap();
+            assert_eq!(resultat.labels, vec![0, 0, 0, 0, 4, 4, 4, 0], "{:?}", mode);
+            assert_eq!(resultat.nb_annotes, 2);
+            assert!(resultat.convergence);
+            assert!(resultat.confiances[..7].iter().all(|&c| c > 0.99), "{:?}", resultat.confiances);
+            // Le tweet isolé reçoit l'étiquette majoritaire (égalité : la plus petite) sans confiance
+            assert_eq!(resultat.confiances[7], 0.0);
+        }
+    }
+
+    #[test]
+    fn sans_annotation_refuse() {
+        let tweets: Vec<Tweet> = deux_composantes()
+            .into_iter()
+            .map(|t| Tweet::new(t.id, t.contenu, NON_ANNOTE))
+            .collect();
+        assert!(propager(&tweets, Mode::Propagation, 3, 100).is_err());
+    }
+
+    #[test]
+    fn numero_de_mode() {
+        assert!(matches!(Mode::depuis_numero(0, 0.0), Ok(Mode::Propagation)));
+        assert!(matches!(Mode::depuis_numero(1, 0.5), Ok(Mode::Diffusion { alpha }) if alpha == 0.5));
+        assert!(Mode::depuis_numero(1, 1.0).is_err());
+        assert!(Mode::depuis_numero(1, 0.0).is_err());
+        assert!(Mode::depuis_numero(2, 0.5).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use rayon::prelude::*;
+
+use crate::clustering::condensed::{self, MatriceCondensee};
+use crate::clustering::{Tweet, couper_arbre, etiquettes_clusters, methode_linkage, plus_proche_voisin};
+use crate::progress::Progress;
+use crate::{parallel, validation};
+
+// Un point de la courbe métrique / K renvoyée à Godot
+pub struct PointCourbe {
+    pub k: usize,
+    pub methode: usize,
+    pub exactitude: f64,
+    pub f1_macro: f64,
+}
+
+// Average, Complete, Ward (cf. methode_linkage)
+const METHODES: [usize; 3] = [0, 1, 2];
+
+/// Balaye le nombre de clusters K dans [k_min, k_max] pour chaque méthode de linkage par validation croisée stratifiée.
+/// Le dendrogramme et le plus proche voisin de chaque tweet de test ne dépendent pas de K :
+/// ils sont calculés une fois par pli et par méthode, seul le découpage est refait pour chaque K.
+pub fn balayer_k(
+    tweets: &[Tweet],
+    k_min: usize,
+    k_max: usize,
+    n_plis: usize,
+    graine: i64,
+) -> Result<Vec<PointCourbe>, String> {
+    let k_min = k_min.max(1);
+    if k_max < k_min {
+        return Err(format!("Intervalle de K invalide ({} > {})", k_min, k_max));
+    }
+    if tweets.len() < n_plis.max(2) {
+        return Err(format!("Pas assez de tweets ({}) pour {} plis", tweets.len(), n_plis));
+    }
+
+    let mut rng = validation::generateur(graine);
+    let etiquettes: Vec<i32> = tweets.iter().map(|t| t.label).collect();
+    let plis = validation::plis_stratifies(&etiquettes, n_plis, &mut rng);
+
+    let taille_min_train = plis.iter().map(|pli| tweets.len() - pli.len()).min().unwrap_or(0);
+    let k_max = k_max.min(taille_min_train);
+    if k_max < k_min {
+        return Err(format!("K min ({}) dépasse la taille de l'entraînement ({})", k_min, taille_min_train));
+    }
+
+    // matrices[k - k_min][méthode]
+    let mut matrices = vec![[[[0; 3]; 3]; METHODES.len()]; k_max - k_min + 1];
+
+    for i in 0..plis.len() {
+        let (idx_train, idx_test) = validation::separer_pli(&plis, i);
+
+        // Ré-indexer les ID du train pour correspondre à leur position dans le vecteur (pour Kodama)
+        let mut train: Vec<Tweet> = idx_train.iter().map(|&j| tweets[j].clone()).collect();
+        for (idx, t) in train.iter_mut().enumerate() {
+            t.id = idx;
+        }
+        let n_train = train.len();
+
+        // La matrice et sa copie de travail, que chaque linkage modifie
+        condensed::verifier_budget(n_train, 2 * condensed::octets_par_distance())?;
+        let condensed = MatriceCondensee::calculer(&train, &Progress::default())?;
+        let mut travail = condensed.clone();
+        let voisins: Vec<(usize, i32)> = parallel::executer(|| {
+            idx_test
+                .par_iter()
+                .map(|&j| (plus_proche_voisin(&tweets[j], &train), tweets[j].label))
+                .collect()
+        });
+
+        for (m, &methode) in METHODES.iter().enumerate() {
+            if m > 0 {
+                travail.recopier(&condensed);
+            }
+            let dendrogram = travail.linkage_en_place(n_train, methode_linkage(methode));
+
+            for k in k_min..=k_max {
+                let mut uf = couper_arbre(&dendrogram, n_train, k);
+                let cluster_labels = etiquettes_clusters(&mut uf, &train);
+
+                for &(voisin, reel) in &voisins {
+                    let root = uf.find(voisin);
+                    let estime = *cluster_labels.get(&root).unwrap_or(&2);
+                    validation::ajouter_prediction(&mut matrices[k - k_min][m], reel, estime);
+                }
+            }
+        }
+    }
+
+    let mut courbe = Vec::with_capacity(matrices.len() * METHODES.len());
+    for (offset, par_methode) in matrices.iter().enumerate() {
+        for (m, matrice) in par_methode.iter().enumerate() {
+            courbe.push(PointCourbe {
+                k: k_min + offset,
+                methode: METHODES[m],
+                exactitude: validation::exactitude(matrice),
+                f1_macro: validation::f1_macro(matrice),
+            });
+        }
+    }
+    Ok(courbe)
+}
+
+// Meilleur réglage : F1 macro le plus haut, puis exactitude, puis le plus petit K
+pub fn meilleur_point(courbe: &[PointCourbe]) -> Option<&PointCourbe> {
+    courbe.iter().max_by(|a, b| {
+        a.f1_macro
+            .total_cmp(&b.f1_macro)
+            .then(a.exactitude.total_cmp(&b.exactitude))
+            .then(b.k.cmp(&a.k))
+    })
+}
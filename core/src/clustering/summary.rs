@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use rayon::prelude::*;
+
+use crate::clustering::cut::ResumeCluster;
+use crate::clustering::dendrogram_svg::echapper;
+use crate::clustering::{Tweet, distance};
+use crate::parallel;
+
+// Nombre maximal de tweets comparés pour chercher le médoïde d'un grand cluster (coût quadratique)
+const MAX_CANDIDATS_MEDOIDE: usize = 300;
+// Poids total de l'a priori informatif du log-odds
+const ALPHA_PRIOR: f64 = 1000.0;
+// Un mot vu moins souvent dans le cluster n'est pas retenu comme mot-clé
+const MIN_OCCURRENCES: usize = 2;
+const LONGUEUR_EXTRAIT: usize = 40;
+
+// Ce qui décrit le contenu d'un cluster, en complément de ResumeCluster
+pub struct Synthese {
+    // Mots les plus caractéristiques et leur score (z-score du log-odds), du plus au moins distinctif
+    pub mots_cles: Vec<(String, f64)>,
+    // Indice du tweet le plus central du cluster, None pour un cluster vide
+    pub medoide: Option<usize>,
+}
+
+/// Résume chaque cluster (`groupes` numérotés de 0 à nb_groupes - 1, comme après cut::resumer).
+/// Mots-clés : log-odds avec a priori de Dirichlet informatif (Monroe et al., 2008) du cluster contre le reste
+/// du corpus, l'a priori étant la fréquence du mot dans tout le corpus. Les tweets comptent comme des ensembles de mots.
+pub fn synthetiser(tweets: &[Tweet], groupes: &[usize], nb_groupes: usize, nb_mots: usize) -> Vec<Synthese> {
+    let mut membres: Vec<Vec<usize>> = vec![Vec::new(); nb_groupes];
+    for (idx, &g) in groupes.iter().enumerate() {
+        membres[g].push(idx);
+    }
+
+    // Occurrences de chaque mot dans tout le corpus et dans chaque cluster
+    let mut corpus: HashMap<&str, usize> = HashMap::new();
+    let mut par_groupe: Vec<HashMap<&str, usize>> = vec![HashMap::new(); nb_groupes];
+    for (tweet, &g) in tweets.iter().zip(groupes) {
+        for mot in &tweet.mots {
+            *corpus.entry(mot.as_str()).or_default() += 1;
+            *par_groupe[g].entry(mot.as_str()).or_default() += 1;
+        }
+    }
+    let total_corpus: usize = corpus.values().sum();
+
+    parallel::executer(|| {
+        membres
+            .par_iter()
+            .zip(par_groupe.par_iter())
+            .map(|(membres, comptes)| Synthese {
+                mots_cles: mots_cles(comptes, &corpus, total_corpus, nb_mots),
+                medoide: medoide(tweets, membres),
+            })
+            .collect()
+    })
+}
+
+fn mots_cles(comptes: &HashMap<&str, usize>, corpus: &HashMap<&str, usize>, total_corpus: usize, nb_mots: usize) -> Vec<(String, f64)> {
+    let total_groupe: usize = comptes.values().sum();
+    let total_reste = total_corpus - total_groupe;
+    let (n_i, n_j) = (total_groupe as f64, total_reste as f64);
+
+    let mut scores: Vec<(String, f64)> = comptes
+        .iter()
+        .filter(|&(_, &y_i)| y_i >= MIN_OCCURRENCES)
+        .map(|(&mot, &y_i)| {
+            let y_w = corpus[mot];
+            let alpha = ALPHA_PRIOR * y_w as f64 / total_corpus as f64;
+            let y_i = y_i as f64;
+            let y_j = (y_w as f64) - y_i;
+
+            let log_odds_i = ((y_i + alpha) / (n_i + ALPHA_PRIOR - y_i - alpha)).ln();
+            let log_odds_j = ((y_j + alpha) / (n_j + ALPHA_PRIOR - y_j - alpha)).ln();
+            let variance = 1.0 / (y_i + alpha) + 1.0 / (y_j + alpha);
+            (mot.to_string(), (log_odds_i - log_odds_j) / variance.sqrt())
+        })
+        .collect();
+
+    // Égalité de score : ordre alphabétique pour un résultat stable
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scores.truncate(nb_mots);
+    scores
+}
+
+// Tweet dont la distance de Jaccard moyenne aux autres est minimale.
+// Pour un grand cluster, candidats et références sont pris à intervalles réguliers parmi les membres.
+fn medoide(tweets: &[Tweet], membres: &[usize]) -> Option<usize> {
+    let pas = membres.len().div_ceil(MAX_CANDIDATS_MEDOIDE).max(1);
+    let candidats: Vec<usize> = membres.iter().step_by(pas).copied().collect();
+
+    candidats
+        .iter()
+        .map(|&i| (i, candidats.iter().map(|&j| distance(&tweets[i], &tweets[j])).sum::<f64>()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Une colonne par cluster : barre empilée selon les étiquettes réelles (même code couleur que les feuilles
+/// du dendrogramme), mots-clés en dessous et tweet médoïde en infobulle. Les clusters vides ne sont pas dessinés.
+pub fn generate_summary_svg(resumes: &[ResumeCluster], syntheses: &[Synthese], tweets: &[Tweet]) -> String {
+    let largeur_colonne = 110.0;
+    let margin = 20.0;
+    let hauteur_barres = 380.0;
+    let zone_mots = 150.0;
+    let width = (2.0 * margin + resumes.len() as f64 * largeur_colonne).max(600.0);
+    let height = 2.0 * margin + hauteur_barres + zone_mots;
+    let base_y = margin + hauteur_barres;
+    let max_taille = resumes.iter().map(|r| r.taille).max().unwrap_or(0).max(1) as f64;
+    let bar_width = largeur_colonne * 0.6;
+
+    let mut svg = String::with_capacity(512 * (resumes.len() + 1));
+    let _ = write!(
+        &mut svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}"><rect width="100%" height="100%" fill="#202020"/><style>text {{ fill: #e0e0e0; font-family: sans-serif; font-size: 11px; }}</style>"##,
+        width, height, width, height
+    );
+
+    for (resume, synthese) in resumes.iter().zip(syntheses) {
+        let Some(medoide) = synthese.medoide else { continue };
+        let centre = margin + (resume.id as f64 + 0.5) * largeur_colonne;
+        let x = centre - bar_width / 2.0;
+
+        let medoide: String = tweets[medoide].contenu.chars().take(LONGUEUR_EXTRAIT * 4).collect();
+        let _ = write!(
+            &mut svg,
+            r#"<g><title>Cluster {} - {} tweets - pureté {:.0}% - médoïde : {}</title>"#,
+            resume.id,
+            resume.taille,
+            resume.purete * 100.0,
+            echapper(&medoide)
+        );
+
+        let mut y = base_y;
+        for (idx, color) in [(0, "#f44336"), (1, "#9e9e9e"), (2, "#4caf50")] {
+            let h = resume.comptes[idx] as f64 / max_taille * hauteur_barres;
+            if h > 0.0 {
+                y -= h;
+                let _ = write!(&mut svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" />"#, x, y, bar_width, h, color);
+            }
+        }
+        let _ = write!(&mut svg, r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#, centre, y - 5.0, resume.taille);
+
+        let _ = write!(&mut svg, r#"<text x="{}" y="{}" text-anchor="middle">#{}</text>"#, centre, base_y + 16.0, resume.id);
+        for (ligne, (mot, _)) in synthese.mots_cles.iter().take(8).enumerate() {
+            let mot: String = mot.chars().take(LONGUEUR_EXTRAIT / 2).collect();
+            let _ = write!(
+                &mut svg,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                centre,
+                base_y + 34.0 + ligne as f64 * 14.0,
+                echapper(&mot)
+            );
+        }
+        let _ = write!(&mut svg, "</g>");
+    }
+
+    let _ = write!(&mut svg, "</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweets() -> Vec<Tweet> {
+        ["pluie triste nul", "pluie triste", "pluie froid triste", "soleil joie", "soleil joie bravo", "soleil plage joie"]
+            .iter()
+            .enumerate()
+            .map(|(id, texte)| Tweet::new(id, texte.to_string(), if id < 3 { 0 } else { 4 }))
+            .collect()
+    }
+
+    #[test]
+    fn mots_cles_et_medoides() {
+        let tweets = tweets();
+        let syntheses = synthetiser(&tweets, &[0, 0, 0, 1, 1, 1], 2, 2);
+        let mots = |g: usize| syntheses[g].mots_cles.iter().map(|(mot, _)| mot.as_str()).collect::<Vec<_>>();
+        assert_eq!(mots(0), vec!["pluie", "triste"]);
+        assert_eq!(mots(1), vec!["joie", "soleil"]);
+        // Le tweet qui partage le plus de mots avec les autres du cluster
+        assert_eq!(syntheses[0].medoide, Some(1));
+        assert_eq!(syntheses[1].medoide, Some(3));
+    }
+
+    #[test]
+    fn cluster_vide_sans_medoide() {
+        let tweets = tweets();
+        let groupes = [0, 0, 0, 2, 2, 2];
+        let syntheses = synthetiser(&tweets, &groupes, 3, 4);
+        assert_eq!(syntheses[1].medoide, None);
+        assert!(syntheses[1].mots_cles.is_empty());
+
+        // Seuls les deux clusters non vides sont dessinés
+        let resumes: Vec<ResumeCluster> = [3, 0, 3]
+            .iter()
+            .enumerate()
+            .map(|(id, &taille)| ResumeCluster { id, taille, label: 2, purete: 1.0, comptes: [0, 0, taille] })
+            .collect();
+        let svg = generate_summary_svg(&resumes, &syntheses, &tweets);
+        assert_eq!(svg.matches("<g>").count(), 2);
+        assert!(!svg.contains("Cluster 1 "));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod encoding;
 pub mod cols_sniffer;
-pub mod transform;
\ No newline at end of file
+pub mod transform;
+pub mod table;
@@ -1,10 +1,8 @@
 use std::fs::File;
 use std::io::{Seek, SeekFrom};
-use std::usize;
 
 use csv::ReaderBuilder;
 use csv_sniffer::Sniffer;
-use godot::global::godot_print;
 
 use crate::csv_ext::cols_sniffer::error::AutoColumnsError;
 use crate::csv_ext::transform::records_to_vec2d;
@@ -0,0 +1,3 @@
+pub static DATA_TARGET_HEADERS: &[&str] = &["tweet", "message", "content", "data", "tweet_content"];
+pub static RATING_TARGET_HEADERS: &[&str] = &["rating", "polarity", "grade", "positivity"];
+pub static TWEET_MAX_CHARS: usize = 280;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use crate::csv_ext::cols_sniffer::ColsSniffer;
 
 impl ColsSniffer {
-    pub(super) fn bytes_is_rating(bytes: &[u8]) -> bool {
+    pub fn bytes_is_rating(bytes: &[u8]) -> bool {
         let mut bytes_iter = bytes.iter();
         
         // Can't be empty
@@ -23,16 +23,13 @@ impl ColsSniffer {
             None => return false,
         };
         
-        if let Some(_) = bytes_iter.next() {return false;}
+        if bytes_iter.next().is_some() {return false;}
     
         // check the three bytes are of format [",x,"]
-        return *first_byte == b'"' && Self::byte_is_rating(*second_byte) && *third_byte == b'"';
+        *first_byte == b'"' && Self::byte_is_rating(*second_byte) && *third_byte == b'"'
     }
     
-    pub(super) fn byte_is_rating(byte: u8) -> bool {
-        match byte {
-            b'0' | b'2' | b'4' => true,
-            _ => false,
-        }
+    pub fn byte_is_rating(byte: u8) -> bool {
+        matches!(byte, b'0' | b'2' | b'4')
     }
 }
\ No newline at end of file
@@ -5,7 +5,7 @@ use crate::csv_ext::cols_sniffer::error::AutoColumnsError;
 use crate::csv_ext::cols_sniffer::config;
 
 impl ColsSniffer {
-    pub(super) fn sniff_columns_from_headers(
+    pub fn sniff_columns_from_headers(
         headers: &StringRecord
     ) -> Result<AutoColumns, AutoColumnsError> {
         let mut cols = AutoColumns {data_column: 0, rating_column: 0};
@@ -45,6 +45,6 @@ impl ColsSniffer {
             return Err(AutoColumnsError::NoDataFound { rating_column: cols.rating_column })
         }
     
-        return Err(AutoColumnsError::NoColumnFound);
+        Err(AutoColumnsError::NoColumnFound)
     }
 }
\ No newline at end of file
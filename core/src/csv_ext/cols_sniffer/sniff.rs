@@ -1,9 +1,8 @@
-use godot::global::godot_print;
 
 use crate::csv_ext::cols_sniffer::{AutoColumns, AutoColumnsError, ColsSniffer};
 
 impl ColsSniffer {
-    pub(super) fn sniff_columns_with_err(
+    pub fn sniff_columns_with_err(
         veced_records: &mut [Vec<Vec<u8>>],
         error: AutoColumnsError
     ) -> Result<AutoColumns, AutoColumnsError> {
@@ -18,7 +17,7 @@ impl ColsSniffer {
         }
     }
 
-    pub(super) fn sniff_columns_from_vec2d(veced_records: &mut [Vec<Vec<u8>>]) -> Result<AutoColumns, AutoColumnsError> {
+    pub fn sniff_columns_from_vec2d(veced_records: &mut [Vec<Vec<u8>>]) -> Result<AutoColumns, AutoColumnsError> {
         let data_column = Self::sniff_data(veced_records);
         let rating_column = Self::sniff_rating(veced_records);
     
@@ -35,6 +34,6 @@ impl ColsSniffer {
             return Err(AutoColumnsError::NoDataFound { rating_column });
         }
     
-        return Err(AutoColumnsError::NoColumnFound);
+        Err(AutoColumnsError::NoColumnFound)
     }
 }
\ No newline at end of file
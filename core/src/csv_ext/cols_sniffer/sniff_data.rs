@@ -4,7 +4,7 @@ use crate::csv_ext::transform::to_string_size;
 use crate::csv_ext::cols_sniffer::config;
 
 impl ColsSniffer {
-    pub(super) fn sniff_data_with_rating(
+    pub fn sniff_data_with_rating(
         veced_records: &mut [Vec<Vec<u8>>],
         rating_column: usize
     ) -> Result<AutoColumns, AutoColumnsError> {
@@ -17,7 +17,7 @@ impl ColsSniffer {
         }
     }
 
-    pub(super) fn sniff_data(
+    pub fn sniff_data(
         veced_records: &mut [Vec<Vec<u8>>]
     ) -> Option<usize> {
         let sizes = to_string_size(veced_records);
@@ -41,8 +41,8 @@ impl ColsSniffer {
                     if length > config::TWEET_MAX_CHARS {Err(())}
                     else {Ok(acc + length)}
                 })
-                .and_then(|sum| Ok(sum/rows))
-                .and_then(|avg| Ok(config::TWEET_MAX_CHARS - avg))
+                .map(|sum| sum/rows)
+                .map(|avg| config::TWEET_MAX_CHARS - avg)
                 .unwrap_or(usize::MAX); 
                 // Either the averrage, or usize::MAX if any thing was too big to be a tweet
     
@@ -1,11 +1,11 @@
 use crate::csv_ext::cols_sniffer::{AutoColumns, ColsSniffer, error::AutoColumnsError};
 
 impl ColsSniffer {
-    pub(super) fn sniff_rating_with_data(
+    pub fn sniff_rating_with_data(
         veced_records: &mut [Vec<Vec<u8>>],
         data_column: usize
     ) -> Result<AutoColumns, AutoColumnsError> {
-        match Self::sniff_rating(&veced_records) {
+        match Self::sniff_rating(veced_records) {
             Some(rating_column) =>
                 Ok(AutoColumns {data_column, rating_column}),
             
@@ -16,7 +16,7 @@ impl ColsSniffer {
 
     // Tries to infer which column contains the rating.
     // That is, a column where all content is always either 0, 2 or 4.
-    pub(super) fn sniff_rating(records: &[Vec<Vec<u8>>]) -> Option<usize> {
+    pub fn sniff_rating(records: &[Vec<Vec<u8>>]) -> Option<usize> {
         records
             .iter()
             .enumerate()
@@ -27,6 +27,6 @@ impl ColsSniffer {
                         Self::bytes_is_rating(field)
                     })
             })
-            .and_then(|(i, _)| Some(i))
+            .map(|(i, _)| i)
     }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use csv::{ByteRecord, Error, ReaderBuilder, WriterBuilder};
+
+use crate::csv_ext::encoding::detect_and_decode;
+
+// Reads every row of a headerless csv, rows may have different lengths.
+// Fields are decoded on their own since the file is not necessarily utf-8.
+pub fn read_rows(path: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut rdr = ReaderBuilder::new().has_headers(false).flexible(true).from_path(path)?;
+    let mut rows = Vec::new();
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        rows.push(record.iter().map(|field| detect_and_decode(field).0).collect());
+    }
+    Ok(rows)
+}
+
+pub fn write_rows(path: &str, rows: &[Vec<String>]) -> Result<(), Error> {
+    let mut wtr = WriterBuilder::new().flexible(true).from_path(path)?;
+    for row in rows {
+        wtr.write_record(row)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -16,7 +16,7 @@ pub fn records_to_vec2d(
     //          While also avoiding code dupplication !
     fn map_fields_in_vec2d(
         record: &mut ByteRecord
-        , vec: &mut Vec<Vec<Vec<u8>>>
+        , vec: &mut [Vec<Vec<u8>>]
     ) {
         record
             .iter()
@@ -0,0 +1,267 @@
+use crate::progress::Progress;
+use crate::validation;
+use empilement::RegressionLogistique;
+use membre::Membre;
+
+mod empilement;
+pub mod membre;
+
+// Plis de la validation croisée qui produit les exemples d'entraînement du méta-classifieur
+const N_PLIS_EMPILEMENT: usize = 5;
+
+// Façon de combiner les membres : 0 = vote majoritaire pondéré, 1 = vote souple (moyenne pondérée
+// des probabilités), 2 = empilement (méta-classifieur entraîné sur les probabilités des membres)
+#[derive(Debug, Clone, Copy)]
+enum Combinaison {
+    VoteMajoritaire,
+    VoteSouple,
+    Empilement,
+}
+
+impl From<i64> for Combinaison {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => Combinaison::VoteSouple,
+            2 => Combinaison::Empilement,
+            _ => Combinaison::VoteMajoritaire,
+        }
+    }
+}
+
+/// Membres de l'ensemble et façon de les combiner
+pub struct Configuration {
+    membres: Vec<Membre>,
+    combinaison: Combinaison,
+}
+
+impl Configuration {
+    /// Membres et combinaison (0 = vote majoritaire pondéré, 1 = vote souple, 2 = empilement)
+    pub fn new(membres: Vec<Membre>, combination: i64) -> Result<Self, String> {
+        if membres.is_empty() {
+            return Err("L'ensemble n'a aucun membre".to_string());
+        }
+        if membres.iter().map(|m| m.poids).sum::<f64>() <= 0.0 {
+            return Err("La somme des poids des membres est nulle".to_string());
+        }
+        Ok(Self { membres, combinaison: Combinaison::from(combination) })
+    }
+
+    // Étapes comptées par la progression : un entraînement par membre (et par pli pour l'empilement)
+    fn nb_etapes(&self) -> usize {
+        match self.combinaison {
+            Combinaison::Empilement => self.membres.len() * (1 + N_PLIS_EMPILEMENT),
+            _ => self.membres.len(),
+        }
+    }
+}
+
+/// Classe un tweet avec les membres entraînés sur tout le fichier `label,texte`
+pub fn classer(path: &str, configuration: &Configuration, tweet: &str) -> Result<i32, String> {
+    let donnees = validation::charger_donnees(path).map_err(|e| format!("Erreur chargement données: {}", e))?;
+    let entrainement: Vec<(&str, i32)> = donnees.iter().map(|t| (t.contenu.as_str(), t.etiquette)).collect();
+    let probabilites = predire(configuration, &entrainement, &[tweet], -1, &Progress::default())?;
+    Ok(classe(&probabilites.combinees[0]))
+}
+
+/// Évaluation sur une division stratifiée 2/3 - 1/3, renvoie le tableau de la matrice de confusion
+/// et la comparaison avec chaque membre. La progression compte les entraînements des membres.
+pub fn evaluer(path: &str, configuration: &Configuration, graine: i64, progress: &Progress) -> Result<String, String> {
+    let donnees = validation::charger_donnees(path).map_err(|e| format!("Erreur chargement données: {}", e))?;
+
+    // Même division que les évaluations de chaque algorithme : même graine, mêmes tweets de test
+    let indices: Vec<usize> = (0..donnees.len()).collect();
+    let (indices_entrainement, indices_test) =
+        validation::diviser_donnees_stratifiee(&indices, |&i| donnees[i].etiquette, 2.0 / 3.0, graine);
+    if indices_entrainement.is_empty() || indices_test.is_empty() {
+        return Err("Base d'entraînement ou test vide après division".to_string());
+    }
+    let entrainement: Vec<(&str, i32)> = indices_entrainement.iter().map(|&i| (donnees[i].contenu.as_str(), donnees[i].etiquette)).collect();
+    let textes_test: Vec<&str> = indices_test.iter().map(|&i| donnees[i].contenu.as_str()).collect();
+
+    progress.set_total(configuration.nb_etapes());
+    let probabilites = predire(configuration, &entrainement, &textes_test, graine, progress)?;
+
+    let matrice = |probabilites: &[[f64; 3]]| {
+        let mut matrice = [[0; 3]; 3];
+        for (&i, p) in indices_test.iter().zip(probabilites) {
+            validation::ajouter_prediction(&mut matrice, donnees[i].etiquette, classe(p));
+        }
+        matrice
+    };
+
+    let matrice_ensemble = matrice(&probabilites.combinees);
+    let mut tableau = validation::format_matrice_confusion(&matrice_ensemble);
+    tableau.push_str(&format!(
+        "\n\nEnsemble : exactitude {:.1} %, F1 macro {:.3}\n",
+        validation::exactitude(&matrice_ensemble) * 100.0,
+        validation::f1_macro(&matrice_ensemble)
+    ));
+    for (membre, probabilites) in configuration.membres.iter().zip(&probabilites.par_membre) {
+        let matrice_membre = matrice(probabilites);
+        tableau.push_str(&format!(
+            "{} (poids {}) : exactitude {:.1} %, F1 macro {:.3}\n",
+            membre.nom(),
+            membre.poids,
+            validation::exactitude(&matrice_membre) * 100.0,
+            validation::f1_macro(&matrice_membre)
+        ));
+    }
+    Ok(tableau)
+}
+
+struct Probabilites {
+    // Probabilités de l'ensemble pour chaque texte
+    combinees: Vec<[f64; 3]>,
+    // Probabilités de chaque membre seul pour chaque texte
+    par_membre: Vec<Vec<[f64; 3]>>,
+}
+
+// Entraîne les membres sur `entrainement` et combine leurs probabilités pour chaque texte
+fn predire(configuration: &Configuration, entrainement: &[(&str, i32)], textes: &[&str], graine: i64, progress: &Progress) -> Result<Probabilites, String> {
+    let par_membre = probabilites_membres(&configuration.membres, entrainement, textes, progress)?;
+    let poids_total: f64 = configuration.membres.iter().map(|m| m.poids).sum();
+
+    let combinees = match configuration.combinaison {
+        Combinaison::VoteMajoritaire => (0..textes.len())
+            .map(|i| {
+                let mut votes = [0.0; 3];
+                for (membre, probabilites) in configuration.membres.iter().zip(&par_membre) {
+                    votes[index_max(&probabilites[i])] += membre.poids / poids_total;
+                }
+                votes
+            })
+            .collect(),
+        Combinaison::VoteSouple => (0..textes.len())
+            .map(|i| {
+                let mut moyenne = [0.0; 3];
+                for (membre, probabilites) in configuration.membres.iter().zip(&par_membre) {
+                    for c in 0..3 {
+                        moyenne[c] += membre.poids / poids_total * probabilites[i][c];
+                    }
+                }
+                moyenne
+            })
+            .collect(),
+        Combinaison::Empilement => {
+            let meta = entrainer_empilement(&configuration.membres, entrainement, graine, progress)?;
+            (0..textes.len()).map(|i| meta.probabilites(&variables(&par_membre, i))).collect()
+        },
+    };
+    Ok(Probabilites { combinees, par_membre })
+}
+
+// L'erreur d'un membre nomme ce membre (rang dans la configuration et algorithme)
+fn probabilites_membres(membres: &[Membre], entrainement: &[(&str, i32)], textes: &[&str], progress: &Progress) -> Result<Vec<Vec<[f64; 3]>>, String> {
+    membres
+        .iter()
+        .enumerate()
+        .map(|(rang, membre)| {
+            if progress.is_cancelled() {
+                return Err("Évaluation annulée".to_string());
+            }
+            let probabilites = membre
+                .probabilites(entrainement, textes)
+                .map_err(|e| format!("Membre {} ({}) : {}", rang + 1, membre.nom(), e))?;
+            progress.advance(1);
+            Ok(probabilites)
+        })
+        .collect()
+}
+
+// Le méta-classifieur apprend sur des probabilités que les membres ont produites sans voir l'exemple :
+// chaque pli de `entrainement` est prédit par des membres entraînés sur les autres plis
+fn entrainer_empilement(membres: &[Membre], entrainement: &[(&str, i32)], graine: i64, progress: &Progress) -> Result<RegressionLogistique, String> {
+    let etiquettes: Vec<i32> = entrainement.iter().map(|&(_, etiquette)| etiquette).collect();
+    let mut rng = validation::generateur(graine);
+    let plis = validation::plis_stratifies(&etiquettes, N_PLIS_EMPILEMENT, &mut rng);
+
+    let mut exemples: Vec<(Vec<f64>, usize)> = Vec::with_capacity(entrainement.len());
+    for i in 0..plis.len() {
+        let (indices_entrainement, indices_pli) = validation::separer_pli(&plis, i);
+        let sous_entrainement: Vec<(&str, i32)> = indices_entrainement.iter().map(|&j| entrainement[j]).collect();
+        let textes: Vec<&str> = indices_pli.iter().map(|&j| entrainement[j].0).collect();
+        let par_membre = probabilites_membres(membres, &sous_entrainement, &textes, progress)?;
+
+        for (rang, &j) in indices_pli.iter().enumerate() {
+            if let Some(c) = validation::index_classe(entrainement[j].1) {
+                exemples.push((variables(&par_membre, rang), c));
+            }
+        }
+    }
+
+    let (variables, classes): (Vec<Vec<f64>>, Vec<usize>) = exemples.into_iter().unzip();
+    Ok(RegressionLogistique::entrainer(&variables, &classes))
+}
+
+// Probabilités de tous les membres pour le texte i, mises bout à bout
+fn variables(par_membre: &[Vec<[f64; 3]>], i: usize) -> Vec<f64> {
+    par_membre.iter().flat_map(|probabilites| probabilites[i]).collect()
+}
+
+// Égalité : la plus petite étiquette
+fn index_max(probabilites: &[f64; 3]) -> usize {
+    (0..3).rev().max_by(|&a, &b| probabilites[a].total_cmp(&probabilites[b])).unwrap_or(1)
+}
+
+fn classe(probabilites: &[f64; 3]) -> i32 {
+    validation::CLASSES[index_max(probabilites)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use membre::Algorithme;
+
+    fn fichier(nom: &str) -> String {
+        let mut contenu = String::new();
+        for i in 0..10 {
+            contenu.push_str(&format!("0,je deteste ce film horrible {}\n", i));
+            contenu.push_str(&format!("4,j adore ce film genial {}\n", i));
+        }
+        let chemin = std::env::temp_dir().join(format!("{}_{}", std::process::id(), nom));
+        std::fs::write(&chemin, contenu).unwrap();
+        chemin.to_string_lossy().into_owned()
+    }
+
+    fn membre(algorithme: Algorithme) -> Membre {
+        Membre { algorithme, poids: 1.0 }
+    }
+
+    #[test]
+    fn vote_des_membres() {
+        let chemin = fichier("ensemble_vote.csv");
+        let membres = vec![
+            membre(Algorithme::Knn { k: 3, vote: 0 }),
+            membre(Algorithme::Bayes { vote: 0, representation: 0, ngram: 1 }),
+        ];
+        for combinaison in 0..3 {
+            let configuration = Configuration::new(membres.clone(), combinaison).unwrap();
+            let tableau = evaluer(&chemin, &configuration, 1, &Progress::default()).unwrap();
+            assert!(tableau.contains("Ensemble : exactitude 100.0 %"), "{}", tableau);
+            assert!(tableau.contains("knn (poids 1)") && tableau.contains("bayes (poids 1)"), "{}", tableau);
+        }
+        assert_eq!(classer(&chemin, &Configuration::new(membres, 0).unwrap(), "j adore ce film").unwrap(), 4);
+        std::fs::remove_file(&chemin).unwrap();
+    }
+
+    #[test]
+    fn membre_en_echec_nomme() {
+        let chemin = fichier("ensemble_echec.csv");
+        // Plus de clusters que de tweets d'entraînement
+        let membres = vec![
+            membre(Algorithme::Bayes { vote: 0, representation: 0, ngram: 1 }),
+            membre(Algorithme::Clustering { k: 100, method: 0 }),
+        ];
+        let configuration = Configuration::new(membres, 0).unwrap();
+        let erreur = evaluer(&chemin, &configuration, 1, &Progress::default()).unwrap_err();
+        std::fs::remove_file(&chemin).unwrap();
+        assert!(erreur.starts_with("Membre 2 (clustering) : "), "{}", erreur);
+    }
+
+    #[test]
+    fn configuration_invalide() {
+        assert!(Configuration::new(vec![], 0).is_err());
+        let sans_poids = Membre { algorithme: Algorithme::Knn { k: 3, vote: 0 }, poids: 0.0 };
+        assert!(Configuration::new(vec![sans_poids], 0).is_err());
+    }
+}
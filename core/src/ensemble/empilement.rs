@@ -0,0 +1,57 @@
+// Méta-classifieur de l'empilement : régression logistique multinomiale sur les probabilités des membres
+
+const ITERATIONS: usize = 500;
+const PAS: f64 = 0.5;
+// Pénalité L2 sur les coefficients (pas sur les biais)
+const REGULARISATION: f64 = 1e-3;
+
+pub struct RegressionLogistique {
+    // coefficients[classe] : un coefficient par variable, puis le biais
+    coefficients: [Vec<f64>; 3],
+}
+
+impl RegressionLogistique {
+    /// Descente de gradient sur l'entropie croisée. `variables[i]` contient les probabilités des membres
+    /// mises bout à bout pour l'exemple i, `classes[i]` son indice de classe (ordre de validation::CLASSES).
+    pub fn entrainer(variables: &[Vec<f64>], classes: &[usize]) -> Self {
+        let nb_variables = variables.first().map(|v| v.len()).unwrap_or(0);
+        let mut coefficients: [Vec<f64>; 3] = std::array::from_fn(|_| vec![0.0; nb_variables + 1]);
+        let n = variables.len().max(1) as f64;
+
+        for _ in 0..ITERATIONS {
+            let mut gradients: [Vec<f64>; 3] = std::array::from_fn(|_| vec![0.0; nb_variables + 1]);
+            for (x, &y) in variables.iter().zip(classes) {
+                let p = softmax(&coefficients, x);
+                for c in 0..3 {
+                    let erreur = p[c] - if c == y { 1.0 } else { 0.0 };
+                    for (g, &v) in gradients[c].iter_mut().zip(x) {
+                        *g += erreur * v;
+                    }
+                    gradients[c][nb_variables] += erreur;
+                }
+            }
+            for (coefs, gradient) in coefficients.iter_mut().zip(&gradients) {
+                for (j, (w, g)) in coefs.iter_mut().zip(gradient).enumerate() {
+                    let penalite = if j < nb_variables { REGULARISATION * *w } else { 0.0 };
+                    *w -= PAS * (g / n + penalite);
+                }
+            }
+        }
+        Self { coefficients }
+    }
+
+    pub fn probabilites(&self, variables: &[f64]) -> [f64; 3] {
+        softmax(&self.coefficients, variables)
+    }
+}
+
+fn softmax(coefficients: &[Vec<f64>; 3], x: &[f64]) -> [f64; 3] {
+    let scores = coefficients.each_ref().map(|w| {
+        let biais = w[w.len() - 1];
+        w.iter().zip(x).map(|(a, b)| a * b).sum::<f64>() + biais
+    });
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exp = scores.map(|s| (s - max).exp());
+    let total: f64 = exp.iter().sum();
+    exp.map(|e| e / total)
+}
@@ -0,0 +1,42 @@
+use crate::naive::modificateurs::Modificateurs;
+use crate::{bayes, clustering, knn, naive};
+
+// Classifieur de l'ensemble et ses réglages (mêmes valeurs que les fonctions *_evaluate de chaque algorithme)
+#[derive(Debug, Clone)]
+pub enum Algorithme {
+    Naive { positifs: String, negatifs: String, seuil: f64, modificateurs: Modificateurs },
+    Knn { k: usize, vote: i64 },
+    Clustering { k: usize, method: usize },
+    Bayes { vote: i64, representation: i64, ngram: i64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Membre {
+    pub algorithme: Algorithme,
+    // Poids dans les votes (ignoré par l'empilement, qui apprend le sien)
+    pub poids: f64,
+}
+
+impl Membre {
+    pub fn nom(&self) -> &'static str {
+        match self.algorithme {
+            Algorithme::Naive { .. } => "naive",
+            Algorithme::Knn { .. } => "knn",
+            Algorithme::Clustering { .. } => "clustering",
+            Algorithme::Bayes { .. } => "bayes",
+        }
+    }
+
+    /// Probabilités de chaque classe (ordre de validation::CLASSES) pour chaque texte,
+    /// le membre étant entraîné sur les paires (texte, étiquette) de `entrainement`
+    pub fn probabilites(&self, entrainement: &[(&str, i32)], textes: &[&str]) -> Result<Vec<[f64; 3]>, String> {
+        match &self.algorithme {
+            Algorithme::Naive { positifs, negatifs, seuil, modificateurs } => {
+                naive::probabilites(textes, positifs, negatifs, *seuil, modificateurs)
+            },
+            Algorithme::Knn { k, vote } => Ok(knn::probabilites(entrainement, textes, *k, *vote)),
+            Algorithme::Clustering { k, method } => clustering::probabilites(entrainement, textes, *k, *method),
+            Algorithme::Bayes { vote, representation, ngram } => Ok(bayes::probabilites(entrainement, textes, *vote, *representation, *ngram)),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{HashSet, HashMap};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::parallel;
+use crate::progress::Progress;
+use crate::validation;
+pub use crate::validation::{TweetEtiquete, charger_donnees};
+
+pub mod selection;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TypeVote {
+    Majoritaire,
+    Pondere,
+}
+
+impl TypeVote {
+    fn vote(&self, proches_voisins: &[(f64, i32)]) -> Option<i32> {
+        match self {
+            TypeVote::Majoritaire => vote_majoritaire(proches_voisins),
+            TypeVote::Pondere => vote_pondere(proches_voisins),
+        }
+    }
+
+    // Poids d'un voisin dans le vote selon sa distance
+    pub fn poids(&self, distance: f64) -> f64 {
+        match self {
+            TypeVote::Majoritaire => 1.0,
+            TypeVote::Pondere => poids_inverse_distance(distance),
+        }
+    }
+}
+
+impl From<i64> for TypeVote {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => TypeVote::Pondere,
+            _ => TypeVote::Majoritaire,
+        }
+    }
+}
+
+impl From<TypeVote> for i64 {
+    fn from(value: TypeVote) -> Self {
+        match value {
+            TypeVote::Majoritaire => 0,
+            TypeVote::Pondere => 1,
+        }
+    }
+}
+
+/// Index des tweets annotés d'un fichier, pour être enregistré : les KNN n'apprennent rien d'autre
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModeleKnn {
+    k: usize,
+    vote: TypeVote,
+    base: Vec<TweetEtiquete>,
+}
+
+impl ModeleKnn {
+    /// Index des paires (texte, étiquette)
+    pub fn new(entrainement: &[(&str, i32)], k: usize, type_vote: i64) -> Self {
+        let base = entrainement
+            .iter()
+            .map(|&(contenu, etiquette)| TweetEtiquete { contenu: contenu.to_string(), etiquette })
+            .collect();
+        Self { k, vote: TypeVote::from(type_vote), base }
+    }
+
+    pub fn entrainer(path: &str, k: i64, type_vote: i64) -> Result<Self, String> {
+        let base = charger_donnees(path).map_err(|e| format!("Erreur chargement données: {}", e))?;
+        if base.is_empty() {
+            return Err("Base d'entraînement vide".to_string());
+        }
+        Ok(Self { k: verifier_k(k, base.len())?, vote: TypeVote::from(type_vote), base })
+    }
+
+    pub fn classifier(&self, tweet: &str) -> Option<i32> {
+        classifier_tweet(tweet, self.k, &self.base, self.vote)
+    }
+
+    /// Part des poids du vote de chaque classe (ordre de validation::CLASSES) parmi les k plus proches voisins
+    pub fn probabilites(&self, tweet: &str) -> [f64; 3] {
+        let mut votes = [0.0; 3];
+        for (distance, idx) in proches_voisins(tweet, self.k.min(self.base.len()), &self.base) {
+            if let Some(c) = validation::index_classe(self.base[idx].etiquette) {
+                votes[c] += self.vote.poids(distance);
+            }
+        }
+        let total: f64 = votes.iter().sum();
+        if total > 0.0 { votes.map(|v| v / total) } else { [1.0 / 3.0; 3] }
+    }
+
+    /// Les k plus proches voisins du tweet, par distance croissante
+    pub fn voisins(&self, tweet: &str) -> Vec<(f64, &TweetEtiquete)> {
+        proches_voisins(tweet, self.k, &self.base).into_iter().map(|(distance, idx)| (distance, &self.base[idx])).collect()
+    }
+}
+
+/// Évaluation sur une division stratifiée 2/3 - 1/3, renvoie le tableau de la matrice de confusion.
+/// La progression compte les tweets de test classés.
+pub fn evaluer(path: &str, k: i64, type_vote: i64, graine: i64, progress: &Progress) -> Result<String, String> {
+    matrice_evaluation(path, k, type_vote, graine, progress).map(|matrice| validation::format_matrice_confusion(&matrice))
+}
+
+/// Matrice de confusion [réel][estimé] de la même évaluation
+pub fn matrice_evaluation(path: &str, k: i64, type_vote: i64, graine: i64, progress: &Progress) -> Result<[[i32; 3]; 3], String> {
+    // Déterminer le type de vote
+    let vote_type = TypeVote::from(type_vote);
+
+    // Charger les données depuis le CSV
+    let base_complete = charger_donnees(path)
+        .map_err(|e| format!("Erreur chargement données: {}", e))?;
+
+    // Division stratifiée 2/3 - 1/3
+    let (base_entrainement, base_test) = validation::diviser_donnees_stratifiee(&base_complete, |t| t.etiquette, 2.0/3.0, graine);
+
+    if base_entrainement.is_empty() || base_test.is_empty() {
+        return Err("Base d'entraînement ou test vide après division".to_string());
+    }
+    let k_usize = verifier_k(k, base_entrainement.len())?;
+
+    // Matrice de confusion : [réel][estimé]
+    // Indices: 0=négatif, 2=neutre, 4=positif
+    let mut matrice_confusion = [[0; 3]; 3];
+
+    // Évaluation sur le jeu de test, chaque tweet est classé indépendamment
+    progress.set_total(base_test.len());
+    let predictions: Option<Vec<(i32, Option<i32>)>> = parallel::executer(|| {
+        base_test
+            .par_iter()
+            .map(|tweet_test| {
+                if progress.is_cancelled() {
+                    return None;
+                }
+                let classe_estimee = classifier_tweet(&tweet_test.contenu, k_usize, &base_entrainement, vote_type);
+                progress.advance(1);
+                Some((tweet_test.etiquette, classe_estimee))
+            })
+            .collect()
+    });
+    let predictions = predictions.ok_or("Évaluation annulée")?;
+
+    for (etiquette, classe_estimee) in predictions {
+        if let Some(classe_estimee) = classe_estimee {
+            validation::ajouter_prediction(&mut matrice_confusion, etiquette, classe_estimee);
+        }
+    }
+
+    Ok(matrice_confusion)
+}
+
+/// k doit être au moins 1 et au plus le nombre de tweets de la base d'entraînement
+pub fn verifier_k(k: i64, taille_base: usize) -> Result<usize, String> {
+    if k < 1 || k as usize > taille_base {
+        return Err(format!("k doit être entre 1 et {} (taille de la base d'entraînement), et non {}", taille_base, k));
+    }
+    Ok(k as usize)
+}
+
+pub fn classifier_tweet(x: &str, k: usize, base: &[TweetEtiquete], type_vote: TypeVote) -> Option<i32> {
+    if base.is_empty() || k == 0 || k > base.len() {
+        return None;
+    }
+
+    let proches_voisins: Vec<(f64, i32)> = proches_voisins(x, k, base)
+        .into_iter()
+        .map(|(distance, idx)| (distance, base[idx].etiquette))
+        .collect();
+
+    type_vote.vote(&proches_voisins)
+}
+
+/// Renvoie les k plus proches voisins de `x` dans la base sous la forme (distance, index dans la base),
+/// triés par distance croissante
+pub fn proches_voisins(x: &str, k: usize, base: &[TweetEtiquete]) -> Vec<(f64, usize)> {
+    // Étape 1: Initialiser la liste des proches voisins
+    let mut proches_voisins: Vec<(f64, usize)> = Vec::new(); // (distance, index)
+    
+    // Étape 2: Parcourir tous les tweets de la base
+    for (idx, tweet) in base.iter().enumerate() {
+        let distance = distance_tweets(x, &tweet.contenu);
+        
+        if proches_voisins.len() < k {
+            proches_voisins.push((distance, idx));
+            // Trier par distance croissante
+            proches_voisins.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        } else {
+            // Vérifier si ce tweet est plus proche que le plus éloigné actuel
+            if distance < proches_voisins.last().unwrap().0 {
+                // Remplacer le voisin le plus éloigné
+                proches_voisins.pop();
+                proches_voisins.push((distance, idx));
+                proches_voisins.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            }
+        }
+    }
+    
+    proches_voisins
+}
+
+/// Probabilités de chaque classe (ordre de validation::CLASSES) pour chaque texte : part des poids du vote
+/// des k plus proches voisins pris dans `entrainement` (texte, étiquette). Utilisé par l'apprentissage actif.
+pub fn probabilites(entrainement: &[(&str, i32)], textes: &[&str], k: usize, type_vote: i64) -> Vec<[f64; 3]> {
+    let modele = ModeleKnn::new(entrainement, k, type_vote);
+    parallel::executer(|| textes.par_iter().map(|texte| modele.probabilites(texte)).collect())
+}
+
+/// Vote majoritaire simple (comptage des occurrences)
+fn vote_majoritaire(proches_voisins: &[(f64, i32)]) -> Option<i32> {
+    let mut votes: HashMap<i32, usize> = HashMap::new();
+    
+    for (_, etiquette) in proches_voisins {
+        *votes.entry(*etiquette).or_insert(0) += 1;
+    }
+
+    let max_votes = votes.values().max().copied().unwrap_or(0);
+    
+    //On prend toutes les classes qui ont les votes max
+    let candidats: Vec<i32> = votes.iter()
+        .filter(|&(_, &count)| count == max_votes)
+        .map(|(&cls, _)| cls)
+        .collect();
+    
+    if candidats.len() == 1 {
+        Some(candidats[0])
+    } else {
+        // On retourne la première classe trouvée dans les proches voisins qui est dans les candidats
+        for (_, etiquette) in proches_voisins {
+            if candidats.contains(etiquette) {
+                return Some(*etiquette);
+            }
+        }
+        None // ça ne doit pas arriver si k > 0
+    }
+}
+
+/// Vote pondéré par l'inverse de la distance
+/// Les voisins plus proches ont plus de poids
+fn vote_pondere(proches_voisins: &[(f64, i32)]) -> Option<i32> {
+    let mut votes_ponderes: HashMap<i32, f64> = HashMap::new();
+
+    for (distance, etiquette) in proches_voisins {
+        let poids = poids_inverse_distance(*distance);
+
+        *votes_ponderes.entry(*etiquette).or_insert(0.0) += poids;
+    }
+    
+    // Parcours dans l'ordre des classes pour que les égalités soient tranchées toujours de la même façon
+    let mut votes_ponderes: Vec<(i32, f64)> = votes_ponderes.into_iter().collect();
+    votes_ponderes.sort_by_key(|&(classe, _)| classe);
+
+    votes_ponderes
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(classe, _)| classe)
+}
+
+fn poids_inverse_distance(distance: f64) -> f64 {
+    // Éviter la division par zéro en ajoutant un petit epsilon
+    let epsilon = 1e-5;
+    1.0 / (distance + epsilon)
+}
+
+/// Calcule la distance entre deux tweets selon la formule :
+/// D(t1, t2) = (Nombre total de mots - Nombre de mots communs) / Nombre total de mots
+fn distance_tweets(tweet1: &str, tweet2: &str) -> f64 {
+    // Tokeniser les tweets en mots (en minuscules pour ignorer la casse)
+    let mots_tweet1: HashSet<String> = tokeniser_tweet(tweet1);
+    let mots_tweet2: HashSet<String> = tokeniser_tweet(tweet2);
+    distance_mots(&mots_tweet1, &mots_tweet2)
+}
+
+/// Même distance que distance_tweets sur des tweets déjà tokenisés
+pub fn distance_mots(mots_tweet1: &HashSet<String>, mots_tweet2: &HashSet<String>) -> f64 {
+    // Calculer l'union des mots (nombre total de mots distincts)
+    let union_mots: HashSet<&String> = mots_tweet1.union(mots_tweet2).collect();
+    let nombre_total_mots = union_mots.len() as f64;
+    
+    // Calculer l'intersection des mots (mots communs)
+    let intersection_mots: HashSet<&String> = mots_tweet1.intersection(mots_tweet2).collect();
+    let nombre_mots_communs = intersection_mots.len() as f64;
+    
+    // Appliquer la formule de distance
+    if nombre_total_mots == 0.0 {
+        0.0 // Si les deux tweets sont vides, distance = 0
+    } else {
+        (nombre_total_mots - nombre_mots_communs) / nombre_total_mots
+    }
+}
+
+/// Tokenise un tweet en mots (minuscules)
+pub fn tokeniser_tweet(tweet: &str) -> HashSet<String> {
+    tweet
+        .split_whitespace()
+        .map(|mot| mot.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 12 tweets par classe, entraînement de 24 tweets après la division 2/3 - 1/3
+    fn fichier(nom: &str) -> std::path::PathBuf {
+        let chemin = std::env::temp_dir().join(format!("{}_{}.csv", std::process::id(), nom));
+        let mut contenu = String::from("label,texte\n");
+        for i in 0..12 {
+            contenu.push_str(&format!("0,triste nul mauvais {}\n2,le train part demain {}\n4,super joie bravo {}\n", i, i, i));
+        }
+        std::fs::write(&chemin, contenu).unwrap();
+        chemin
+    }
+
+    fn evaluer_k(nom: &str, k: i64) -> Result<[[i32; 3]; 3], String> {
+        let chemin = fichier(nom);
+        let resultat = matrice_evaluation(chemin.to_str().unwrap(), k, 0, 1, &Progress::default());
+        std::fs::remove_file(&chemin).unwrap();
+        resultat
+    }
+
+    #[test]
+    fn k_nul_refuse() {
+        let erreur = evaluer_k("knn_k_nul", 0).unwrap_err();
+        assert!(erreur.contains("entre 1 et 24"), "{}", erreur);
+    }
+
+    #[test]
+    fn k_plus_grand_que_l_entrainement_refuse() {
+        assert!(evaluer_k("knn_k_grand", 100_000).is_err());
+        assert!(evaluer_k("knn_k_25", 25).is_err());
+    }
+
+    #[test]
+    fn k_valide() {
+        let matrice = evaluer_k("knn_k_valide", 3).unwrap();
+        assert_eq!(matrice, [[4, 0, 0], [0, 4, 0], [0, 0, 4]]);
+        assert!(evaluer_k("knn_k_24", 24).is_ok());
+    }
+
+    #[test]
+    fn verification_de_k() {
+        assert_eq!(verifier_k(1, 1), Ok(1));
+        assert!(verifier_k(-3, 10).is_err());
+        assert!(verifier_k(2, 0).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use rayon::prelude::*;
+
+use crate::knn::{TweetEtiquete, TypeVote, proches_voisins};
+use crate::{parallel, validation};
+
+// Un point de la courbe métrique / k renvoyée à Godot
+pub struct PointCourbe {
+    pub k: usize,
+    pub vote: TypeVote,
+    pub exactitude: f64,
+    pub f1_macro: f64,
+}
+
+const VOTES: [TypeVote; 2] = [TypeVote::Majoritaire, TypeVote::Pondere];
+
+/// Balaye k dans [k_min, k_max] pour chaque type de vote par validation croisée stratifiée.
+/// Les voisins ne sont calculés qu'une fois par tweet de test (pour le k le plus grand),
+/// les k plus petits réutilisent le début de la liste triée.
+pub fn balayer_k(
+    base: &[TweetEtiquete],
+    k_min: usize,
+    k_max: usize,
+    n_plis: usize,
+    graine: i64,
+) -> Result<Vec<PointCourbe>, String> {
+    let k_min = k_min.max(1);
+    if k_max < k_min {
+        return Err(format!("Intervalle de k invalide ({} > {})", k_min, k_max));
+    }
+    if base.len() < n_plis.max(2) {
+        return Err(format!("Pas assez de tweets ({}) pour {} plis", base.len(), n_plis));
+    }
+
+    let mut rng = validation::generateur(graine);
+    let etiquettes: Vec<i32> = base.iter().map(|t| t.etiquette).collect();
+    let plis = validation::plis_stratifies(&etiquettes, n_plis, &mut rng);
+
+    // Le plus petit ensemble d'entraînement borne le k utilisable
+    let taille_min_train = (0..plis.len())
+        .map(|i| base.len() - plis[i].len())
+        .min()
+        .unwrap_or(0);
+    let k_max = k_max.min(taille_min_train);
+    if k_max < k_min {
+        return Err(format!("k_min ({}) dépasse la taille de l'entraînement ({})", k_min, taille_min_train));
+    }
+
+    // matrices[k - k_min][vote]
+    let mut matrices = vec![[[[0; 3]; 3]; VOTES.len()]; k_max - k_min + 1];
+
+    for i in 0..plis.len() {
+        let (idx_train, idx_test) = validation::separer_pli(&plis, i);
+        let entrainement: Vec<TweetEtiquete> = idx_train.iter().map(|&j| base[j].clone()).collect();
+
+        // Pour chaque tweet de test, les prédictions de tous les réglages (k, vote)
+        let predictions: Vec<(i32, Vec<Option<i32>>)> = parallel::executer(|| {
+            idx_test
+                .par_iter()
+                .map(|&j| {
+                    let tweet_test = &base[j];
+                    let voisins: Vec<(f64, i32)> = proches_voisins(&tweet_test.contenu, k_max, &entrainement)
+                        .into_iter()
+                        .map(|(distance, idx)| (distance, entrainement[idx].etiquette))
+                        .collect();
+
+                    let estimations = (k_min..=k_max)
+                        .flat_map(|k| VOTES.iter().map(move |vote| (k, vote)))
+                        .map(|(k, vote)| vote.vote(&voisins[..k]))
+                        .collect();
+                    (tweet_test.etiquette, estimations)
+                })
+                .collect()
+        });
+
+        for (reel, estimations) in predictions {
+            for (r, estime) in estimations.into_iter().enumerate() {
+                if let Some(classe_estimee) = estime {
+                    validation::ajouter_prediction(&mut matrices[r / VOTES.len()][r % VOTES.len()], reel, classe_estimee);
+                }
+            }
+        }
+    }
+
+    let mut courbe = Vec::with_capacity(matrices.len() * VOTES.len());
+    for (offset, par_vote) in matrices.iter().enumerate() {
+        for (v, matrice) in par_vote.iter().enumerate() {
+            courbe.push(PointCourbe {
+                k: k_min + offset,
+                vote: VOTES[v],
+                exactitude: validation::exactitude(matrice),
+                f1_macro: validation::f1_macro(matrice),
+            });
+        }
+    }
+    Ok(courbe)
+}
+
+// Meilleur réglage : F1 macro le plus haut, puis exactitude, puis le plus petit k
+pub fn meilleur_point(courbe: &[PointCourbe]) -> Option<&PointCourbe> {
+    courbe.iter().max_by(|a, b| {
+        a.f1_macro
+            .total_cmp(&b.f1_macro)
+            .then(a.exactitude.total_cmp(&b.exactitude))
+            .then(b.k.cmp(&a.k))
+    })
+}
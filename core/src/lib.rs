@@ -0,0 +1,18 @@
+// Classification de tweets sans dépendance à Godot, utilisée par l'extension et par les outils en ligne de commande
+
+pub mod active_learning;
+pub mod agreement;
+pub mod bayes;
+pub mod cleandata;
+pub mod clustering;
+pub mod csv_ext;
+pub mod ensemble;
+pub mod knn;
+pub mod modele;
+pub mod naive;
+pub mod parallel;
+pub mod progress;
+#[cfg(feature = "python")]
+mod python;
+pub mod regex_ext;
+pub mod validation;
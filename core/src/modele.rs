@@ -0,0 +1,132 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::bayes::BayesModel;
+use crate::clustering::cache::{CleModele, ModeleClustering};
+use crate::knn::ModeleKnn;
+use crate::validation;
+
+/// Modèle entraîné sur un fichier `label,texte` et enregistré en JSON, quel que soit l'algorithme
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modele {
+    Bayes(BayesModel),
+    Knn(ModeleKnn),
+    Clustering(ModeleClustering),
+}
+
+impl Modele {
+    pub fn bayes(path: &str, type_vote: i64, type_representation: i64, ngram_type: i64) -> Result<Self, Box<dyn Error>> {
+        Ok(Modele::Bayes(BayesModel::entrainer(path, type_vote, type_representation, ngram_type)?))
+    }
+
+    pub fn knn(path: &str, k: i64, type_vote: i64) -> Result<Self, Box<dyn Error>> {
+        Ok(Modele::Knn(ModeleKnn::entrainer(path, k, type_vote)?))
+    }
+
+    /// Coupe en `k` clusters, méthodes numérotées comme pour l'évaluation du clustering
+    pub fn clustering(path: &str, k: usize, method: usize) -> Result<Self, Box<dyn Error>> {
+        Ok(Modele::Clustering(ModeleClustering::construire(CleModele::new(path, method)?, k.max(1))?))
+    }
+
+    pub fn algorithme(&self) -> &'static str {
+        match self {
+            Modele::Bayes(_) => "bayes",
+            Modele::Knn(_) => "knn",
+            Modele::Clustering(_) => "clustering",
+        }
+    }
+
+    /// Classe estimée (0, 2 ou 4), neutre si le modèle ne peut pas trancher
+    pub fn classifier(&mut self, tweet: &str) -> i32 {
+        match self {
+            Modele::Bayes(modele) => modele.classifier(tweet).unwrap_or(2),
+            Modele::Knn(modele) => modele.classifier(tweet).unwrap_or(2),
+            Modele::Clustering(modele) => modele.predire(tweet),
+        }
+    }
+
+    /// Ce qui a mené à la classe du tweet, selon l'algorithme
+    pub fn expliquer(&mut self, tweet: &str) -> Explication {
+        match self {
+            Modele::Bayes(modele) => Explication::Bayes {
+                probabilites: modele.probabilites(tweet),
+                mots: modele
+                    .contributions(tweet)
+                    .into_iter()
+                    .map(|(mot, occurrences, log_vraisemblances)| MotBayes { mot, occurrences, log_vraisemblances })
+                    .collect(),
+            },
+            Modele::Knn(modele) => Explication::Knn {
+                voisins: modele
+                    .voisins(tweet)
+                    .into_iter()
+                    .map(|(distance, voisin)| Voisin { texte: voisin.contenu.clone(), etiquette: voisin.etiquette, distance })
+                    .collect(),
+            },
+            Modele::Clustering(modele) => {
+                let (cluster, etiquette, voisin) = modele.situer(tweet);
+                Explication::Clustering { cluster, etiquette, voisin: voisin.map(|i| modele.tweets()[i].contenu.clone()) }
+            },
+        }
+    }
+
+    /// Probabilité de chaque classe (ordre de validation::CLASSES). Le clustering ne donne qu'une décision :
+    /// toute la probabilité va à l'étiquette du cluster.
+    pub fn probabilites(&mut self, tweet: &str) -> [f64; 3] {
+        match self {
+            Modele::Bayes(modele) => modele.probabilites(tweet),
+            Modele::Knn(modele) => modele.probabilites(tweet),
+            Modele::Clustering(modele) => {
+                let mut probabilites = [0.0; 3];
+                if let Some(c) = validation::index_classe(modele.predire(tweet)) {
+                    probabilites[c] = 1.0;
+                }
+                probabilites
+            },
+        }
+    }
+
+    pub fn enregistrer(&self, chemin: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(BufWriter::new(File::create(chemin)?), self)?;
+        Ok(())
+    }
+
+    /// Charge un modèle enregistré par `enregistrer`, ou un modèle de clustering enregistré depuis Godot
+    pub fn charger(chemin: &str) -> Result<Self, Box<dyn Error>> {
+        match serde_json::from_reader(BufReader::new(File::open(chemin)?)) {
+            Ok(modele) => Ok(modele),
+            Err(e) => ModeleClustering::charger(chemin).map(Modele::Clustering).map_err(|_| e.into()),
+        }
+    }
+}
+
+/// Explication d'une classification, sérialisable en JSON
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Explication {
+    // Probabilités a posteriori (ordre de validation::CLASSES) et mots connus du modèle
+    Bayes { probabilites: [f64; 3], mots: Vec<MotBayes> },
+    // Les k plus proches voisins, par distance croissante
+    Knn { voisins: Vec<Voisin> },
+    // Cluster du tweet, étiquette majoritaire du cluster et, pour une méthode hiérarchique, tweet le plus proche
+    Clustering { cluster: usize, etiquette: i32, voisin: Option<String> },
+}
+
+#[derive(Debug, Serialize)]
+pub struct MotBayes {
+    pub mot: String,
+    pub occurrences: usize,
+    // Par classe (ordre de validation::CLASSES), absente si la classe n'a pas été vue à l'entraînement
+    pub log_vraisemblances: [Option<f64>; 3],
+}
+
+#[derive(Debug, Serialize)]
+pub struct Voisin {
+    pub texte: String,
+    pub etiquette: i32,
+    pub distance: f64,
+}
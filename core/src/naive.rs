@@ -0,0 +1,194 @@
+// Classification naïve par lexique, partagée par l'extension Godot et l'annotateur en ligne de commande
+
+use crate::progress::Progress;
+use crate::validation::{self, TweetEtiquete, charger_donnees};
+use lexique::Lexique;
+use modificateurs::Modificateurs;
+
+pub mod annotation;
+pub mod induction;
+pub mod lexique;
+pub mod modificateurs;
+
+/// Classe d'un tweet (4 = positif, 2 = neutre, 0 = négatif) selon les polarités des entrées du lexique qu'il contient
+pub fn analyser_tweet(tweet: &str, lexique: &Lexique, modificateurs: &Modificateurs, weight: f32) -> i32 {
+    let score = lexique.scorer(tweet, modificateurs);
+    compute_polarity_with_weight(score.negatif as f32, score.positif as f32, weight)
+}
+
+// Les polarités sont des masses : avec des listes sans score, ce sont les nombres de mots positifs et négatifs.
+// weight est entre 0 et 1 : 0,5 donne la polarité majoritaire, 1 demande des entrées exclusivement positives
+// ou négatives. Sans aucune entrée, le tweet est neutre.
+pub fn compute_polarity_with_weight(negatives: f32, positives: f32, weight: f32) -> i32 {
+    let f_total = negatives + positives;
+
+    if f_total == 0.0 {
+        return 2;
+    }
+
+    // Si weight est 1.0, il faut un ratio strictement supérieur, donc impossible (max 1.0) :
+    // le cas ratio == 1.0 est accepté à part
+    let pos_ratio = positives / f_total;
+    if pos_ratio > weight || pos_ratio == 1.0 {
+        return 4;
+    }
+
+    let neg_ratio = negatives / f_total;
+    if neg_ratio > weight || neg_ratio == 1.0 {
+        return 0;
+    }
+
+    2
+}
+
+/// Dictionnaire positif (polarité 1 pour les listes sans score) puis négatif (-1), ignoré si son chemin est vide
+pub fn charger_lexique(path_pos: &str, path_neg: &str) -> Result<Lexique, String> {
+    let mut lexique = Lexique::default();
+    lexique.ajouter_fichier(path_pos, 1.0)
+        .map_err(|e| format!("Erreur dico pos: {}", e))?;
+    if !path_neg.is_empty() {
+        lexique.ajouter_fichier(path_neg, -1.0)
+            .map_err(|e| format!("Erreur dico neg: {}", e))?;
+    }
+    Ok(lexique)
+}
+
+// Évaluation sur le tiers de test d'une division stratifiée (même graine, mêmes tweets de test que les autres
+// algorithmes), renvoie le tableau de la matrice de confusion.
+// Les jeux vides ne sont pas des erreurs : le message est affiché à la place du tableau.
+pub fn evaluer(
+    path_data: &str,
+    path_pos: &str,
+    path_neg: &str,
+    weight: f64,
+    modificateurs: &Modificateurs,
+    graine: i64,
+    progress: &Progress,
+) -> Result<String, String> {
+    // 1. Chargement des dictionnaires
+    let lexique = charger_lexique(path_pos, path_neg)?;
+
+    // 2. Chargement des données complètes
+    let all_data = charger_donnees(path_data)
+        .map_err(|e| format!("Erreur data: {}", e))?;
+
+    if all_data.is_empty() {
+         return Ok("Dataset vide".to_string());
+    }
+
+    // 3. Division Stratifiée (2/3 Train, 1/3 Test) (même si on utilise pas le 2/3 train)
+    let (_train, test) = validation::diviser_donnees_stratifiee(&all_data, |t| t.etiquette, 2.0 / 3.0, graine);
+
+    if test.is_empty() {
+        return Ok("Erreur: Jeu de test vide après division".to_string());
+    }
+
+    // 4. Évaluation sur le set de test
+    let matrice_confusion = classer_test(&test, &lexique, modificateurs, weight, progress)?;
+    Ok(validation::format_matrice_confusion(&matrice_confusion))
+}
+
+// Matrice de confusion [réel][estimé] de la même évaluation, un jeu de test vide étant cette fois une erreur
+pub fn matrice_evaluation(
+    path_data: &str,
+    path_pos: &str,
+    path_neg: &str,
+    weight: f64,
+    modificateurs: &Modificateurs,
+    graine: i64,
+    progress: &Progress,
+) -> Result<[[i32; 3]; 3], String> {
+    let lexique = charger_lexique(path_pos, path_neg)?;
+    let all_data = charger_donnees(path_data).map_err(|e| format!("Erreur data: {}", e))?;
+    let (_train, test) = validation::diviser_donnees_stratifiee(&all_data, |t| t.etiquette, 2.0 / 3.0, graine);
+    if test.is_empty() {
+        return Err("Jeu de test vide après division".to_string());
+    }
+    classer_test(&test, &lexique, modificateurs, weight, progress)
+}
+
+// La progression compte les tweets de test classés
+fn classer_test(test: &[TweetEtiquete], lexique: &Lexique, modificateurs: &Modificateurs, weight: f64, progress: &Progress) -> Result<[[i32; 3]; 3], String> {
+    let mut matrice_confusion = [[0; 3]; 3];
+
+    progress.set_total(test.len());
+    for tweet in test {
+        if progress.is_cancelled() {
+            return Err("Évaluation annulée".to_string());
+        }
+        let classe_estimee = analyser_tweet(&tweet.contenu, lexique, modificateurs, weight as f32);
+        validation::ajouter_prediction(&mut matrice_confusion, tweet.etiquette, classe_estimee);
+        progress.advance(1);
+    }
+    Ok(matrice_confusion)
+}
+
+// Apprend le lexique et l'écrit, les graines étant chargées comme des dictionnaires
+pub fn apprendre_lexique(
+    path_data: &str,
+    path_pos_out: &str,
+    path_neg_out: &str,
+    parametres: &induction::Parametres,
+    seed_pos: &str,
+    seed_neg: &str,
+) -> Result<induction::LexiqueAppris, String> {
+    let donnees = charger_donnees(path_data).map_err(|e| format!("Erreur data: {}", e))?;
+    let tweets: Vec<(&str, i32)> = donnees.iter().map(|t| (t.contenu.as_str(), t.etiquette)).collect();
+    let graines = if seed_pos.is_empty() && seed_neg.is_empty() {
+        None
+    } else {
+        let mut graines = Lexique::default();
+        if !seed_pos.is_empty() {
+            graines.ajouter_fichier(seed_pos, 1.0).map_err(|e| format!("Erreur graines pos: {}", e))?;
+        }
+        if !seed_neg.is_empty() {
+            graines.ajouter_fichier(seed_neg, -1.0).map_err(|e| format!("Erreur graines neg: {}", e))?;
+        }
+        Some(graines)
+    };
+
+    let appris = induction::apprendre(&tweets, parametres, graines.as_ref());
+    induction::ecrire(path_pos_out, &appris.positifs, parametres.methode)
+        .map_err(|e| format!("Erreur écriture lexique pos: {}", e))?;
+    induction::ecrire(path_neg_out, &appris.negatifs, parametres.methode)
+        .map_err(|e| format!("Erreur écriture lexique neg: {}", e))?;
+    Ok(appris)
+}
+
+/// Probabilités de chaque classe (ordre de validation::CLASSES) pour chaque texte. L'approche par lexique
+/// ne donne qu'une décision : toute la probabilité va à la classe estimée. Utilisé par l'ensemble de classifieurs.
+pub fn probabilites(textes: &[&str], path_pos: &str, path_neg: &str, weight: f64, modificateurs: &Modificateurs) -> Result<Vec<[f64; 3]>, String> {
+    let lexique = charger_lexique(path_pos, path_neg)?;
+
+    Ok(textes
+        .iter()
+        .map(|texte| {
+            let mut probabilites = [0.0; 3];
+            if let Some(c) = validation::index_classe(analyser_tweet(texte, &lexique, modificateurs, weight as f32)) {
+                probabilites[c] = 1.0;
+            }
+            probabilites
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modificateurs_propres_a_chaque_appel() {
+        let chemin = std::env::temp_dir().join(format!("{}_{}", std::process::id(), "naive_positifs.txt"));
+        std::fs::write(&chemin, "good,great").unwrap();
+        let chemin = chemin.to_string_lossy().into_owned();
+        let negation = Modificateurs { negation: true, ..Modificateurs::AUCUN };
+
+        let textes = ["this is good", "this is not good"];
+        let sans = probabilites(&textes, &chemin, "", 0.5, &Modificateurs::default()).unwrap();
+        let avec = probabilites(&textes, &chemin, "", 0.5, &negation).unwrap();
+        std::fs::remove_file(&chemin).unwrap();
+
+        assert_eq!(sans, vec![[0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(avec, vec![[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]);
+    }
+}
@@ -0,0 +1,186 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use csv::{ByteRecord, ReaderBuilder, Writer};
+
+use crate::csv_ext::encoding::detect_and_decode;
+use super::analyser_tweet;
+use super::lexique::Lexique;
+use super::modificateurs::Modificateurs;
+
+/// Colonne d'un CSV, par son indice (à partir de 0) ou par son nom dans l'en-tête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Colonne {
+    Indice(usize),
+    Nom(String),
+}
+
+impl FromStr for Colonne {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Nom de colonne vide".to_string());
+        }
+        Ok(s.parse::<usize>().map(Colonne::Indice).unwrap_or_else(|_| Colonne::Nom(s.to_string())))
+    }
+}
+
+impl Colonne {
+    fn resoudre(&self, entete: Option<&[String]>) -> Result<usize, String> {
+        match (self, entete) {
+            (Colonne::Indice(i), _) => Ok(*i),
+            (Colonne::Nom(nom), Some(entete)) => entete
+                .iter()
+                .position(|c| c.trim() == nom)
+                .ok_or_else(|| format!("Colonne « {} » absente de l'en-tête", nom)),
+            (Colonne::Nom(nom), None) => Err(format!("Colonne « {} » désignée par son nom sans en-tête", nom)),
+        }
+    }
+}
+
+pub struct Annotation {
+    // Colonne du texte à classer
+    pub colonne_texte: Colonne,
+    // Colonnes recopiées après l'étiquette (la colonne du texte seule si vide)
+    pub colonnes_sortie: Vec<Colonne>,
+    // La première ligne de l'entrée est un en-tête
+    pub entete: bool,
+    pub weight: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct Bilan {
+    pub annotees: usize,
+    // Lignes trop courtes pour contenir une des colonnes demandées
+    pub ignorees: usize,
+    // Nombre de tweets par classe, dans l'ordre 0, 2, 4
+    pub par_classe: [usize; 3],
+}
+
+/// Annote chaque ligne de `entree` et écrit `label,colonnes...` dans `sortie`, précédé d'un en-tête
+/// comme les fichiers annotés du projet. L'encodage de chaque champ est détecté (Sentiment140 contient du Latin-1).
+pub fn annoter(entree: &str, sortie: &str, annotation: &Annotation, lexique: &Lexique, modificateurs: &Modificateurs) -> Result<Bilan, Box<dyn Error>> {
+    let mut reader = ReaderBuilder::new().has_headers(annotation.entete).flexible(true).from_path(entree)?;
+    let entete: Option<Vec<String>> = if annotation.entete {
+        Some(reader.byte_headers()?.iter().map(|c| detect_and_decode(c).0).collect())
+    } else {
+        None
+    };
+
+    let texte = annotation.colonne_texte.resoudre(entete.as_deref())?;
+    let colonnes = if annotation.colonnes_sortie.is_empty() {
+        vec![texte]
+    } else {
+        annotation.colonnes_sortie.iter().map(|c| c.resoudre(entete.as_deref())).collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut writer = Writer::from_path(sortie)?;
+    let mut ligne_entete = vec!["label".to_string()];
+    ligne_entete.extend(colonnes.iter().map(|&c| match &entete {
+        Some(entete) if c < entete.len() => entete[c].clone(),
+        _ if c == texte => "text".to_string(),
+        _ => format!("column_{}", c),
+    }));
+    writer.write_record(&ligne_entete)?;
+
+    let mut bilan = Bilan::default();
+    let mut record = ByteRecord::new();
+    while reader.read_byte_record(&mut record)? {
+        let (Some(contenu), true) = (record.get(texte), colonnes.iter().all(|&c| c < record.len())) else {
+            bilan.ignorees += 1;
+            continue;
+        };
+        let classe = analyser_tweet(&detect_and_decode(contenu).0, lexique, modificateurs, annotation.weight);
+        bilan.annotees += 1;
+        bilan.par_classe[(classe / 2) as usize] += 1;
+
+        let mut ligne = vec![classe.to_string()];
+        ligne.extend(colonnes.iter().map(|&c| detect_and_decode(&record[c]).0));
+        writer.write_record(&ligne)?;
+    }
+    writer.flush()?;
+    Ok(bilan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation;
+
+    fn chemin(nom: &str) -> String {
+        std::env::temp_dir().join(format!("{}_{}", std::process::id(), nom)).to_string_lossy().into_owned()
+    }
+
+    fn lexique() -> Lexique {
+        let (positifs, negatifs) = (chemin("annotation_positifs.txt"), chemin("annotation_negatifs.txt"));
+        std::fs::write(&positifs, "good,great").unwrap();
+        std::fs::write(&negatifs, "bad,awful").unwrap();
+        let lexique = crate::naive::charger_lexique(&positifs, &negatifs);
+        std::fs::remove_file(&positifs).unwrap();
+        std::fs::remove_file(&negatifs).unwrap();
+        lexique.unwrap()
+    }
+
+    #[test]
+    fn colonne_par_indice_ou_par_nom() {
+        assert_eq!("2".parse::<Colonne>(), Ok(Colonne::Indice(2)));
+        assert_eq!(" text ".parse::<Colonne>(), Ok(Colonne::Nom("text".to_string())));
+        assert!("".parse::<Colonne>().is_err());
+        let entete = ["id".to_string(), "text".to_string()];
+        assert_eq!(Colonne::Nom("text".to_string()).resoudre(Some(&entete)), Ok(1));
+        assert!(Colonne::Nom("user".to_string()).resoudre(Some(&entete)).is_err());
+        assert!(Colonne::Nom("text".to_string()).resoudre(None).is_err());
+    }
+
+    // Le fichier annoté se relit comme les autres fichiers `label,texte` du projet
+    #[test]
+    fn fichier_annote_relu() {
+        let (entree, sortie) = (chemin("annotation_entree.csv"), chemin("annotation_sortie.csv"));
+        let mut contenu = b"id,text\n1,a good day\n2,an awful day\n3,a day\n4\n5,caf\xe9 bad\n".to_vec();
+        contenu.extend_from_slice(b"6,\"good, great\"\n");
+        std::fs::write(&entree, contenu).unwrap();
+
+        let annotation = Annotation { colonne_texte: Colonne::Nom("text".to_string()), colonnes_sortie: vec![], entete: true, weight: 0.5 };
+        let bilan = annoter(&entree, &sortie, &annotation, &lexique(), &Modificateurs::AUCUN).unwrap();
+        let relu = validation::charger_donnees(&sortie);
+        let ecrit = std::fs::read_to_string(&sortie).unwrap();
+        std::fs::remove_file(&entree).unwrap();
+        std::fs::remove_file(&sortie).unwrap();
+
+        assert_eq!((bilan.annotees, bilan.ignorees, bilan.par_classe), (5, 1, [2, 1, 2]));
+        assert!(ecrit.starts_with("label,text\n"), "{}", ecrit);
+        let relu: Vec<(i32, String)> = relu.unwrap().into_iter().map(|t| (t.etiquette, t.contenu)).collect();
+        assert_eq!(
+            relu,
+            vec![
+                (4, "a good day".to_string()),
+                (0, "an awful day".to_string()),
+                (2, "a day".to_string()),
+                (0, "café bad".to_string()),
+                (4, "good, great".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn colonnes_recopiees_sans_en_tete() {
+        let (entree, sortie) = (chemin("annotation_sans_en_tete.csv"), chemin("annotation_colonnes.csv"));
+        std::fs::write(&entree, "7,bad movie,alice\n").unwrap();
+
+        let annotation = Annotation {
+            colonne_texte: Colonne::Indice(1),
+            colonnes_sortie: vec![Colonne::Indice(0), Colonne::Indice(1)],
+            entete: false,
+            weight: 0.5,
+        };
+        let bilan = annoter(&entree, &sortie, &annotation, &lexique(), &Modificateurs::AUCUN).unwrap();
+        let ecrit = std::fs::read_to_string(&sortie).unwrap();
+        std::fs::remove_file(&entree).unwrap();
+        std::fs::remove_file(&sortie).unwrap();
+
+        assert_eq!(bilan.annotees, 1);
+        assert_eq!(ecrit, "label,column_0,text\n0,7,bad movie\n");
+    }
+}
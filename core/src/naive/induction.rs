@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use super::lexique::{Lexique, tokeniser};
+
+// z-score minimal du log-odds (écart significatif à 5 %)
+const Z_MINIMAL: f64 = 1.96;
+
+// Score d'un mot entre les tweets positifs (4) et négatifs (0)
+#[derive(Debug, Clone, Copy)]
+pub enum Methode {
+    // Log-odds avec a priori de Dirichlet informatif (Monroe et al., 2008) : les occurrences du mot dans tous
+    // les tweets annotés, neutres compris, servent d'a priori, le score est le z-score de l'écart
+    LogOdds,
+    // PMI(mot, positif) - PMI(mot, négatif), soit log2(P(mot|positif) / P(mot|négatif)) avec lissage de Laplace
+    Pmi,
+}
+
+impl From<i64> for Methode {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => Methode::Pmi,
+            _ => Methode::LogOdds,
+        }
+    }
+}
+
+pub struct Parametres {
+    pub methode: Methode,
+    // Occurrences minimales du mot dans les tweets positifs et négatifs réunis
+    pub min_occurrences: usize,
+    // Nombre maximal de mots par polarité, graines non comprises (0 = pas de limite)
+    pub nb_max: usize,
+}
+
+pub struct LexiqueAppris {
+    // Triés par score absolu décroissant, scores positifs d'un côté et négatifs de l'autre
+    pub positifs: Vec<(String, f64)>,
+    pub negatifs: Vec<(String, f64)>,
+    // Graines dont les données donnent la polarité contraire
+    pub conflits_graines: usize,
+}
+
+/// Apprend les mots positifs et négatifs de tweets (texte, étiquette). Les entrées de `graines` sont toujours gardées
+/// avec leur polarité : avec le score appris s'il est de même signe, sinon avec le score absolu médian de leur liste.
+pub fn apprendre(tweets: &[(&str, i32)], parametres: &Parametres, graines: Option<&Lexique>) -> LexiqueAppris {
+    let mut comptes: HashMap<String, [usize; 3]> = HashMap::new();
+    let mut totaux = [0usize; 3];
+    for &(texte, etiquette) in tweets {
+        let classe = match etiquette {
+            4 => 0,
+            0 => 1,
+            2 => 2,
+            _ => continue,
+        };
+        for mot in tokeniser(texte) {
+            comptes.entry(mot).or_default()[classe] += 1;
+            totaux[classe] += 1;
+        }
+    }
+
+    let vocabulaire = comptes.len() as f64;
+    let scores: HashMap<&str, f64> = comptes
+        .iter()
+        .filter(|(_, c)| c[0] + c[1] >= parametres.min_occurrences.max(1))
+        .map(|(mot, c)| {
+            let score = match parametres.methode {
+                Methode::LogOdds => log_odds(c, &totaux),
+                Methode::Pmi => pmi(c, &totaux, vocabulaire),
+            };
+            (mot.as_str(), score)
+        })
+        .collect();
+
+    let significatif = |score: f64| match parametres.methode {
+        Methode::LogOdds => score.abs() >= Z_MINIMAL,
+        Methode::Pmi => score != 0.0,
+    };
+    let selection = |positif: bool| {
+        let mut liste: Vec<(String, f64)> = scores
+            .iter()
+            .filter(|&(_, &s)| significatif(s) && (s > 0.0) == positif)
+            .map(|(&mot, &s)| (mot.to_string(), s))
+            .collect();
+        trier(&mut liste);
+        if parametres.nb_max > 0 {
+            liste.truncate(parametres.nb_max);
+        }
+        liste
+    };
+    let mut appris = LexiqueAppris { positifs: selection(true), negatifs: selection(false), conflits_graines: 0 };
+
+    if let Some(graines) = graines {
+        let mediane = |liste: &[(String, f64)]| liste.get(liste.len() / 2).map(|&(_, s)| s.abs()).unwrap_or(1.0);
+        let (mediane_pos, mediane_neg) = (mediane(&appris.positifs), mediane(&appris.negatifs));
+
+        for (entree, polarite) in graines.entrees() {
+            let liste = if polarite > 0.0 { &mut appris.positifs } else { &mut appris.negatifs };
+            if liste.iter().any(|(mot, _)| *mot == entree) {
+                continue;
+            }
+            let score = match scores.get(entree.as_str()) {
+                Some(&s) if (s > 0.0) == (polarite > 0.0) => s,
+                appris_contraire => {
+                    if appris_contraire.is_some() {
+                        appris.conflits_graines += 1;
+                    }
+                    if polarite > 0.0 { mediane_pos } else { -mediane_neg }
+                },
+            };
+            // Une graine contredite par les données ne doit pas rester aussi dans l'autre liste
+            let autre = if polarite > 0.0 { &mut appris.negatifs } else { &mut appris.positifs };
+            autre.retain(|(mot, _)| *mot != entree);
+            let liste = if polarite > 0.0 { &mut appris.positifs } else { &mut appris.negatifs };
+            liste.push((entree, score));
+        }
+        trier(&mut appris.positifs);
+        trier(&mut appris.negatifs);
+    }
+    appris
+}
+
+// c et totaux : [positif, négatif, neutre]
+fn log_odds(c: &[usize; 3], totaux: &[usize; 3]) -> f64 {
+    let alpha = (c[0] + c[1] + c[2]) as f64;
+    let alpha_0 = (totaux[0] + totaux[1] + totaux[2]) as f64;
+    let (y_pos, y_neg) = (c[0] as f64, c[1] as f64);
+    let (n_pos, n_neg) = (totaux[0] as f64, totaux[1] as f64);
+
+    let delta = ((y_pos + alpha) / (n_pos + alpha_0 - y_pos - alpha)).ln() - ((y_neg + alpha) / (n_neg + alpha_0 - y_neg - alpha)).ln();
+    let variance = 1.0 / (y_pos + alpha) + 1.0 / (y_neg + alpha);
+    delta / variance.sqrt()
+}
+
+fn pmi(c: &[usize; 3], totaux: &[usize; 3], vocabulaire: f64) -> f64 {
+    let p_pos = (c[0] as f64 + 1.0) / (totaux[0] as f64 + vocabulaire);
+    let p_neg = (c[1] as f64 + 1.0) / (totaux[1] as f64 + vocabulaire);
+    (p_pos / p_neg).log2()
+}
+
+fn trier(liste: &mut [(String, f64)]) {
+    liste.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()).then_with(|| a.0.cmp(&b.0)));
+}
+
+/// Écrit un lexique au format `mot\tscore`, lisible par le classifieur naïf
+pub fn ecrire(chemin: &str, entrees: &[(String, f64)], methode: Methode) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(chemin)?);
+    let nom = match methode {
+        Methode::LogOdds => "z-score du log-odds",
+        Methode::Pmi => "PMI positif - PMI négatif",
+    };
+    writeln!(writer, "# mot\tscore ({})", nom)?;
+    for (mot, score) in entrees {
+        writeln!(writer, "{}\t{:.4}", mot, score)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::naive::modificateurs::Modificateurs;
+
+    // « love » n'apparaît que dans les tweets positifs, « hate » dans les négatifs, « movie » partout
+    fn tweets() -> Vec<(&'static str, i32)> {
+        let mut tweets = Vec::new();
+        for _ in 0..10 {
+            tweets.push(("love movie", 4));
+            tweets.push(("hate movie", 0));
+        }
+        tweets.push(("movie", 2));
+        tweets
+    }
+
+    fn chemin(nom: &str) -> String {
+        std::env::temp_dir().join(format!("{}_{}", std::process::id(), nom)).to_string_lossy().into_owned()
+    }
+
+    fn mots(liste: &[(String, f64)]) -> Vec<&str> {
+        liste.iter().map(|(mot, _)| mot.as_str()).collect()
+    }
+
+    #[test]
+    fn mots_polarises_appris() {
+        for methode in [Methode::LogOdds, Methode::Pmi] {
+            let appris = apprendre(&tweets(), &Parametres { methode, min_occurrences: 1, nb_max: 0 }, None);
+            assert_eq!(mots(&appris.positifs), vec!["love"], "{:?}", methode);
+            assert_eq!(mots(&appris.negatifs), vec!["hate"], "{:?}", methode);
+            assert!(appris.positifs[0].1 > 0.0 && appris.negatifs[0].1 < 0.0);
+        }
+    }
+
+    #[test]
+    fn occurrences_minimales() {
+        let appris = apprendre(&tweets(), &Parametres { methode: Methode::Pmi, min_occurrences: 11, nb_max: 0 }, None);
+        assert!(appris.positifs.is_empty() && appris.negatifs.is_empty());
+    }
+
+    #[test]
+    fn graine_contredite_changee_de_liste() {
+        let fichier_graines = chemin("induction_graines.txt");
+        std::fs::write(&fichier_graines, "hate,great").unwrap();
+        let mut graines = Lexique::default();
+        graines.ajouter_fichier(&fichier_graines, 1.0).unwrap();
+        std::fs::remove_file(&fichier_graines).unwrap();
+
+        let appris = apprendre(&tweets(), &Parametres { methode: Methode::Pmi, min_occurrences: 1, nb_max: 0 }, Some(&graines));
+        assert_eq!(appris.conflits_graines, 1);
+        let mut positifs = mots(&appris.positifs);
+        positifs.sort_unstable();
+        assert_eq!(positifs, vec!["great", "hate", "love"]);
+        assert!(appris.negatifs.is_empty());
+    }
+
+    #[test]
+    fn lexique_ecrit_relu_par_le_classifieur() {
+        let appris = apprendre(&tweets(), &Parametres { methode: Methode::LogOdds, min_occurrences: 1, nb_max: 0 }, None);
+        let (positifs, negatifs) = (chemin("induction_positifs.txt"), chemin("induction_negatifs.txt"));
+        ecrire(&positifs, &appris.positifs, Methode::LogOdds).unwrap();
+        ecrire(&negatifs, &appris.negatifs, Methode::LogOdds).unwrap();
+        let lexique = crate::naive::charger_lexique(&positifs, &negatifs);
+        std::fs::remove_file(&positifs).unwrap();
+        std::fs::remove_file(&negatifs).unwrap();
+
+        let lexique = lexique.unwrap();
+        let score = lexique.scorer("I love this movie", &Modificateurs::AUCUN);
+        assert!(score.positif > 0.0 && score.negatif == 0.0);
+        let score = lexique.scorer("I hate this movie", &Modificateurs::AUCUN);
+        assert!(score.negatif > 0.0 && score.positif == 0.0);
+    }
+}
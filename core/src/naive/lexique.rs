@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+use crate::csv_ext::encoding;
+use super::modificateurs::Modificateurs;
+
+// Constante de normalisation du score composé, la même que VADER
+const ALPHA_COMPOSE: f64 = 15.0;
+
+/// Lexique de sentiment : chaque entrée (mot ou expression de plusieurs mots) a une polarité dans [-1, 1].
+///
+/// Formats de fichier reconnus, ligne par ligne :
+/// - SentiWordNet : `POS\tID\tPosScore\tNegScore\tSynsetTerms\tGloss`, la polarité d'un terme est la moyenne
+///   de PosScore - NegScore sur ses sens (`bon#1 bon#2`), `_` sépare les mots d'une expression ;
+/// - VADER, AFINN : `entrée\tscore[\t...]` ;
+/// - liste simple : entrées séparées par des virgules, qui prennent la polarité par défaut du fichier.
+///
+/// Les scores d'un fichier sont ramenés dans [-1, 1] en les divisant par le plus grand score absolu du fichier
+/// (AFINN va de -5 à 5, VADER de -4 à 4). Les lignes vides ou commençant par `#` sont ignorées.
+/// L'encodage de chaque ligne est détecté, les dictionnaires fournis mélangeant UTF-8 et Latin-1.
+#[derive(Debug, Default)]
+pub struct Lexique {
+    // Entrées découpées avec le même découpage que les tweets
+    entrees: HashMap<Vec<String>, f64>,
+    // Nombre de mots de la plus longue expression
+    longueur_max: usize,
+}
+
+// Polarités des entrées d'un tweet
+#[derive(Debug, Default)]
+pub struct Score {
+    // Somme des polarités positives
+    pub positif: f64,
+    // Somme des valeurs absolues des polarités négatives
+    pub negatif: f64,
+    // Entrées trouvées avec leur polarité, dans l'ordre du tweet
+    pub correspondances: Vec<(String, f64)>,
+}
+
+impl Score {
+    fn ajouter(&mut self, entree: String, polarite: f64) {
+        if polarite > 0.0 {
+            self.positif += polarite;
+        } else {
+            self.negatif -= polarite;
+        }
+        self.correspondances.push((entree, polarite));
+    }
+
+    /// Score composé dans ]-1, 1[ à la manière de VADER : x / sqrt(x² + 15), x étant la somme des polarités
+    pub fn compose(&self) -> f64 {
+        let somme = self.positif - self.negatif;
+        somme / (somme * somme + ALPHA_COMPOSE).sqrt()
+    }
+}
+
+impl Lexique {
+    /// Ajoute les entrées d'un fichier. `polarite_defaut` est utilisée pour les listes sans score
+    /// (1 pour un dictionnaire positif, -1 pour un négatif). Une entrée déjà présente est remplacée.
+    pub fn ajouter_fichier(&mut self, chemin: &str, polarite_defaut: f64) -> Result<(), Box<dyn Error>> {
+        let contenu = fs::read(chemin)?;
+
+        // Somme et nombre de sens par terme, pour la moyenne de SentiWordNet
+        let mut scores: HashMap<Vec<String>, (f64, usize)> = HashMap::new();
+        for ligne in contenu.split(|&octet| octet == b'\n') {
+            let (ligne, _) = encoding::detect_and_decode(ligne);
+            let ligne = ligne.trim();
+            if ligne.is_empty() || ligne.starts_with('#') {
+                continue;
+            }
+            for (entree, score) in lire_ligne(ligne, polarite_defaut) {
+                let mots = tokeniser(&entree);
+                if mots.is_empty() {
+                    continue;
+                }
+                let cumul = scores.entry(mots).or_default();
+                cumul.0 += score;
+                cumul.1 += 1;
+            }
+        }
+
+        let max = scores.values().map(|&(somme, n)| (somme / n as f64).abs()).fold(0.0, f64::max);
+        let echelle = if max > 1.0 { max } else { 1.0 };
+        for (mots, (somme, n)) in scores {
+            let polarite = somme / n as f64 / echelle;
+            if polarite == 0.0 {
+                continue;
+            }
+            self.longueur_max = self.longueur_max.max(mots.len());
+            self.entrees.insert(mots, polarite);
+        }
+        Ok(())
+    }
+
+    /// Entrées (mots séparés par une espace) avec leur polarité
+    pub fn entrees(&self) -> impl Iterator<Item = (String, f64)> + '_ {
+        self.entrees.iter().map(|(mots, &polarite)| (mots.join(" "), polarite))
+    }
+
+    /// Cherche les entrées du lexique dans le tweet, la plus longue expression d'abord à chaque position.
+    /// Les mots d'une expression trouvée ne sont pas comptés une seconde fois. Un modificateur actif
+    /// (« very », « not »...) ne compte pas seul comme entrée, mais peut commencer une expression (« not bad »).
+    pub fn scorer(&self, tweet: &str, modificateurs: &Modificateurs) -> Score {
+        let bruts: Vec<&str> = tweet.split(|c: char| !c.is_alphanumeric()).filter(|s| !s.is_empty()).collect();
+        let mots: Vec<String> = bruts.iter().map(|s| s.to_lowercase()).collect();
+        let en_majuscules: Vec<bool> = bruts.iter().map(|s| est_en_majuscules(s)).collect();
+        // Les majuscules ne marquent l'insistance que si le reste du tweet n'est pas lui aussi en majuscules
+        let insistance = en_majuscules.contains(&false);
+        let mut score = Score::default();
+
+        let mut i = 0;
+        while i < mots.len() {
+            let longueur_max = self.longueur_max.min(mots.len() - i);
+            let longueur_min = if modificateurs.est_modificateur(&mots[i]) { 2 } else { 1 };
+            let trouve = (longueur_min..=longueur_max)
+                .rev()
+                .find_map(|longueur| self.entrees.get(&mots[i..i + longueur]).map(|&polarite| (longueur, polarite)));
+
+            match trouve {
+                Some((longueur, polarite)) => {
+                    let mut polarite = polarite * modificateurs.facteur_contexte(&mots[..i]);
+                    if insistance && en_majuscules[i..i + longueur].iter().all(|&m| m) {
+                        polarite *= modificateurs.facteur_majuscules();
+                    }
+                    score.ajouter(mots[i..i + longueur].join(" "), polarite);
+                    i += longueur;
+                },
+                None => i += 1,
+            }
+        }
+
+        for (emoji, polarite) in modificateurs.emojis(tweet) {
+            score.ajouter(emoji, polarite);
+        }
+
+        let exclamation = modificateurs.facteur_exclamation(tweet);
+        if score.positif > score.negatif {
+            score.positif *= exclamation;
+        } else if score.negatif > score.positif {
+            score.negatif *= exclamation;
+        }
+        score
+    }
+}
+
+// Entrées d'une ligne avec leur score brut
+fn lire_ligne(ligne: &str, polarite_defaut: f64) -> Vec<(String, f64)> {
+    let colonnes: Vec<&str> = ligne.split('\t').collect();
+
+    // SentiWordNet
+    if colonnes.len() >= 5
+        && let (Ok(positif), Ok(negatif)) = (colonnes[2].trim().parse::<f64>(), colonnes[3].trim().parse::<f64>())
+    {
+        return colonnes[4]
+            .split_whitespace()
+            .map(|terme| {
+                let terme = terme.split('#').next().unwrap_or(terme);
+                (terme.replace('_', " "), positif - negatif)
+            })
+            .collect();
+    }
+
+    // VADER, AFINN
+    if colonnes.len() >= 2
+        && let Ok(score) = colonnes[1].trim().parse::<f64>()
+    {
+        return vec![(colonnes[0].to_string(), score)];
+    }
+
+    ligne.split(',').map(|entree| (entree.to_string(), polarite_defaut)).collect()
+}
+
+// Au moins deux lettres, toutes en majuscules (« GOOD », pas « I »)
+fn est_en_majuscules(mot: &str) -> bool {
+    mot.chars().filter(|c| c.is_alphabetic()).count() >= 2 && !mot.chars().any(|c| c.is_lowercase())
+}
+
+// Mots en minuscules, séparés par tout caractère non alphanumérique
+pub fn tokeniser(texte: &str) -> Vec<String> {
+    texte
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
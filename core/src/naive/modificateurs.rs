@@ -0,0 +1,137 @@
+// Règles de VADER, avec des facteurs multiplicatifs puisque les polarités du lexique sont ramenées dans [-1, 1]
+const INTENSIFICATION: f64 = 0.3;
+const ATTENUATION: f64 = 0.3;
+// Un modificateur compte de moins en moins en s'éloignant du mot qu'il modifie (trois mots au plus)
+const PORTEE_INTENSITE: usize = 3;
+const DECROISSANCE: f64 = 0.05;
+// Une négation inverse la polarité en l'atténuant : « pas bon » est moins négatif que « mauvais »
+const FACTEUR_NEGATION: f64 = -0.74;
+const FACTEUR_MAJUSCULES: f64 = 1.2;
+const EXCLAMATION: f64 = 0.1;
+const MAX_EXCLAMATIONS: usize = 4;
+
+const INTENSIFIEURS: &[&str] = &[
+    "very", "really", "so", "extremely", "absolutely", "totally", "incredibly", "super", "too", "most", "completely",
+    "highly", "truly", "utterly", "especially", "exceptionally", "hugely", "remarkably", "damn", "freaking", "frickin",
+    "fucking", "such", "très", "vraiment", "trop", "tellement", "extrêmement", "hyper", "carrément", "particulièrement",
+];
+const ATTENUATEURS: &[&str] = &[
+    "slightly", "somewhat", "kinda", "sorta", "barely", "hardly", "scarcely", "marginally", "partly", "occasionally",
+    "less", "little", "fairly", "peu", "assez", "plutôt", "légèrement", "moyennement", "presque", "moins",
+];
+// "t" est le reste de n't une fois l'apostrophe retirée (don't, can't)
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "none", "nobody", "nothing", "neither", "nor", "nowhere", "without", "cannot", "cant",
+    "dont", "doesnt", "didnt", "isnt", "arent", "wasnt", "werent", "wont", "wouldnt", "couldnt", "shouldnt", "aint",
+    "t", "ne", "pas", "jamais", "aucun", "aucune", "rien", "sans", "ni", "nul",
+];
+
+const EMOTICONES: &[(&str, f64)] = &[
+    (":)", 0.5), (":-)", 0.5), ("(:", 0.5), (":]", 0.5), ("=)", 0.5), (";)", 0.4), (";-)", 0.4), (":p", 0.3),
+    (":P", 0.3), (";p", 0.3), (":D", 0.7), (":-D", 0.7), ("xD", 0.6), ("XD", 0.6), ("<3", 0.8), (":(", -0.5),
+    (":-(", -0.5), ("):", -0.5), (":[", -0.5), ("=(", -0.5), (":'(", -0.7), (":/", -0.3), (":-/", -0.3),
+    (":|", -0.2), ("D:", -0.6), (">:(", -0.8), ("</3", -0.8),
+];
+const EMOJIS: &[(char, f64)] = &[
+    ('😀', 0.6), ('😃', 0.6), ('😄', 0.7), ('😁', 0.6), ('😊', 0.6), ('🙂', 0.4), ('😉', 0.4), ('😍', 0.9),
+    ('🥰', 0.9), ('😘', 0.7), ('😂', 0.5), ('🤣', 0.5), ('😎', 0.5), ('👍', 0.6), ('👏', 0.6), ('🙌', 0.6),
+    ('🎉', 0.7), ('❤', 0.8), ('♥', 0.8), ('💕', 0.8), ('💖', 0.8), ('✨', 0.3), ('🔥', 0.4), ('💯', 0.5),
+    ('😢', -0.6), ('😭', -0.7), ('😞', -0.6), ('😔', -0.5), ('🙁', -0.4), ('☹', -0.5), ('😒', -0.5), ('😩', -0.6),
+    ('😫', -0.6), ('😠', -0.7), ('😡', -0.8), ('🤬', -0.9), ('👎', -0.6), ('💔', -0.8), ('😤', -0.5), ('🤮', -0.8),
+    ('😱', -0.4), ('🙄', -0.4),
+];
+
+/// Règles de valence appliquées par-dessus le lexique, chacune activable séparément (toutes inactives par défaut).
+/// Elles sont passées à chaque classification avec le lexique, par la valeur qui les détient.
+#[derive(Debug, Clone, Copy)]
+pub struct Modificateurs {
+    // « very good » : jusqu'à trois mots avant l'entrée
+    pub intensifieurs: bool,
+    // « kinda good »
+    pub attenuateurs: bool,
+    // « not good » : négation dans les `fenetre_negation` mots avant l'entrée
+    pub negation: bool,
+    pub fenetre_negation: usize,
+    // Chaque « ! » (quatre au plus) renforce la polarité dominante
+    pub exclamation: bool,
+    // Entrée en majuscules dans un tweet qui n'est pas entièrement en majuscules
+    pub majuscules: bool,
+    // Émoticônes et emojis
+    pub emojis: bool,
+}
+
+impl Default for Modificateurs {
+    fn default() -> Self {
+        Self::AUCUN
+    }
+}
+
+impl Modificateurs {
+    pub const AUCUN: Self = Self {
+        intensifieurs: false,
+        attenuateurs: false,
+        negation: false,
+        fenetre_negation: 3,
+        exclamation: false,
+        majuscules: false,
+        emojis: false,
+    };
+
+    // Mot qui modifie les suivants au lieu de compter comme une entrée du lexique
+    pub fn est_modificateur(&self, mot: &str) -> bool {
+        (self.intensifieurs && INTENSIFIEURS.contains(&mot))
+            || (self.attenuateurs && ATTENUATEURS.contains(&mot))
+            || (self.negation && NEGATIONS.contains(&mot))
+    }
+
+    /// Facteur appliqué à la polarité d'une entrée précédée des mots `precedents`
+    pub fn facteur_contexte(&self, precedents: &[String]) -> f64 {
+        let mut facteur = 1.0;
+        for (distance, mot) in precedents.iter().rev().take(PORTEE_INTENSITE).enumerate() {
+            let poids = 1.0 - DECROISSANCE * distance as f64;
+            if self.intensifieurs && INTENSIFIEURS.contains(&mot.as_str()) {
+                facteur *= 1.0 + INTENSIFICATION * poids;
+            } else if self.attenuateurs && ATTENUATEURS.contains(&mot.as_str()) {
+                facteur *= 1.0 - ATTENUATION * poids;
+            }
+        }
+        // Une seule inversion même si la fenêtre contient plusieurs négations (« ne ... pas »)
+        if self.negation && precedents.iter().rev().take(self.fenetre_negation).any(|mot| NEGATIONS.contains(&mot.as_str())) {
+            facteur *= FACTEUR_NEGATION;
+        }
+        facteur
+    }
+
+    pub fn facteur_majuscules(&self) -> f64 {
+        if self.majuscules { FACTEUR_MAJUSCULES } else { 1.0 }
+    }
+
+    /// Facteur de la polarité dominante d'un tweet selon son nombre de points d'exclamation
+    pub fn facteur_exclamation(&self, tweet: &str) -> f64 {
+        if !self.exclamation {
+            return 1.0;
+        }
+        1.0 + EXCLAMATION * tweet.matches('!').count().min(MAX_EXCLAMATIONS) as f64
+    }
+
+    /// Émoticônes (mots entiers, ponctuation finale ignorée) et emojis du tweet avec leur polarité
+    pub fn emojis(&self, tweet: &str) -> Vec<(String, f64)> {
+        if !self.emojis {
+            return Vec::new();
+        }
+        let mut trouves = Vec::new();
+        for mot in tweet.split_whitespace() {
+            let mot_nu = mot.trim_end_matches([',', '.', '!', '?']);
+            if let Some(&(emoticone, polarite)) = EMOTICONES.iter().find(|(e, _)| *e == mot_nu) {
+                trouves.push((emoticone.to_string(), polarite));
+                continue;
+            }
+            for c in mot.chars() {
+                if let Some(&(emoji, polarite)) = EMOJIS.iter().find(|(e, _)| *e == c) {
+                    trouves.push((emoji.to_string(), polarite));
+                }
+            }
+        }
+        trouves
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+// Nombre de threads utilisés pour les calculs parallèles (0 = autant que de cœurs)
+static NB_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+// Pool construit pour le nombre de threads courant, gardé d'un appel à l'autre
+static POOL: RwLock<Option<(usize, Arc<ThreadPool>)>> = RwLock::new(None);
+
+pub fn set_nb_threads(nb: usize) {
+    NB_THREADS.store(nb, Ordering::Relaxed);
+    let mut pool = POOL.write().unwrap_or_else(|e| e.into_inner());
+    if pool.as_ref().is_some_and(|(taille, _)| *taille != nb) {
+        *pool = None;
+    }
+}
+
+pub fn nb_threads() -> usize {
+    NB_THREADS.load(Ordering::Relaxed)
+}
+
+// Pool correspondant au nombre de threads configuré, construit au premier usage
+fn pool() -> Option<Arc<ThreadPool>> {
+    let nb = NB_THREADS.load(Ordering::Relaxed);
+    if let Some((taille, pool)) = POOL.read().unwrap_or_else(|e| e.into_inner()).as_ref()
+        && *taille == nb
+    {
+        return Some(Arc::clone(pool));
+    }
+
+    let mut cache = POOL.write().unwrap_or_else(|e| e.into_inner());
+    match cache.as_ref() {
+        Some((taille, pool)) if *taille == nb => Some(Arc::clone(pool)),
+        _ => {
+            let pool = Arc::new(ThreadPoolBuilder::new().num_threads(nb).build().ok()?);
+            *cache = Some((nb, Arc::clone(&pool)));
+            Some(pool)
+        },
+    }
+}
+
+/// Exécute `op` dans un pool rayon dimensionné selon le nombre de threads configuré.
+/// Les itérateurs parallèles lancés dans `op` utilisent ce pool.
+pub fn executer<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match pool() {
+        Some(pool) => pool.install(op),
+        // Impossible de créer le pool : on reste sur le pool global
+        None => op(),
+    }
+}
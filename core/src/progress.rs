@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// État partagé entre un calcul long et celui qui le suit (les tâches Godot).
+// Chaque champ est atomique ou verrouillé pour être mis à jour depuis les threads de rayon.
+// La valeur par défaut est un suivi que personne ne lit, pour les appels synchrones (CLI, Python, serveur).
+#[derive(Clone, Default)]
+pub struct Progress {
+    cancelled: Arc<AtomicBool>,
+    done: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+    logs: Arc<Mutex<Vec<String>>>,
+}
+
+impl Progress {
+    pub fn set_total(&self, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, steps: usize) {
+        self.done.fetch_add(steps, Ordering::Relaxed);
+    }
+
+    pub fn set_done(&self, done: usize) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    // (fait, total)
+    pub fn state(&self) -> (usize, usize) {
+        (self.done.load(Ordering::Relaxed), self.total.load(Ordering::Relaxed))
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn log(&self, message: String) {
+        if let Ok(mut logs) = self.logs.lock() {
+            logs.push(message);
+        }
+    }
+
+    pub fn take_logs(&self) -> Vec<String> {
+        match self.logs.lock() {
+            Ok(mut logs) => std::mem::take(&mut *logs),
+            Err(_) => vec![],
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::bayes::{self, BayesModel};
+use crate::cleandata::entry;
+use crate::clustering;
+use crate::knn::{self, ModeleKnn};
+use crate::modele::Modele;
+use crate::naive::{self, modificateurs::Modificateurs};
+use crate::progress::Progress;
+use crate::validation::{self, Rapport};
+
+// Liaisons Python (module `twitter_classification_core`), construites avec la feature `python`.
+// Les étiquettes sont 0, 2 et 4 ; les probabilités et métriques par classe suivent cet ordre (validation::CLASSES).
+
+/// Modèle entraîné (Bayes, KNN ou clustering), enregistrable au format de la ligne de commande et du serveur
+#[pyclass(name = "Model")]
+struct ModelePython {
+    modele: Modele,
+}
+
+#[pymethods]
+impl ModelePython {
+    #[staticmethod]
+    #[pyo3(signature = (texts, labels, vote = 0, representation = 0, ngram = 0))]
+    fn fit_bayes(texts: Vec<String>, labels: Vec<i32>, vote: i64, representation: i64, ngram: i64) -> PyResult<Self> {
+        let entrainement = paires(&texts, &labels)?;
+        Ok(Self { modele: Modele::Bayes(BayesModel::depuis_paires(&entrainement, vote, representation, ngram)) })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (texts, labels, k = 3, vote = 0))]
+    fn fit_knn(texts: Vec<String>, labels: Vec<i32>, k: usize, vote: i64) -> PyResult<Self> {
+        let entrainement = paires(&texts, &labels)?;
+        Ok(Self { modele: Modele::Knn(ModeleKnn::new(&entrainement, k.max(1), vote)) })
+    }
+
+    /// Coupe en `k` clusters d'un fichier `label,texte` (le modèle de clustering garde son jeu de données)
+    #[staticmethod]
+    #[pyo3(signature = (path, k = 3, method = 0))]
+    fn fit_clustering(path: &str, k: usize, method: usize) -> PyResult<Self> {
+        let modele = Modele::clustering(path, k, method).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { modele })
+    }
+
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let modele = Modele::charger(path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+        Ok(Self { modele })
+    }
+
+    fn save(&self, path: &str) -> PyResult<()> {
+        self.modele.enregistrer(path).map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.modele.algorithme()
+    }
+
+    fn predict<'py>(&mut self, py: Python<'py>, texts: Vec<String>) -> Bound<'py, PyArray1<i32>> {
+        let modele = &mut self.modele;
+        let labels: Vec<i32> = py.detach(|| texts.iter().map(|texte| modele.classifier(texte)).collect());
+        labels.into_pyarray(py)
+    }
+
+    /// Tableau (n, 3) des probabilités de chaque classe
+    fn predict_proba<'py>(&mut self, py: Python<'py>, texts: Vec<String>) -> Bound<'py, PyArray2<f64>> {
+        let modele = &mut self.modele;
+        let probabilites: Vec<f64> = py.detach(|| texts.iter().flat_map(|texte| modele.probabilites(texte)).collect());
+        Array2::from_shape_vec((texts.len(), 3), probabilites).expect("3 probabilités par texte").into_pyarray(py)
+    }
+
+    /// Explication JSON de la classe du texte
+    fn explain(&mut self, text: &str) -> PyResult<String> {
+        serde_json::to_string(&self.modele.expliquer(text)).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// Textes et étiquettes d'un fichier `label,texte` (première ligne ignorée)
+#[pyfunction]
+fn load_dataset<'py>(py: Python<'py>, path: &str) -> PyResult<(Vec<String>, Bound<'py, PyArray1<i32>>)> {
+    let donnees = knn::charger_donnees(path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let (textes, etiquettes): (Vec<String>, Vec<i32>) = donnees.into_iter().map(|t| (t.contenu, t.etiquette)).unzip();
+    Ok((textes, etiquettes.into_pyarray(py)))
+}
+
+/// Nettoie un CSV de tweets comme CleanData::clean_data, écrit `label,texte` dans `output`.
+/// `rules` : fichier de règles TOML/JSON à la place des filtres automatiques.
+/// Renvoie le chemin absolu du fichier écrit et les messages des filtres.
+#[pyfunction]
+#[pyo3(signature = (input, output, rules = None))]
+fn clean_data(py: Python<'_>, input: &str, output: &str, rules: Option<&str>) -> PyResult<(String, Vec<String>)> {
+    let mut logs = Vec::new();
+    let chemin = py
+        .detach(|| entry::clean_data_to(input, output, rules, &mut |message| logs.push(message), &Progress::default()))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok((chemin, logs))
+}
+
+/// Métriques des prédictions `y_pred` face aux étiquettes `y_true`
+#[pyfunction]
+fn metrics<'py>(py: Python<'py>, y_true: Vec<i32>, y_pred: Vec<i32>) -> PyResult<Bound<'py, PyDict>> {
+    if y_true.len() != y_pred.len() {
+        return Err(PyValueError::new_err("y_true et y_pred n'ont pas la même longueur"));
+    }
+    let mut matrice = [[0; 3]; 3];
+    for (&reel, &estime) in y_true.iter().zip(&y_pred) {
+        validation::ajouter_prediction(&mut matrice, reel, estime);
+    }
+    rapport(py, &Rapport::new(&matrice))
+}
+
+/// Évaluation de Bayes sur une division stratifiée 2/3 - 1/3 d'un fichier `label,texte`
+#[pyfunction]
+#[pyo3(signature = (path, vote = 0, representation = 0, ngram = 0, seed = 0))]
+fn evaluate_bayes<'py>(py: Python<'py>, path: &str, vote: i64, representation: i64, ngram: i64, seed: i64) -> PyResult<Bound<'py, PyDict>> {
+    let matrice = py
+        .detach(|| bayes::matrice_evaluation(path, vote, representation, ngram, seed, &Progress::default()))
+        .map_err(PyValueError::new_err)?;
+    rapport(py, &Rapport::new(&matrice))
+}
+
+/// Évaluation des KNN sur une division stratifiée 2/3 - 1/3 d'un fichier `label,texte`
+#[pyfunction]
+#[pyo3(signature = (path, k = 3, vote = 0, seed = 0))]
+fn evaluate_knn<'py>(py: Python<'py>, path: &str, k: i64, vote: i64, seed: i64) -> PyResult<Bound<'py, PyDict>> {
+    let matrice = py
+        .detach(|| knn::matrice_evaluation(path, k, vote, seed, &Progress::default()))
+        .map_err(PyValueError::new_err)?;
+    rapport(py, &Rapport::new(&matrice))
+}
+
+/// Évaluation du classifieur naïf sur une division stratifiée 2/3 - 1/3 d'un fichier `label,texte`.
+/// `modifiers` : règles de valence (`intensifiers`, `diminishers`, `negation`, `exclamation`, `caps`, `emoji`
+/// booléens et `negation_window`), toutes inactives par défaut.
+#[pyfunction]
+#[pyo3(signature = (path, positive, negative = "", weight = 0.5, modifiers = None, seed = 0))]
+fn evaluate_naive<'py>(
+    py: Python<'py>,
+    path: &str,
+    positive: &str,
+    negative: &str,
+    weight: f64,
+    modifiers: Option<&Bound<'py, PyDict>>,
+    seed: i64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let modificateurs = match modifiers {
+        Some(options) => lire_modificateurs(options)?,
+        None => Modificateurs::default(),
+    };
+    let weight = weight.clamp(0.0, 1.0);
+    let matrice = py
+        .detach(|| naive::matrice_evaluation(path, positive, negative, weight, &modificateurs, seed, &Progress::default()))
+        .map_err(PyValueError::new_err)?;
+    rapport(py, &Rapport::new(&matrice))
+}
+
+/// Évaluation du clustering sur une division stratifiée 2/3 - 1/3 d'un fichier `label,texte`,
+/// avec les métriques de la partition (`silhouette`, `davies_bouldin`, `purity`, `nmi`, `ari`)
+#[pyfunction]
+#[pyo3(signature = (path, k = 3, method = 0, seed = 0))]
+fn evaluate_clustering<'py>(py: Python<'py>, path: &str, k: usize, method: usize, seed: i64) -> PyResult<Bound<'py, PyDict>> {
+    let resultat = py
+        .detach(|| clustering::run_evaluation_pipeline(path, k, method, seed, &Progress::default()).map_err(|e| e.to_string()))
+        .map_err(PyValueError::new_err)?;
+    let dict = rapport(py, &Rapport::new(&resultat.confusion))?;
+    let metriques = &resultat.metriques;
+    dict.set_item("silhouette", metriques.silhouette)?;
+    dict.set_item("davies_bouldin", metriques.davies_bouldin)?;
+    dict.set_item("purity", metriques.purete)?;
+    dict.set_item("nmi", metriques.nmi)?;
+    dict.set_item("ari", metriques.ari)?;
+    Ok(dict)
+}
+
+// Les clés absentes gardent la valeur par défaut, comme Naive.set_modifiers côté Godot
+fn lire_modificateurs(options: &Bound<'_, PyDict>) -> PyResult<Modificateurs> {
+    let mut m = Modificateurs::default();
+    for (cle, valeur) in options.iter() {
+        let cle: String = cle.extract()?;
+        match cle.as_str() {
+            "intensifiers" => m.intensifieurs = valeur.extract()?,
+            "diminishers" => m.attenuateurs = valeur.extract()?,
+            "negation" => m.negation = valeur.extract()?,
+            "exclamation" => m.exclamation = valeur.extract()?,
+            "caps" => m.majuscules = valeur.extract()?,
+            "emoji" => m.emojis = valeur.extract()?,
+            "negation_window" => m.fenetre_negation = valeur.extract::<usize>()?.max(1),
+            _ => return Err(PyValueError::new_err(format!("Modificateur inconnu : {}", cle))),
+        }
+    }
+    Ok(m)
+}
+
+// Matrice de confusion (3, 3) et métriques par classe en tableaux numpy
+fn rapport<'py>(py: Python<'py>, rapport: &Rapport) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    let matrice: Vec<i32> = rapport.matrice.iter().flatten().copied().collect();
+    dict.set_item("matrix", Array2::from_shape_vec((3, 3), matrice).expect("matrice 3 x 3").into_pyarray(py))?;
+    dict.set_item("classes", validation::CLASSES.to_vec().into_pyarray(py))?;
+    dict.set_item("accuracy", rapport.exactitude)?;
+    dict.set_item("f1_macro", rapport.f1_macro)?;
+    dict.set_item("precision", rapport.classes.iter().map(|c| c.precision).collect::<Vec<_>>().into_pyarray(py))?;
+    dict.set_item("recall", rapport.classes.iter().map(|c| c.rappel).collect::<Vec<_>>().into_pyarray(py))?;
+    dict.set_item("f1", rapport.classes.iter().map(|c| c.f1).collect::<Vec<_>>().into_pyarray(py))?;
+    dict.set_item("support", rapport.classes.iter().map(|c| c.support).collect::<Vec<_>>().into_pyarray(py))?;
+    Ok(dict)
+}
+
+fn paires<'a>(textes: &'a [String], etiquettes: &[i32]) -> PyResult<Vec<(&'a str, i32)>> {
+    if textes.len() != etiquettes.len() {
+        return Err(PyValueError::new_err("texts et labels n'ont pas la même longueur"));
+    }
+    Ok(textes.iter().map(String::as_str).zip(etiquettes.iter().copied()).collect())
+}
+
+#[pymodule]
+fn twitter_classification_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ModelePython>()?;
+    m.add_function(wrap_pyfunction!(load_dataset, m)?)?;
+    m.add_function(wrap_pyfunction!(clean_data, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_bayes, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_knn, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_naive, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_clustering, m)?)?;
+    Ok(())
+}
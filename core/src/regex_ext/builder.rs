@@ -1,6 +1,6 @@
 use regex::Regex;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct RegexLogicalBuilder {
     proc_result : String,   // Current state of the processed regex string
 }
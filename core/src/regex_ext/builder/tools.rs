@@ -11,6 +11,6 @@ impl RegexLogicalBuilder {
     }
 
     pub fn strings_to_builders(strings: &[&str]) -> Vec<RegexLogicalBuilder> {
-        strings.into_iter().map(|s| RegexLogicalBuilder::from(*s)).collect()
+        strings.iter().map(|s| RegexLogicalBuilder::from(*s)).collect()
     }
 }
\ No newline at end of file
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+// Outils communs de sélection de modèle (validation croisée, métriques sur la matrice de confusion)
+
+// Indices de la matrice de confusion : 0=négatif, 2=neutre, 4=positif
+pub const CLASSES: [i32; 3] = [0, 2, 4];
+
+// Étiquette des tweets qui restent à annoter
+pub const NON_ANNOTE: i32 = -1;
+
+pub fn index_classe(etiquette: i32) -> Option<usize> {
+    CLASSES.iter().position(|&c| c == etiquette)
+}
+
+// Une graine négative donne un tirage aléatoire, sinon le tirage est reproductible
+pub fn generateur(graine: i64) -> StdRng {
+    if graine < 0 {
+        StdRng::from_os_rng()
+    } else {
+        StdRng::seed_from_u64(graine as u64)
+    }
+}
+
+/// Tweet d'un fichier annoté `label,texte`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweetEtiquete {
+    pub contenu: String,
+    pub etiquette: i32, // 4=positif, 2=neutre, 0=négatif
+}
+
+/// Charge un fichier annoté `label,texte` : l'en-tête et les lignes sans étiquette numérique sont ignorés,
+/// le texte peut contenir des virgules.
+pub fn charger_donnees(chemin: &str) -> Result<Vec<TweetEtiquete>, Box<dyn Error>> {
+    lire_fichier(chemin, |premiere| premiere.parse::<i32>().ok())
+}
+
+/// Charge un fichier `label,texte` dont seuls quelques tweets sont annotés : une première colonne vide ou à -1
+/// marque un tweet à annoter (NON_ANNOTE). Les autres étiquettes hors de CLASSES sont ignorées, comme les lignes
+/// dont la première colonne n'est ni vide ni un entier.
+pub fn charger_donnees_partielles(chemin: &str) -> Result<Vec<TweetEtiquete>, Box<dyn Error>> {
+    lire_fichier(chemin, |premiere| {
+        let premiere = premiere.trim().trim_matches('"');
+        if premiere.is_empty() {
+            return Some(NON_ANNOTE);
+        }
+        premiere.parse::<i32>().ok().filter(|&e| e == NON_ANNOTE || index_classe(e).is_some())
+    })
+}
+
+// Lignes après l'en-tête dont `etiquette` accepte la première colonne
+fn lire_fichier(chemin: &str, etiquette: impl Fn(&str) -> Option<i32>) -> Result<Vec<TweetEtiquete>, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(chemin)?);
+    let mut donnees = Vec::new();
+    for ligne in reader.lines().skip(1) {
+        let ligne = ligne?;
+        let colonnes: Vec<&str> = ligne.split(',').collect();
+        if colonnes.len() >= 2
+            && let Some(etiquette) = etiquette(colonnes[0])
+        {
+            let contenu = colonnes[1..].join(",").trim_matches('"').to_string();
+            donnees.push(TweetEtiquete { contenu, etiquette });
+        }
+    }
+    Ok(donnees)
+}
+
+/// Division stratifiée (mêmes proportions de classes dans l'entraînement et le test) utilisée par toutes les évaluations.
+/// Une graine positive ou nulle rend la division reproductible (classes parcourues dans l'ordre).
+pub fn diviser_donnees_stratifiee<T: Clone>(donnees: &[T], etiquette: impl Fn(&T) -> i32, ratio_train: f64, graine: i64) -> (Vec<T>, Vec<T>) {
+    let mut rng = generateur(graine);
+    let mut par_classe: BTreeMap<i32, Vec<T>> = BTreeMap::new();
+    for donnee in donnees {
+        par_classe.entry(etiquette(donnee)).or_default().push(donnee.clone());
+    }
+
+    let mut entrainement = Vec::new();
+    let mut test = Vec::new();
+    for (_, mut donnees_classe) in par_classe {
+        donnees_classe.shuffle(&mut rng);
+        let split = (donnees_classe.len() as f64 * ratio_train) as usize;
+        entrainement.extend_from_slice(&donnees_classe[..split]);
+        test.extend_from_slice(&donnees_classe[split..]);
+    }
+
+    // Mélanger pour ne pas avoir les classes groupées
+    entrainement.shuffle(&mut rng);
+    test.shuffle(&mut rng);
+    (entrainement, test)
+}
+
+/// Découpe les indices des données en `n_plis` plis stratifiés :
+/// chaque classe est répartie à tour de rôle dans les plis pour garder les mêmes proportions.
+pub fn plis_stratifies(etiquettes: &[i32], n_plis: usize, rng: &mut StdRng) -> Vec<Vec<usize>> {
+    let n_plis = n_plis.max(2);
+    let mut par_classe: HashMap<i32, Vec<usize>> = HashMap::new();
+    for (idx, &etiquette) in etiquettes.iter().enumerate() {
+        par_classe.entry(etiquette).or_default().push(idx);
+    }
+
+    // Parcours des classes dans un ordre fixe pour que la graine suffise à reproduire le découpage
+    let mut classes: Vec<i32> = par_classe.keys().copied().collect();
+    classes.sort_unstable();
+
+    let mut plis = vec![Vec::new(); n_plis];
+    let mut pli_courant = 0;
+    for classe in classes {
+        let indices = par_classe.get_mut(&classe).unwrap();
+        indices.shuffle(rng);
+        for &idx in indices.iter() {
+            plis[pli_courant].push(idx);
+            pli_courant = (pli_courant + 1) % n_plis;
+        }
+    }
+    plis
+}
+
+// Renvoie (entraînement, test) pour le pli `i`
+pub fn separer_pli(plis: &[Vec<usize>], i: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut entrainement = Vec::new();
+    for (j, pli) in plis.iter().enumerate() {
+        if j != i {
+            entrainement.extend_from_slice(pli);
+        }
+    }
+    (entrainement, plis[i].clone())
+}
+
+pub fn ajouter_prediction(matrice: &mut [[i32; 3]; 3], reel: i32, estime: i32) {
+    if let (Some(idx_reel), Some(idx_estime)) = (index_classe(reel), index_classe(estime)) {
+        matrice[idx_reel][idx_estime] += 1;
+    }
+}
+
+pub fn exactitude(matrice: &[[i32; 3]; 3]) -> f64 {
+    let total: i32 = matrice.iter().flatten().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let corrects: i32 = (0..3).map(|i| matrice[i][i]).sum();
+    corrects as f64 / total as f64
+}
+
+// Moyenne des F1 des classes présentes (réellement ou en estimation)
+pub fn f1_macro(matrice: &[[i32; 3]; 3]) -> f64 {
+    let mut somme = 0.0;
+    let mut n_classes = 0;
+    for (c, ligne) in matrice.iter().enumerate() {
+        let vrais_positifs = ligne[c] as f64;
+        let n_reel: i32 = ligne.iter().sum();
+        let n_estime: i32 = (0..3).map(|r| matrice[r][c]).sum();
+        if n_reel + n_estime == 0 {
+            continue;
+        }
+        somme += 2.0 * vrais_positifs / (n_reel + n_estime) as f64;
+        n_classes += 1;
+    }
+    if n_classes == 0 { 0.0 } else { somme / n_classes as f64 }
+}
+
+/// Métriques d'une matrice de confusion, pour une sortie lisible par d'autres programmes (JSON)
+#[derive(Debug, Clone, Serialize)]
+pub struct Rapport {
+    // matrice[réel][estimé], dans l'ordre de CLASSES
+    pub matrice: [[i32; 3]; 3],
+    pub total: i32,
+    pub exactitude: f64,
+    pub f1_macro: f64,
+    pub classes: Vec<MetriquesClasse>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetriquesClasse {
+    pub classe: i32,
+    pub precision: f64,
+    pub rappel: f64,
+    pub f1: f64,
+    // Nombre de tweets réellement de cette classe
+    pub support: i32,
+}
+
+impl Rapport {
+    pub fn new(matrice: &[[i32; 3]; 3]) -> Self {
+        let ratio = |a: i32, b: i32| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+        let classes = CLASSES
+            .iter()
+            .enumerate()
+            .map(|(c, &classe)| {
+                let n_reel: i32 = matrice[c].iter().sum();
+                let n_estime: i32 = (0..3).map(|r| matrice[r][c]).sum();
+                MetriquesClasse {
+                    classe,
+                    precision: ratio(matrice[c][c], n_estime),
+                    rappel: ratio(matrice[c][c], n_reel),
+                    f1: ratio(2 * matrice[c][c], n_reel + n_estime),
+                    support: n_reel,
+                }
+            })
+            .collect();
+
+        Self {
+            matrice: *matrice,
+            total: matrice.iter().flatten().sum(),
+            exactitude: exactitude(matrice),
+            f1_macro: f1_macro(matrice),
+            classes,
+        }
+    }
+}
+
+// Formate la matrice de confusion selon le format demandé
+pub fn format_matrice_confusion(matrice: &[[i32; 3]]) -> String {
+    let n_pos_reel = matrice[2][0] + matrice[2][1] + matrice[2][2]; // Réel: Positif (index 2)
+    let n_neg_reel = matrice[0][0] + matrice[0][1] + matrice[0][2]; // Réel: Négatif (index 0)
+    let n_neu_reel = matrice[1][0] + matrice[1][1] + matrice[1][2]; // Réel: Neutre (index 1)
+    
+    let n_pos_estime = matrice[0][2] + matrice[1][2] + matrice[2][2]; // Estimé: Positif
+    let n_neg_estime = matrice[0][0] + matrice[1][0] + matrice[2][0]; // Estimé: Négatif
+    let n_neu_estime = matrice[0][1] + matrice[1][1] + matrice[2][1]; // Estimé: Neutre
+    
+    let total = n_pos_reel + n_neg_reel + n_neu_reel;
+
+    format!(
+        "[table=5]\n\
+        [cell]Réel/Estimé[/cell][cell]Positive[/cell][cell]Négatif[/cell][cell]Neutre[/cell][cell]Total réel[/cell]\n\
+        [cell]Positive[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
+        [cell]Négatif[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
+        [cell]Neutre[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
+        [cell]Total estimé[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
+        [/table]",
+        // Ligne Positive réelle
+        matrice[2][2], // TP_pos
+        matrice[2][0], // FN_pos→neg
+        matrice[2][1], // FN_pos→neu
+        n_pos_reel,
+        
+        // Ligne Négative réelle
+        matrice[0][2], // FP_neg→pos
+        matrice[0][0], // TP_neg
+        matrice[0][1], // FN_neg→neu
+        n_neg_reel,
+        
+        // Ligne Neutre réelle
+        matrice[1][2], // FP_neu→pos
+        matrice[1][0], // FP_neu→neg
+        matrice[1][1], // TP_neu
+        n_neu_reel,
+        
+        // Totaux estimés
+        n_pos_estime,
+        n_neg_estime,
+        n_neu_estime,
+        total
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 30 négatifs, 60 neutres et 90 positifs, numérotés
+    fn donnees() -> Vec<(usize, i32)> {
+        [(0, 30), (2, 60), (4, 90)]
+            .iter()
+            .flat_map(|&(classe, nb)| std::iter::repeat_n(classe, nb))
+            .enumerate()
+            .collect()
+    }
+
+    fn comptes(donnees: &[(usize, i32)]) -> [usize; 3] {
+        let mut comptes = [0; 3];
+        for &(_, classe) in donnees {
+            comptes[index_classe(classe).unwrap()] += 1;
+        }
+        comptes
+    }
+
+    #[test]
+    fn division_garde_les_proportions() {
+        let (entrainement, test) = diviser_donnees_stratifiee(&donnees(), |d| d.1, 2.0 / 3.0, 7);
+        assert_eq!(comptes(&entrainement), [20, 40, 60]);
+        assert_eq!(comptes(&test), [10, 20, 30]);
+
+        let mut ids: Vec<usize> = entrainement.iter().chain(&test).map(|d| d.0).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..180).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn division_reproductible() {
+        let donnees = donnees();
+        let division = |graine| diviser_donnees_stratifiee(&donnees, |d| d.1, 2.0 / 3.0, graine);
+        assert_eq!(division(3), division(3));
+        assert_ne!(division(3).1, division(4).1);
+    }
+
+    #[test]
+    fn plis_disjoints_et_complets() {
+        let etiquettes: Vec<i32> = donnees().iter().map(|d| d.1).collect();
+        let plis = plis_stratifies(&etiquettes, 5, &mut generateur(11));
+        assert_eq!(plis.len(), 5);
+
+        let mut vus = vec![false; etiquettes.len()];
+        for pli in &plis {
+            assert_eq!(pli.len(), 36);
+            for &i in pli {
+                assert!(!vus[i], "{} dans deux plis", i);
+                vus[i] = true;
+            }
+        }
+        assert!(vus.iter().all(|&v| v));
+
+        let (entrainement, test) = separer_pli(&plis, 2);
+        assert_eq!(test, plis[2]);
+        assert_eq!(entrainement.len(), 144);
+        assert!(entrainement.iter().all(|i| !test.contains(i)));
+        assert_eq!(plis_stratifies(&etiquettes, 5, &mut generateur(11)), plis);
+    }
+
+    #[test]
+    fn chargement() {
+        let chemin = std::env::temp_dir().join(format!("{}_validation.csv", std::process::id()));
+        std::fs::write(&chemin, "label,texte\n4,bien, vraiment\n,à annoter\n-1,aussi\n3,hors classes\nx,sans étiquette\n0,\"mal\"\n").unwrap();
+        let chemin_str = chemin.to_str().unwrap();
+        let etiquettes = |donnees: Vec<TweetEtiquete>| donnees.into_iter().map(|t| (t.etiquette, t.contenu)).collect::<Vec<_>>();
+
+        let completes = etiquettes(charger_donnees(chemin_str).unwrap());
+        let partielles = etiquettes(charger_donnees_partielles(chemin_str).unwrap());
+        std::fs::remove_file(&chemin).unwrap();
+
+        assert_eq!(completes, vec![
+            (4, "bien, vraiment".to_string()),
+            (-1, "aussi".to_string()),
+            (3, "hors classes".to_string()),
+            (0, "mal".to_string()),
+        ]);
+        assert_eq!(partielles, vec![
+            (4, "bien, vraiment".to_string()),
+            (NON_ANNOTE, "à annoter".to_string()),
+            (NON_ANNOTE, "aussi".to_string()),
+            (0, "mal".to_string()),
+        ]);
+    }
+}
@@ -10,8 +10,19 @@ var ngram_mode = 2
 var database_path = ""
 var positive_path = ""
 var negative_path = ""
+# Garde le modèle de clustering entre deux classifications
+var clustering_node = null
+# Les évaluations tournent en arrière-plan, l'interface reste utilisable
+var jobs = null
+var job_id = -1
+var job_is_clustering = false
 
 func _ready():
+	jobs = Jobs.new()
+	add_child(jobs)
+	jobs.progress.connect(_on_job_progress)
+	jobs.finished.connect(_on_job_finished)
+	jobs.log_sent.connect(_on_job_log_sent)
 	_on_algo_button_item_selected(0)
 	pass
 
@@ -103,6 +114,9 @@ func _on_algo_button_item_selected(index: int) -> void:
 			$ClusterOptionsBar.hide()
 			$BayesOptionsBar.show()
 			mode = $BayesOptionsBar/ModesButton.selected
+	# Une seule évaluation à la fois
+	if job_id != -1:
+		$LaunchButtonsContainer/EvaluateButton.disabled = true
 	pass # Replace with function body.
 
 
@@ -116,7 +130,9 @@ func _on_classify_button_button_up() -> void:
 		var classe = knn_node.knn_execute(database_path,tweet,k,mode)
 		$ResultLabel.text = classe
 	elif $ClusterOptionsBar.visible == true:
-		var clustering_node = Clustering.new()
+		if clustering_node == null:
+			clustering_node = Clustering.new()
+			add_child(clustering_node)
 		var classe = clustering_node.clustering_execute(database_path,tweet,k,mode)
 		$ResultLabel.text = classe
 	elif $BayesOptionsBar.visible == true:
@@ -127,51 +143,87 @@ func _on_classify_button_button_up() -> void:
 
 
 func _on_evaluate_button_button_up() -> void:
+	job_is_clustering = false
 	if $NaiveOptionsBar.visible == true:
-		var naive_node = Naive.new()
-		var tableau_string = naive_node.naive_evaluate(database_path,positive_path,negative_path,k)
-		var eval_window = create_evaluation_window()
-		eval_window.get_child(0).text = tableau_string
-		eval_window.visible = true
+		job_id = jobs.start_naive_evaluate(database_path,positive_path,negative_path,k,{},-1)
 	elif $KNNOptionsBar.visible == true:
-		var knn_node = Knn.new()
-		var tableau_string = knn_node.knn_evaluate(database_path,k,mode)
-		var eval_window = create_evaluation_window()
-		eval_window.get_child(0).text = tableau_string
-		eval_window.visible = true
+		job_id = jobs.start_knn_evaluate(database_path,k,mode,-1)
 	elif $ClusterOptionsBar.visible == true:
-		var clustering_node = Clustering.new()
-		var result = clustering_node.clustering_evaluate(database_path,k,mode)
-		print("test")
-		var eval_window = create_evaluation_window()
-		eval_window.get_child(0).text = result["matrix"]
-		eval_window.visible = true
-		var image = Image.new()
-		var err = image.load_svg_from_string(result["svg"])
-		if err == OK:
-			var texture = ImageTexture.create_from_image(image)
-			var new_window = Window.new()
-			new_window.title = "Dendrogram"
-			new_window.initial_position = Window.WINDOW_INITIAL_POSITION_CENTER_PRIMARY_SCREEN
-			new_window.size = Vector2i(1280,800)
-			new_window.visible = false
-			new_window.close_requested.connect(_on_close_requested.bind(new_window))
-			var new_texture_rect = TextureRect.new()
-			new_texture_rect.expand_mode = TextureRect.EXPAND_FIT_WIDTH
-			new_texture_rect.set_anchors_and_offsets_preset(Control.PRESET_FULL_RECT)
-			new_window.add_child(new_texture_rect)
-			add_child(new_window)
-			new_texture_rect.texture = texture
-			new_window.visible = true
-		else:
-			print("Erreur lors du chargement du SVG")
+		job_is_clustering = true
+		job_id = jobs.start_clustering_evaluate(database_path,k,mode,-1)
 	elif $BayesOptionsBar.visible == true:
-		var bayes_node = Bayes.new()
-		var tableau_string = bayes_node.bayes_evaluate(database_path, mode,representation,ngram_mode)
+		job_id = jobs.start_bayes_evaluate(database_path,mode,representation,ngram_mode,-1)
+	$LaunchButtonsContainer/EvaluateButton.disabled = true
+	$LaunchButtonsContainer/CancelButton.show()
+	$ResultLabel.text = "Évaluation en cours..."
+	pass # Replace with function body.
+
+
+func _on_cancel_button_button_up() -> void:
+	if job_id != -1:
+		jobs.cancel(job_id)
+	pass
+
+
+func _on_job_progress(id, done, total):
+	if id == job_id and total > 0:
+		$ResultLabel.text = "Évaluation en cours... %d %%" % (100 * done / total)
+	pass
+
+
+func _on_job_log_sent(message):
+	print(message)
+	pass
+
+
+func _on_job_finished(id, result):
+	if id != job_id:
+		return
+	job_id = -1
+	$LaunchButtonsContainer/EvaluateButton.disabled = false
+	$LaunchButtonsContainer/CancelButton.hide()
+	if result == null:
+		$ResultLabel.text = "Évaluation annulée"
+		return
+	$ResultLabel.text = ""
+	if job_is_clustering:
+		show_clustering_evaluation(result)
+	else:
 		var eval_window = create_evaluation_window()
-		eval_window.get_child(0).text = tableau_string
+		eval_window.get_child(0).text = result
 		eval_window.visible = true
-	pass # Replace with function body.
+	pass
+
+
+func show_clustering_evaluation(result):
+	var eval_window = create_evaluation_window()
+	if result["status"] != "OK":
+		eval_window.get_child(0).text = result["message"]
+		eval_window.visible = true
+		return
+	eval_window.get_child(0).text = result["matrix"]
+	eval_window.get_child(0).text += "\n\nSilhouette : %.3f\nDavies-Bouldin : %.3f\nPureté : %.3f\nNMI : %.3f\nARI : %.3f" % [result["silhouette"], result["davies_bouldin"], result["purity"], result["nmi"], result["ari"]]
+	eval_window.visible = true
+	var image = Image.new()
+	var err = image.load_svg_from_string(result["svg"])
+	if err == OK:
+		var texture = ImageTexture.create_from_image(image)
+		var new_window = Window.new()
+		new_window.title = "Dendrogram"
+		new_window.initial_position = Window.WINDOW_INITIAL_POSITION_CENTER_PRIMARY_SCREEN
+		new_window.size = Vector2i(1280,800)
+		new_window.visible = false
+		new_window.close_requested.connect(_on_close_requested.bind(new_window))
+		var new_texture_rect = TextureRect.new()
+		new_texture_rect.expand_mode = TextureRect.EXPAND_FIT_WIDTH
+		new_texture_rect.set_anchors_and_offsets_preset(Control.PRESET_FULL_RECT)
+		new_window.add_child(new_texture_rect)
+		add_child(new_window)
+		new_texture_rect.texture = texture
+		new_window.visible = true
+	else:
+		print("Erreur lors du chargement du SVG")
+	pass
 
 
 func _on_positive_button_button_up() -> void:
@@ -1,12 +1,23 @@
 extends Control
 
 var filedialog
+# Les évaluations tournent en arrière-plan, l'interface reste utilisable
+var jobs = null
+var job_id = -1
 var database_path = ""
 var tweet = ""
 var mode = 0
 var representation = 1
 var ngram_mode = 2
 
+func _ready():
+	jobs = Jobs.new()
+	add_child(jobs)
+	jobs.progress.connect(_on_job_progress)
+	jobs.finished.connect(_on_job_finished)
+	jobs.log_sent.connect(_on_job_log_sent)
+	pass
+
 func _on_import_database_button_up() -> void:
 	filedialog = FileDialog.new()
 	filedialog.file_mode = FileDialog.FILE_MODE_OPEN_FILE
@@ -46,7 +57,7 @@ func check_to_enable_or_disable_launch_button():
 	else:
 		$LaunchButton.disabled = false
 		
-	if database_path.is_empty():
+	if database_path.is_empty() or job_id != -1:
 		$EvaluateButton.disabled = true
 	else:
 		$EvaluateButton.disabled = false
@@ -59,12 +70,40 @@ func _on_launch_button_button_up() -> void:
 	pass # Replace with function body.
 
 func _on_evaluate_button_button_up() -> void:
-	var bayes_node = Bayes.new()
-	var tableau_string = bayes_node.bayes_evaluate(database_path, mode,representation,ngram_mode)
-	$EvaluationWindow/EvaluationTableLabel.text = tableau_string
-	$EvaluationWindow.visible = true
+	job_id = jobs.start_bayes_evaluate(database_path,mode,representation,ngram_mode,-1)
+	$EvaluateButton.disabled = true
+	$CancelButton.show()
+	$ResultLabel.text = "Évaluation en cours..."
 	pass # Replace with function body.
 
+func _on_cancel_button_button_up() -> void:
+	if job_id != -1:
+		jobs.cancel(job_id)
+	pass
+
+func _on_job_progress(id, done, total):
+	if id == job_id and total > 0:
+		$ResultLabel.text = "Évaluation en cours... %d %%" % (100 * done / total)
+	pass
+
+func _on_job_log_sent(message):
+	print(message)
+	pass
+
+func _on_job_finished(id, result):
+	if id != job_id:
+		return
+	job_id = -1
+	$CancelButton.hide()
+	check_to_enable_or_disable_launch_button()
+	if result == null:
+		$ResultLabel.text = "Évaluation annulée"
+		return
+	$ResultLabel.text = ""
+	$EvaluationWindow/EvaluationTableLabel.text = result
+	$EvaluationWindow.visible = true
+	pass
+
 func _on_evaluation_window_close_requested() -> void:
 	$EvaluationWindow.visible = false
 	pass # Replace with function body.
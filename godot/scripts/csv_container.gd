@@ -2,8 +2,16 @@ extends Control
 
 const main_theme = preload("res://assets/main_theme.tres")
 
+# Bayes, entropie (voir ActiveLearning.active_load)
+const ACTIVE_MODEL = 0
+const ACTIVE_STRATEGY = 0
+const ACTIVE_K = 10
+const BATCH_SIZE = 20
+
 var csv_path = ""
 var data = []
+var grid_container
+var active_learning = ActiveLearning.new()
 
 signal csv_success
 signal csv_error
@@ -11,20 +19,29 @@ signal csv_error
 func _ready():
 	#Récupérer les données du CSV clean
 	load_csv(csv_path)
+	#File d'annotation : les lignes sans étiquette sont proposées par lots, les plus utiles d'abord
+	add_child(active_learning)
+	active_learning.active_load(csv_path, ACTIVE_MODEL, ACTIVE_STRATEGY, ACTIVE_K)
 	#Ajout du Scroll Container
 	var scroll_container = ScrollContainer.new()
 	scroll_container.set_anchors_and_offsets_preset(Control.PRESET_FULL_RECT)
 	$table.add_child(scroll_container)
 	#Ajout du Grid Container
-	var grid_container = GridContainer.new()
+	grid_container = GridContainer.new()
 	grid_container.theme = main_theme
 	grid_container.columns = data[0].size()
 	print(grid_container.columns)
 	scroll_container.add_child(grid_container)
 	grid_container.size_flags_horizontal = Control.SIZE_EXPAND_FILL
 	grid_container.size_flags_vertical = Control.SIZE_EXPAND_FILL
-	#Ajout des cellules
-	for row_index in range(data.size()):
+	fill_grid(range(data.size()))
+	pass
+
+#Ajout des cellules, lignes dans l'ordre donné
+func fill_grid(row_order) -> void:
+	for child in grid_container.get_children():
+		child.queue_free()
+	for row_index in row_order:
 		for col_index in range(data[row_index].size()):
 			var line_edit = LineEdit.new()
 			line_edit.text = data[row_index][col_index]
@@ -36,7 +53,6 @@ func _ready():
 			line_edit.set_meta("column", col_index)
 			line_edit.text_changed.connect(_on_line_edit_text_changed.bind(line_edit))
 			grid_container.add_child(line_edit)
-	pass
 	
 func load_csv(file_path: String) -> void:
 	var file = FileAccess.open(file_path, FileAccess.READ)
@@ -44,14 +60,34 @@ func load_csv(file_path: String) -> void:
 	if file:
 		while not file.eof_reached():
 			var line = file.get_csv_line()
-			if line.size() > 0 and line[0] != "":
+			# Une ligne sans étiquette (première colonne vide ou -1) reste à annoter, seules les lignes vides sont ignorées
+			if line.size() > 1 or (line.size() > 0 and line[0] != ""):
 				data.append(line)
 		file.close()
 	
 func _on_line_edit_text_changed(new_text: String, line_edit : LineEdit):
 	data[line_edit.get_meta("row")][line_edit.get_meta("column")] = new_text
+	if line_edit.get_meta("column") == 0:
+		var label = int(new_text) if new_text.is_valid_int() else -1
+		active_learning.active_set_label(line_edit.get_meta("row"), label)
 	pass
 
+#Affiche en tête le prochain lot de tweets à annoter, le modèle tenant compte des annotations déjà saisies
+func _on_next_batch_button_button_up() -> void:
+	var batch = active_learning.active_next_batch(BATCH_SIZE)
+	if batch["status"] != "OK":
+		return
+	var row_order = []
+	var in_batch = {}
+	for row in batch["rows"]:
+		row_order.append(row)
+		in_batch[row] = true
+	for row_index in range(data.size()):
+		if not in_batch.has(row_index):
+			row_order.append(row_index)
+	fill_grid(row_order)
+	$buttons/NextBatchButton.text = "Prochain lot (" + str(batch["n_unlabeled"]) + " à annoter)"
+
 func _on_save_button_button_up() -> void:
 	if csv_path == "":
 		csv_error.emit()
@@ -86,11 +86,17 @@ func _on_bayes_button_button_up():
 
 func _on_clean_csv_file_selected(path):
 	add_logs_container()
-	clean_data = CleanData.new()
+	clean_data = Jobs.new()
+	add_child(clean_data)
 	clean_data.log_sent.connect(logs_container._on_log_received)
-	var new_path = clean_data.clean_data(path)
-	print(new_path)
+	clean_data.finished.connect(_on_clean_data_finished)
+	clean_data.start_clean_data(path)
 	pass # Replace with function body.
+
+func _on_clean_data_finished(_job_id, new_path):
+	print(new_path)
+	clean_data.queue_free()
+	pass
 	
 func _on_positive_words_file_selected(path):
 	positive_path = path
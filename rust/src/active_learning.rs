@@ -0,0 +1,95 @@
+use godot::prelude::*;
+use twitter_classification_core::active_learning::FileAnnotation;
+use twitter_classification_core::validation;
+
+/// File d'annotation par apprentissage actif sur un CSV `label,texte` dont la première colonne est vide ou à -1
+/// pour les tweets restant à annoter. Chaque lot est choisi avec un modèle réentraîné sur les étiquettes
+/// connues à ce moment, si bien que les annotations reçues par active_set_label servent dès le lot suivant.
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct ActiveLearning {
+    base: Base<Node>,
+    file: FileAnnotation,
+}
+
+#[godot_api]
+impl INode for ActiveLearning {
+    fn init(base: Base<Node>) -> Self {
+        Self { base, file: FileAnnotation::default() }
+    }
+}
+
+#[godot_api]
+impl ActiveLearning {
+    /// Charge le fichier à annoter.
+    /// model : 0 = Bayes, 1 = KNN (`k` voisins). strategy : 0 = entropie, 1 = marge, 2 = diversité.
+    #[func]
+    fn active_load(&mut self, path: GString, model: i64, strategy: i64, k: i64) -> Dictionary {
+        let mut result_dict = Dictionary::new();
+
+        match self.file.charger(&path.to_string(), model, strategy, k) {
+            Ok(()) => {
+                result_dict.set("status", "OK");
+                self.ajouter_comptes(&mut result_dict);
+            },
+            Err(e) => {
+                godot_print!("Active Learning Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
+            }
+        }
+        result_dict
+    }
+
+    /// Les `size` prochains tweets à annoter, du plus au moins utile : numéro de ligne dans le fichier,
+    /// texte, score de la stratégie, étiquette prédite et sa probabilité (pour pré-remplir l'annotation).
+    #[func]
+    fn active_next_batch(&mut self, size: i64) -> Dictionary {
+        let mut result_dict = Dictionary::new();
+        let lot = self.file.prochain_lot(size.max(0) as usize);
+
+        result_dict.set("status", "OK");
+        result_dict.set("rows", lot.lignes.iter().map(|&l| l as i64).collect::<PackedInt64Array>());
+        result_dict.set("texts", lot.textes.iter().map(|t| GString::from(t.as_str())).collect::<PackedStringArray>());
+        result_dict.set("scores", lot.scores.iter().copied().collect::<PackedFloat64Array>());
+        result_dict.set("predicted", lot.predites.iter().map(|&p| p as i64).collect::<PackedInt64Array>());
+        result_dict.set("confidence", lot.confiances.iter().copied().collect::<PackedFloat64Array>());
+        self.ajouter_comptes(&mut result_dict);
+        result_dict
+    }
+
+    /// Annote la ligne `row` (numéro de ligne du fichier) ; une étiquette hors de 0, 2, 4 la remet à annoter
+    #[func]
+    fn active_set_label(&mut self, row: i64, label: i64) -> bool {
+        match usize::try_from(row) {
+            Ok(row) => {
+                let label = i32::try_from(label)
+                    .ok()
+                    .filter(|&label| validation::index_classe(label).is_some())
+                    .unwrap_or(validation::NON_ANNOTE);
+                self.file.etiqueter(row, label)
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Écrit le fichier avec les annotations reçues
+    #[func]
+    fn active_save(&mut self, path: GString) -> bool {
+        match self.file.enregistrer(&path.to_string()) {
+            Ok(()) => true,
+            Err(e) => {
+                godot_print!("Active Learning Error: {}", e);
+                false
+            }
+        }
+    }
+}
+
+impl ActiveLearning {
+    fn ajouter_comptes(&self, result_dict: &mut Dictionary) {
+        let (annotes, candidats) = self.file.comptes();
+        result_dict.set("n_labeled", annotes as i64);
+        result_dict.set("n_unlabeled", candidats as i64);
+    }
+}
@@ -0,0 +1,86 @@
+use godot::prelude::*;
+use twitter_classification_core::agreement::{aligner, conflits, ecrire_conflits, kappa};
+use twitter_classification_core::validation;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct Agreement {
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for Agreement {
+    fn init(base: Base<Node>) -> Self {
+        Self { base }
+    }
+}
+
+#[godot_api]
+impl Agreement {
+    /// Accord entre annotateurs sur deux fichiers `label,texte` ou plus, alignés sur le texte des tweets
+    /// (espaces superflus ignorés). Une étiquette hors de 0, 2, 4 (-1 par exemple) compte comme non annotée.
+    /// Renvoie le kappa de Cohen de chaque paire de fichiers, le kappa de Fleiss et l'accord de chaque classe.
+    /// Si `conflicts_path` n'est pas vide, y écrit les tweets en désaccord : étiquette majoritaire (-1 en cas
+    /// d'égalité, à trancher), texte, puis l'étiquette de chaque annotateur.
+    #[func]
+    fn agreement_compute(&mut self, paths: PackedStringArray, conflicts_path: GString) -> Dictionary {
+        let mut result_dict = Dictionary::new();
+
+        let chemins: Vec<String> = paths.as_slice().iter().map(|p| p.to_string()).collect();
+        let resultat = aligner(&chemins).and_then(|alignement| {
+            let conflits = conflits(&alignement);
+            if !conflicts_path.is_empty() {
+                ecrire_conflits(&conflicts_path.to_string(), &alignement, &conflits)?;
+            }
+            Ok((alignement, conflits.len()))
+        });
+
+        match resultat {
+            Ok((alignement, nb_conflits)) => {
+                let nb_annotateurs = alignement.annotateurs.len();
+                let communs: Vec<Vec<Option<usize>>> = alignement
+                    .annotations
+                    .into_iter()
+                    .filter(|ligne| ligne.iter().flatten().count() >= 2)
+                    .collect();
+                let global = kappa::fleiss(&communs);
+
+                let mut pairs = Array::<Dictionary>::new();
+                for paire in kappa::cohen(&communs, nb_annotateurs) {
+                    let mut d = Dictionary::new();
+                    d.set("first", alignement.annotateurs[paire.premier].as_str());
+                    d.set("second", alignement.annotateurs[paire.second].as_str());
+                    d.set("n", paire.n as i64);
+                    d.set("observed", paire.observe);
+                    d.set("kappa", paire.kappa);
+                    pairs.push(&d);
+                }
+
+                let mut classes = Array::<Dictionary>::new();
+                for (classe, accord) in validation::CLASSES.iter().zip(global.par_classe) {
+                    if let Some(accord) = accord {
+                        let mut d = Dictionary::new();
+                        d.set("label", *classe as i64);
+                        d.set("agreement", accord);
+                        classes.push(&d);
+                    }
+                }
+
+                result_dict.set("status", "OK");
+                result_dict.set("annotators", alignement.annotateurs.iter().map(|a| GString::from(a.as_str())).collect::<PackedStringArray>());
+                result_dict.set("n_items", communs.len() as i64);
+                result_dict.set("n_conflicts", nb_conflits as i64);
+                result_dict.set("observed_agreement", global.observe);
+                result_dict.set("fleiss_kappa", global.fleiss);
+                result_dict.set("pairs", pairs);
+                result_dict.set("classes", classes);
+            },
+            Err(e) => {
+                godot_print!("Agreement Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
+            }
+        }
+        result_dict
+    }
+}
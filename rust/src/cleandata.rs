@@ -1,9 +1,6 @@
 use godot::prelude::*;
-
-mod generic;
-mod entry;
-mod error;
-mod rule_filter;
+use twitter_classification_core::cleandata::{entry, rules_file};
+use twitter_classification_core::progress::Progress;
 
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -22,18 +19,51 @@ impl INode for CleanData {
 impl CleanData {
     #[func]
     fn clean_data(&mut self, path: GString) -> GString {
-        return match self.clean_data_body(&path.to_string()) {
+        self.clean(&path.to_string(), None)
+    }
+
+    // Same as clean_data, with the rules of a TOML/JSON rules file instead of the automatic filters
+    #[func]
+    fn clean_data_with_rules(&mut self, path: GString, rules_path: GString) -> GString {
+        self.clean(&path.to_string(), Some(&rules_path.to_string()))
+    }
+
+    // Loads a rules file without cleaning anything, to report mistakes before a long cleaning
+    #[func]
+    fn check_rules(&mut self, rules_path: GString) -> Dictionary {
+        let mut result_dict = Dictionary::new();
+        match rules_file::load(&rules_path.to_string()) {
+            Ok(rules) => {
+                result_dict.set("status", "OK");
+                result_dict.set("message", format!("{} rules", rules.rules.len()));
+            },
+            Err(e) => {
+                godot_print!("CleanData Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("{e}"));
+            }
+        }
+        result_dict
+    }
+
+    #[signal]
+    fn log_sent(message : GString);
+}
+
+impl CleanData {
+    fn clean(&mut self, path: &str, rules_path: Option<&str>) -> GString {
+        let mut log = |message: String| self.signals().log_sent().emit(&GString::from(message));
+        let result = entry::clean_data_body(path, rules_path, &mut log, &Progress::default());
+
+        match result {
             Ok(temp_path) => GString::from(temp_path),
             Err(e) => {
                 self.signals()
                     .log_sent()
                     .emit(&GString::from(format!("{e}")));
 
-                return GString::from("");
+                GString::from("")
             },
-        };
+        }
     }
-
-    #[signal]
-    fn log_sent(message : GString);
 }
@@ -1,56 +1,62 @@
-use std::collections::{HashMap, HashSet};
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use std::fmt::Write;
-
-use rand::seq::SliceRandom;
-use rand::rng;
-
-use kodama::{Method, linkage};
 use godot::prelude::*;
-
-#[derive(Debug, Clone)]
-struct Tweet {
-    id: usize,
-    contenu: String,
-    mots: HashSet<String>,
-    label: i32, // 4=positif, 2=neutre, 0=négatif
-}
+use twitter_classification_core::clustering::{
+    charger_tweets_annotes, condensed, propagation, selection, summary, CacheClustering, ResultatEvaluation,
+};
 
 #[derive(GodotClass)]
 #[class(base = Node)]
 struct Clustering {
     base: Base<Node>,
+    cache: CacheClustering,
 }
 
 #[godot_api]
 impl INode for Clustering {
     fn init(base: Base<Node>) -> Self {
-        Self { base }
+        Self { base, cache: CacheClustering::default() }
     }
 }
 
 #[godot_api]
 impl Clustering {
+    /// Choix automatique du nombre de clusters K (et de la méthode de linkage) par validation croisée stratifiée.
+    /// Renvoie la courbe métrique / K pour l'affichage et le meilleur réglage trouvé.
     #[func]
-    fn clustering_evaluate(&mut self, path: GString, k: i64, method: i64) -> Variant {
-        let path_str = path.to_string();
+    fn clustering_select_k(&mut self, path: GString, k_min: i64, k_max: i64, n_plis: i64, graine: i64) -> Dictionary {
         let mut result_dict = Dictionary::new();
 
-        match run_evaluation_pipeline(&path_str, k as usize, method as usize) {
-            Ok((svg, matrix_str)) => {
-                result_dict.insert("status", "OK");
-                result_dict.insert("svg", svg);
-                result_dict.insert("matrix", matrix_str);
+        let courbe = charger_tweets_annotes(&path.to_string())
+            .map_err(|e| e.to_string())
+            .and_then(|tweets| selection::balayer_k(&tweets, k_min.max(1) as usize, k_max.max(0) as usize, n_plis.max(2) as usize, graine));
+
+        match courbe {
+            Ok(courbe) => {
+                let mut points = Array::<Dictionary>::new();
+                for point in &courbe {
+                    let mut d = Dictionary::new();
+                    d.set("k", point.k as i64);
+                    d.set("method", point.methode as i64);
+                    d.set("accuracy", point.exactitude);
+                    d.set("f1_macro", point.f1_macro);
+                    points.push(&d);
+                }
+
+                result_dict.set("status", "OK");
+                result_dict.set("curve", points);
+                if let Some(meilleur) = selection::meilleur_point(&courbe) {
+                    result_dict.set("best_k", meilleur.k as i64);
+                    result_dict.set("best_method", meilleur.methode as i64);
+                    result_dict.set("best_accuracy", meilleur.exactitude);
+                    result_dict.set("best_f1_macro", meilleur.f1_macro);
+                }
             },
             Err(e) => {
                 godot_print!("Clustering Error: {}", e);
-                result_dict.insert("status", "ERROR");
-                result_dict.insert("message", format!("Erreur: {}", e));
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
             }
         }
-        result_dict.to_variant()
+        result_dict
     }
 
     #[func]
@@ -58,7 +64,7 @@ impl Clustering {
         let tweet_str = tweet_content.to_string();
         let path_str = path.to_string();
         
-        match predict_tweet_class(&path_str, &tweet_str, k as usize, method as usize) {
+        match self.cache.predict_tweet_class(&path_str, &tweet_str, k as usize, method as usize) {
             Ok(predicted_label) => {
                 let text = match predicted_label {
                     4 => "POSITIF",
@@ -74,393 +80,218 @@ impl Clustering {
             }
         }
     }
-}
-
-fn run_evaluation_pipeline(csv_path: &str, k: usize, method: usize) -> Result<(String, String), Box<dyn Error>> {
-    // Chargement des données
-    let tweets = charger_tweets_annotes(csv_path)?;
-    let n = tweets.len();
-    if n == 0 { return Err("Aucun tweet trouvé.".into()); }
-    if n < k { return Err(format!("Pas assez de tweets ({}) pour K={}", n, k).into()); }
 
-    // Division du dataset : 2/3 entraînement, 1/3 test
-    let (mut train, test) = diviser_donnees_stratifiee(&tweets, 2.0 / 3.0);
-
-    let n_train = train.len();
-    if n_train < k { return Err(format!("Pas assez de tweets d'entrainement ({}) pour K={}", n_train, k).into()); }
+    /// Groupes formés par la coupe du dendrogramme, pour inspecter les clusters.
+    /// criterion : 0 = nombre de clusters (value = K), 1 = seuil de dissimilarité, 2 = coefficient d'incohérence
+    /// (calculé sur `depth` niveaux). Les méthodes de partitionnement n'acceptent que le critère 0.
+    /// Renvoie le cluster de chaque tweet (dans l'ordre du fichier), puis la taille, l'étiquette majoritaire
+    /// et la pureté de chaque cluster, numérotés par taille décroissante.
+    #[func]
+    fn clustering_clusters(&mut self, path: GString, method: i64, criterion: i64, value: f64, depth: i64) -> Dictionary {
+        let mut result_dict = Dictionary::new();
 
-    // Ré-indexer les ID du train pour correspondre à leur position dans le vecteur (pour Kodama)
-    for (idx, t) in train.iter_mut().enumerate() {
-        t.id = idx;
-    }
-    
-    // Calcul de la matrice de distance en comparant chaque tweet avec les autres
-    let mut condensed = Vec::with_capacity(n_train * (n_train - 1) / 2);
-    for i in 0..n_train - 1 {
-        for j in i + 1..n_train {
-            condensed.push(distance(&train[i], &train[j]));
+        let resultat = self.cache.decouper(&path.to_string(), method, criterion, value, depth);
+
+        match resultat {
+            Ok((assignations, resumes)) => {
+                let assignments: PackedInt64Array = assignations.iter().map(|&c| c as i64).collect();
+                let total: usize = resumes.iter().map(|r| r.taille).sum();
+                let bien_ranges: f64 = resumes.iter().map(|r| r.purete * r.taille as f64).sum();
+
+                let mut clusters = Array::<Dictionary>::new();
+                for resume in &resumes {
+                    let mut d = Dictionary::new();
+                    d.set("id", resume.id as i64);
+                    d.set("size", resume.taille as i64);
+                    d.set("label", resume.label as i64);
+                    d.set("purity", resume.purete);
+                    d.set("negative", resume.comptes[0] as i64);
+                    d.set("neutral", resume.comptes[1] as i64);
+                    d.set("positive", resume.comptes[2] as i64);
+                    clusters.push(&d);
+                }
+
+                result_dict.set("status", "OK");
+                result_dict.set("assignments", assignments);
+                result_dict.set("n_clusters", resumes.len() as i64);
+                result_dict.set("clusters", clusters);
+                result_dict.set("purity", if total == 0 { 0.0 } else { bien_ranges / total as f64 });
+            },
+            Err(e) => {
+                godot_print!("Clustering Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
+            }
         }
+        result_dict
     }
 
-    // Clustering hiérarchique
-    let linkage_method = match method { 0 => Method::Average, 1 => Method::Complete, 2 => Method::Ward, _ => Method::Average };
-    let dendrogram = linkage(&mut condensed, n_train, linkage_method);
-
-    // Génération du SVG
-    let svg = generate_dendrogram_svg(&dendrogram, &train);
-
-    // Découpage de l'arbre pour obtenir K clusters avec UnionFind pour fusionner les groupes jusqu'à avoir K groupes
-    let steps = dendrogram.steps();
-    let max_index = if steps.is_empty() { n_train } else { n_train + steps.len() };
-    let mut uf = UnionFind::new(max_index);
-    
-    let steps_to_process = if n_train > k { n_train - k } else { 0 };
-    for step in steps.iter().take(steps_to_process) {
-        uf.union(step.cluster1, step.cluster2);
-    }
-
-    // Vote majoritaire et Matrice de confusion en regardant quel est le sentiment dominant dans chaque cluster formé.
-    let mut cluster_votes: HashMap<usize, HashMap<i32, usize>> = HashMap::new();
-    for tweet in &train {
-        let root = uf.find(tweet.id);
-        *cluster_votes.entry(root).or_default().entry(tweet.label).or_default() += 1;
-    }
-
-    // On assigne une étiquette finale à chaque cluster
-    let mut cluster_labels: HashMap<usize, i32> = HashMap::new();
-    for (root, votes) in cluster_votes {
-        let best_label = votes.into_iter().max_by_key(|&(_, count)| count).map(|(l, _)| l).unwrap_or(2);
-        cluster_labels.insert(root, best_label);
-    }
-
-    // Construction de la matrice de confusion (Réel vs Estimé)
-    let mut matrice = [[0; 3]; 3];
-    let index_map: HashMap<i32, usize> = [(0, 0), (2, 1), (4, 2)].iter().cloned().collect();
-
-    for t_test in &test {
-        // Trouver le voisin le plus proche dans le train
-        let mut best_dist = f64::MAX;
-        let mut best_neighbor_idx = 0; // ID dans le train
+    /// Synthèse des clusters obtenus avec les mêmes critères de coupe que clustering_clusters :
+    /// pour chaque cluster, ses `n_keywords` mots les plus caractéristiques (log-odds contre le reste du corpus),
+    /// son tweet médoïde et la répartition des étiquettes, plus un SVG qui les dessine. Les clusters vides sont omis.
+    #[func]
+    fn clustering_summary(&mut self, path: GString, method: i64, criterion: i64, value: f64, depth: i64, n_keywords: i64) -> Dictionary {
+        let mut result_dict = Dictionary::new();
 
-        for t_train in &train {
-            let d = distance(t_test, t_train);
-            if d < best_dist {
-                best_dist = d;
-                best_neighbor_idx = t_train.id;
+        let resultat = self.cache.decouper(&path.to_string(), method, criterion, value, depth).and_then(|(groupes, resumes)| {
+            let tweets = self.cache.modele_courant().ok_or("Aucun modèle")?.tweets();
+            let syntheses = summary::synthetiser(tweets, &groupes, resumes.len(), n_keywords.max(0) as usize);
+            let svg = summary::generate_summary_svg(&resumes, &syntheses, tweets);
+            let medoides: Vec<Option<String>> = syntheses.iter().map(|s| s.medoide.map(|m| tweets[m].contenu.clone())).collect();
+            Ok((resumes, syntheses, medoides, svg))
+        });
+
+        match resultat {
+            Ok((resumes, syntheses, medoides, svg)) => {
+                let mut clusters = Array::<Dictionary>::new();
+                for ((resume, synthese), medoide) in resumes.iter().zip(&syntheses).zip(medoides) {
+                    let Some(medoide) = medoide else { continue };
+                    let keywords: PackedStringArray = synthese.mots_cles.iter().map(|(mot, _)| GString::from(mot.as_str())).collect();
+                    let scores: PackedFloat64Array = synthese.mots_cles.iter().map(|&(_, score)| score).collect();
+
+                    let mut d = Dictionary::new();
+                    d.set("id", resume.id as i64);
+                    d.set("size", resume.taille as i64);
+                    d.set("label", resume.label as i64);
+                    d.set("purity", resume.purete);
+                    d.set("negative", resume.comptes[0] as i64);
+                    d.set("neutral", resume.comptes[1] as i64);
+                    d.set("positive", resume.comptes[2] as i64);
+                    d.set("keywords", keywords);
+                    d.set("keyword_scores", scores);
+                    d.set("medoid", medoide);
+                    clusters.push(&d);
+                }
+
+                result_dict.set("status", "OK");
+                result_dict.set("clusters", clusters);
+                result_dict.set("svg", svg);
+            },
+            Err(e) => {
+                godot_print!("Clustering Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
             }
         }
-
-        // Trouver le cluster du voisin
-        let root = uf.find(best_neighbor_idx);
-        // Prédire le label du cluster
-        let label_estime = *cluster_labels.get(&root).unwrap_or(&2);
-
-        // Mettre à jour la matrice
-        if let (Some(&idx_reel), Some(&idx_estime)) = (index_map.get(&t_test.label), index_map.get(&label_estime)) {
-            matrice[idx_reel][idx_estime] += 1;
-        }
+        result_dict
     }
 
-    Ok((svg, format_matrice_confusion(&matrice)))
-}
-
-fn diviser_donnees_stratifiee(donnees: &[Tweet], ratio_train: f64) -> (Vec<Tweet>, Vec<Tweet>) {
-    let mut rng = rng();
-    let mut par_classe: HashMap<i32, Vec<Tweet>> = HashMap::new();
-    
-    // Grouper par classe
-    for t in donnees {
-        par_classe.entry(t.label).or_insert_with(Vec::new).push(t.clone());
-    }
-    
-    let mut entrainement = Vec::new();
-    let mut test = Vec::new();
-    
-    // Splitter chaque classe
-    for (_, mut vec) in par_classe {
-        vec.shuffle(&mut rng); // Mélange aléatoire
-        let split = (vec.len() as f64 * ratio_train) as usize;
-        
-        entrainement.extend_from_slice(&vec[..split]);
-        test.extend_from_slice(&vec[split..]);
-    }
-    
-    // Mélanger le tout pour ne pas avoir les classes groupées
-    entrainement.shuffle(&mut rng);
-    test.shuffle(&mut rng);
-    
-    (entrainement, test)
-}
-
-fn predict_tweet_class(path: &str, input_tweet: &str, k: usize, method: usize) -> Result<i32, Box<dyn Error>> {
-    // On refait le clustering ici pour avoir le contexte des groupes
-    let tweets = charger_tweets_annotes(path)?;
-    let n = tweets.len();
-    if n < k { return Err("K est trop grand".into()); }
-
-    let mut condensed = Vec::new();
-    for i in 0..n - 1 {
-        for j in i + 1..n {
-            condensed.push(distance(&tweets[i], &tweets[j]));
+    /// Enregistre le modèle gardé en mémoire (celui du dernier clustering_execute).
+    /// Renvoie false s'il n'y a pas encore de modèle ou en cas d'erreur d'écriture.
+    #[func]
+    fn clustering_save_model(&mut self, model_path: GString) -> bool {
+        let Some(modele) = self.cache.modele_courant() else {
+            godot_print!("Clustering Error: aucun modèle à enregistrer");
+            return false;
+        };
+        match modele.enregistrer(&model_path.to_string()) {
+            Ok(()) => true,
+            Err(e) => {
+                godot_print!("Clustering Error: {}", e);
+                false
+            }
         }
     }
 
-    let linkage_method = match method { 0 => Method::Average, 1 => Method::Complete, 2 => Method::Ward, _ => Method::Average };
-    let dendrogram = linkage(&mut condensed, n, linkage_method);
-
-    // Reconstruction des clusters
-    let steps = dendrogram.steps();
-    let max_index = if steps.is_empty() { n } else { n + steps.len() };
-    let mut uf = UnionFind::new(max_index);
-
-    let steps_to_run = if n > k { n - k } else { 0 };
-    for step in steps.iter().take(steps_to_run) {
-        uf.union(step.cluster1, step.cluster2);
-    }
-
-    // Calcul des labels des clusters via vote
-    let mut cluster_votes: HashMap<usize, HashMap<i32, usize>> = HashMap::new();
-    for tweet in &tweets {
-        let root = uf.find(tweet.id);
-        *cluster_votes.entry(root).or_default().entry(tweet.label).or_default() += 1;
-    }
-
-    let mut cluster_labels: HashMap<usize, i32> = HashMap::new();
-    for (root, votes) in cluster_votes {
-        let lbl = votes.into_iter().max_by_key(|&(_, c)| c).map(|(l, _)| l).unwrap_or(2);
-        cluster_labels.insert(root, lbl);
-    }
-
-    // Voisin le plus proche en cherchant quel tweet connu ressemble le plus au tweet entré par l'utilisateur.
-    let input_words = tokeniser_tweet(input_tweet);
-    let input_obj = Tweet { id: 0, contenu: input_tweet.to_string(), mots: input_words, label: -1 };
-    
-    let mut best_dist = f64::MAX;
-    let mut best_neighbor_idx = 0;
-
-    for tweet in &tweets {
-        let d = distance(&input_obj, tweet);
-        if d < best_dist {
-            best_dist = d;
-            best_neighbor_idx = tweet.id;
+    /// Charge un modèle enregistré. Il n'est utilisé que si le jeu de données n'a pas changé depuis
+    /// et que clustering_execute est appelé avec la même méthode, sinon il est reconstruit.
+    #[func]
+    fn clustering_load_model(&mut self, model_path: GString) -> bool {
+        match self.cache.charger(&model_path.to_string()) {
+            Ok(()) => true,
+            Err(e) => {
+                godot_print!("Clustering Error: {}", e);
+                false
+            }
         }
     }
 
-    // On retourne le label du cluster auquel appartient ce voisin
-    let neighbor_cluster = uf.find(best_neighbor_idx);
-    Ok(*cluster_labels.get(&neighbor_cluster).unwrap_or(&2))
-}
-
-// Ne fonctionne pas exactement comme prévu dû à Godot qui n'affiche pas le texte. A voir comme un proof of concept
-fn generate_dendrogram_svg(dendrogram: &kodama::Dendrogram<f64>, tweets: &[Tweet]) -> String {
-    let n = tweets.len();
-    let steps = dendrogram.steps();
-
-    let total_nodes = n + steps.len();
-    
-    let mut children_map: Vec<Option<(usize, usize)>> = vec![None; total_nodes + 1];
+    /// Annotation semi-supervisée d'un CSV `label,texte` dont seuls quelques tweets sont annotés (première colonne vide ou -1 sinon).
+    /// Les étiquettes se propagent sur le graphe des `k` plus proches voisins (distance de Jaccard du clustering).
+    /// mode : 0 = propagation (les tweets annotés gardent leur étiquette), 1 = diffusion (leur étiquette pèse 1 - alpha
+    /// et peut être corrigée par leurs voisins). Renvoie l'étiquette et la confiance de chaque tweet, dans l'ordre du fichier.
+    #[func]
+    fn clustering_propagate(&mut self, path: GString, mode: i64, k: i64, alpha: f64, max_iterations: i64) -> Dictionary {
+        let mut result_dict = Dictionary::new();
 
-    for (i, step) in steps.iter().enumerate() {
-        let new_cluster_idx = n + i;
-        if new_cluster_idx < children_map.len() {
-             children_map[new_cluster_idx] = Some((step.cluster1, step.cluster2));
+        let resultat = propagation::Mode::depuis_numero(mode, alpha).map_err(Into::into).and_then(|mode| {
+            propagation::propager_fichier(&path.to_string(), mode, k.max(1) as usize, max_iterations.max(1) as usize)
+        });
+
+        match resultat {
+            Ok((tweets, resultat)) => {
+                let labels: PackedInt64Array = resultat.labels.iter().map(|&l| l as i64).collect();
+                let confidence: PackedFloat64Array = resultat.confiances.iter().copied().collect();
+                let texts: PackedStringArray = tweets.iter().map(|t| GString::from(t.contenu.as_str())).collect();
+                let labeled: PackedByteArray = tweets.iter().map(|t| (t.label != propagation::NON_ANNOTE) as u8).collect();
+
+                result_dict.set("status", "OK");
+                result_dict.set("labels", labels);
+                result_dict.set("confidence", confidence);
+                result_dict.set("texts", texts);
+                result_dict.set("labeled", labeled);
+                result_dict.set("n_labeled", resultat.nb_annotes as i64);
+                result_dict.set("n_unlabeled", (tweets.len() - resultat.nb_annotes) as i64);
+                result_dict.set("iterations", resultat.iterations as i64);
+                result_dict.set("converged", resultat.convergence);
+            },
+            Err(e) => {
+                godot_print!("Clustering Error: {}", e);
+                result_dict.set("status", "ERROR");
+                result_dict.set("message", format!("Erreur: {}", e));
+            }
         }
+        result_dict
     }
 
-    let root_idx = if steps.is_empty() { 
-        0 
-    } else { 
-        n + steps.len() - 1 
-    };
-
-    let mut visual_order = Vec::with_capacity(n);
-    get_leaf_order(root_idx, &children_map, &mut visual_order);
-
-    let width = 1200.0;
-    let height = 600.0;
-    let margin = 20.0;
-    let drawing_height = height - 2.0 * margin;
-    let drawing_width = width - 2.0 * margin;
-    let max_dissim = steps.last().map(|s| s.dissimilarity).unwrap_or(1.0).max(0.0001);
-
-    let mut leaf_x_positions = HashMap::new();
-    let step_x = drawing_width / (n as f64).max(1.0);
-    for (vis_idx, &original_idx) in visual_order.iter().enumerate() {
-        let x = margin + (vis_idx as f64 * step_x) + (step_x / 2.0);
-        leaf_x_positions.insert(original_idx, x);
+    #[func]
+    fn clustering_clear_cache(&mut self) {
+        self.cache.vider();
     }
 
-    let mut svg = String::with_capacity(50 * 1024);
-
-    let _ = write!(&mut svg, r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}"><rect width="100%" height="100%" fill="#202020"/><style>.link {{ stroke: #4a90e2; stroke-width: 2; fill: none; }}</style>"##, width, height, width, height);
-    
-    let mut node_pos: HashMap<usize, (f64, f64)> = HashMap::new();
-    let base_y = margin + drawing_height;
-
-    // Dessin des feuilles (POINTS SEULEMENT)
-    for i in 0..n {
-        if let Some(&x) = leaf_x_positions.get(&i) {
-            node_pos.insert(i, (x, base_y));
-            // Couleur selon le label (Positif=Vert, Négatif=Rouge, Autre=Gris)
-            let color = match tweets[i].label { 4 => "#4caf50", 0 => "#f44336", _ => "#9e9e9e" };
-            
-            let _ = write!(&mut svg, r#"<circle cx="{}" cy="{}" r="4" fill="{}" />"#, x, base_y, color);
-        }
+    /// Taille maximale (en Mo) de la matrice de distance des méthodes hiérarchiques,
+    /// au-delà l'évaluation échoue avant d'allouer la matrice
+    #[func]
+    fn set_memory_budget_mb(mb: i64) {
+        condensed::set_budget_mo(mb.max(0) as usize);
     }
 
-    // Dessin des liens (branches de l'arbre)
-    for (i, step) in steps.iter().enumerate() {
-        let h_ratio = step.dissimilarity / max_dissim;
-        let merge_y = base_y - (h_ratio * drawing_height);
-        let c1 = step.cluster1; 
-        let c2 = step.cluster2;
-        let new_cluster = n + i;
-
-        if let (Some(&(x1, y1)), Some(&(x2, y2))) = (node_pos.get(&c1), node_pos.get(&c2)) {
-            let new_x = (x1 + x2) / 2.0;
-            node_pos.insert(new_cluster, (new_x, merge_y));
-            // Lignes du dendrogramme
-            let _ = write!(&mut svg, r#"<path d="M{} {} V{} H{} V{}" class="link" />"#, x1, y1, merge_y, x2, y2);
-        }
+    #[func]
+    fn get_memory_budget_mb() -> i64 {
+        condensed::budget_mo() as i64
     }
 
-    let _ = write!(&mut svg, "</svg>");
-    svg
-}
-
-// Structure pour gérer les ensembles disjoints pour couper l'arbre
-struct UnionFind {
-    parent: Vec<usize>,
-}
-
-impl UnionFind {
-
-    fn new(size: usize) -> Self {
-        Self { parent: (0..size + 1).collect() }
-    }
-    
-    // Trouve la racine d'un élément
-    fn find(&mut self, i: usize) -> usize {
-        if i >= self.parent.len() {
-            return i; 
-        }
-        if self.parent[i] == i {
-            i
-        } else {
-            let root = self.find(self.parent[i]);
-            self.parent[i] = root;
-            root
-        }
+    /// Distances stockées en f32 plutôt qu'en f64 : deux fois plus de tweets pour le même budget
+    #[func]
+    fn set_single_precision(enabled: bool) {
+        condensed::set_simple_precision(enabled);
     }
 
-    // Fusionne deux ensembles
-    fn union(&mut self, i: usize, j: usize) {
-        if i >= self.parent.len() || j >= self.parent.len() { return; }
-        let root_i = self.find(i);
-        let root_j = self.find(j);
-        if root_i != root_j {
-            self.parent[root_i] = root_j;
-        }
+    #[func]
+    fn is_single_precision() -> bool {
+        condensed::simple_precision()
     }
 }
 
-// Utilisé dans le SVG pour déterminer l'ordre des feuilles pour éviter de croiser les lignes
-fn get_leaf_order(node: usize, children: &[Option<(usize, usize)>], order: &mut Vec<usize>) {
-    if node >= children.len() { return; }
-
-    match children[node] {
-        Some((left, right)) => {
-            get_leaf_order(left, children, order);
-            get_leaf_order(right, children, order);
+// Dictionary renvoyé à Godot par start_clustering_evaluate, à construire sur le thread principal.
+// L'erreur est déjà envoyée par log_sent de Jobs.
+pub(crate) fn resultat_evaluation(resultat: Result<ResultatEvaluation, String>) -> Dictionary {
+    let mut result_dict = Dictionary::new();
+    match resultat {
+        Ok(resultat) => {
+            result_dict.set("status", "OK");
+            result_dict.set("svg", resultat.svg);
+            result_dict.set("matrix", resultat.matrice);
+            result_dict.set("silhouette", resultat.metriques.silhouette);
+            result_dict.set("davies_bouldin", resultat.metriques.davies_bouldin);
+            result_dict.set("purity", resultat.metriques.purete);
+            result_dict.set("nmi", resultat.metriques.nmi);
+            result_dict.set("ari", resultat.metriques.ari);
         },
-        None => {
-            order.push(node);
+        Err(e) => {
+            result_dict.set("status", "ERROR");
+            result_dict.set("message", format!("Erreur: {}", e));
         }
     }
+    result_dict
 }
-
-fn charger_tweets_annotes(chemin: &str) -> Result<Vec<Tweet>, Box<dyn Error>> {
-    
-    let fichier = File::open(chemin)?;
-    let reader = BufReader::new(fichier);
-    let mut donnees = Vec::new();
-    for (index, ligne) in reader.lines().enumerate() {
-        let ligne = ligne?;
-        if index == 0 { continue; }
-        let colonnes: Vec<&str> = ligne.split(',').collect();
-        if colonnes.len() >= 2 {
-            if let Ok(etiquette) = colonnes[0].parse::<i32>() {
-                let contenu_raw = colonnes[1..].join(",");
-                let contenu = contenu_raw.trim_matches('"').to_string();
-                donnees.push(Tweet {
-                    id: index - 1,
-                    mots: tokeniser_tweet(&contenu),
-                    contenu,
-                    label: etiquette,
-                });
-            }
-        }
-    }
-    Ok(donnees)
-}
-
-fn tokeniser_tweet(t: &str) -> HashSet<String> {
-    t.split(|c: char| !c.is_ascii_alphanumeric())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_ascii_lowercase())
-        .collect()
-}
-
-fn distance(t1: &Tweet, t2: &Tweet) -> f64 {
-    let len1 = t1.mots.len() as f64;
-    let len2 = t2.mots.len() as f64;
-    let common = t1.mots.intersection(&t2.mots).count() as f64;
-    let union = len1 + len2 - common;
-    if union == 0.0 { 1.0 } else { (union - common) / union }
-}
-
-// Formate la matrice de confusion selon le format demandé
-fn format_matrice_confusion(matrice: &[[i32; 3]]) -> String {
-    let n_pos_reel = matrice[2][0] + matrice[2][1] + matrice[2][2]; // Réel: Positif (index 2)
-    let n_neg_reel = matrice[0][0] + matrice[0][1] + matrice[0][2]; // Réel: Négatif (index 0)
-    let n_neu_reel = matrice[1][0] + matrice[1][1] + matrice[1][2]; // Réel: Neutre (index 1)
-    
-    let n_pos_estime = matrice[0][2] + matrice[1][2] + matrice[2][2]; // Estimé: Positif
-    let n_neg_estime = matrice[0][0] + matrice[1][0] + matrice[2][0]; // Estimé: Négatif
-    let n_neu_estime = matrice[0][1] + matrice[1][1] + matrice[2][1]; // Estimé: Neutre
-    
-    let total = n_pos_reel + n_neg_reel + n_neu_reel;
-
-    format!(
-        "[table=5]\n\
-        [cell]Réel/Estimé[/cell][cell]Positive[/cell][cell]Négatif[/cell][cell]Neutre[/cell][cell]Total réel[/cell]\n\
-        [cell]Positive[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Négatif[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Neutre[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Total estimé[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [/table]",
-        // Ligne Positive réelle
-        matrice[2][2], // TP_pos
-        matrice[2][0], // FN_pos→neg
-        matrice[2][1], // FN_pos→neu
-        n_pos_reel,
-        
-        // Ligne Négative réelle
-        matrice[0][2], // FP_neg→pos
-        matrice[0][0], // TP_neg
-        matrice[0][1], // FN_neg→neu
-        n_neg_reel,
-        
-        // Ligne Neutre réelle
-        matrice[1][2], // FP_neu→pos
-        matrice[1][0], // FP_neu→neg
-        matrice[1][1], // TP_neu
-        n_neu_reel,
-        
-        // Totaux estimés
-        n_pos_estime,
-        n_neg_estime,
-        n_neu_estime,
-        total
-    )
-}
\ No newline at end of file
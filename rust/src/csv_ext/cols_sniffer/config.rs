@@ -1,3 +0,0 @@
-pub(super) static DATA_TARGET_HEADERS: &[&str] = &["tweet", "message", "content", "data", "tweet_content"];
-pub(super) static RATING_TARGET_HEADERS: &[&str] = &["rating", "polarity", "grade", "positivity"];
-pub(super) static TWEET_MAX_CHARS: usize = 280;
\ No newline at end of file
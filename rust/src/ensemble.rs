@@ -0,0 +1,95 @@
+use godot::prelude::*;
+use twitter_classification_core::ensemble::membre::{Algorithme, Membre};
+use twitter_classification_core::ensemble::{self, Configuration};
+use twitter_classification_core::naive::modificateurs::Modificateurs;
+
+use crate::naive_classification::lire_modificateurs;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct Ensemble {
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for Ensemble {
+    fn init(base: Base<Node>) -> Self {
+        Self { base }
+    }
+}
+
+#[godot_api]
+impl Ensemble {
+    /// Classe un tweet avec les membres entraînés sur tout le fichier.
+    /// Chaque membre est un Dictionary : `algorithm` ("naive", "knn", "clustering", "bayes"), `weight` et ses réglages.
+    #[func]
+    fn ensemble_execute(&mut self, path: GString, members: Array<Dictionary>, combination: i64, tweet: GString) -> GString {
+        let resultat = configuration(&members, combination)
+            .and_then(|configuration| ensemble::classer(&path.to_string(), &configuration, &tweet.to_string()));
+
+        match resultat {
+            Ok(classe) => {
+                let texte = match classe {
+                    4 => "POSITIF",
+                    2 => "NEUTRE",
+                    0 => "NÉGATIF",
+                    _ => "INCONNU",
+                };
+                GString::from(texte)
+            },
+            Err(e) => {
+                self.signals().log_sent().emit(&GString::from(e));
+                GString::from("ERREUR")
+            },
+        }
+    }
+
+    #[signal]
+    fn log_sent(message: GString);
+}
+
+/// Membres et combinaison lus depuis Godot, de quoi lancer une évaluation sur un autre thread
+pub(crate) fn configuration(members: &Array<Dictionary>, combination: i64) -> Result<Configuration, String> {
+    let membres = members.iter_shared().map(|d| lire_membre(&d)).collect::<Result<Vec<_>, _>>()?;
+    Configuration::new(membres, combination)
+}
+
+/// Lit un membre décrit depuis Godot : `algorithm` ("naive", "knn", "clustering" ou "bayes"), `weight` (1 par défaut)
+/// et les réglages de l'algorithme (`positive`, `negative`, `threshold`, `modifiers` ; `k`, `vote` ; `k`, `method` ;
+/// `vote`, `representation`, `ngram`), les réglages absents prenant les valeurs par défaut des onglets.
+fn lire_membre(d: &Dictionary) -> Result<Membre, String> {
+    let entier = |cle: &str, defaut: i64| d.get(cle).and_then(|v| v.try_to::<i64>().ok()).unwrap_or(defaut);
+    let reel = |cle: &str, defaut: f64| {
+        d.get(cle)
+            .and_then(|v| v.try_to::<f64>().ok().or_else(|| v.try_to::<i64>().ok().map(|e| e as f64)))
+            .unwrap_or(defaut)
+    };
+    let texte = |cle: &str| d.get(cle).and_then(|v| v.try_to::<GString>().ok()).map(|s| s.to_string());
+
+    let nom = texte("algorithm").ok_or("Membre sans algorithme")?;
+    let algorithme = match nom.as_str() {
+        "naive" => Algorithme::Naive {
+            positifs: texte("positive").ok_or("Membre naive sans dictionnaire positif")?,
+            negatifs: texte("negative").ok_or("Membre naive sans dictionnaire négatif")?,
+            seuil: reel("threshold", 0.5),
+            modificateurs: d
+                .get("modifiers")
+                .and_then(|v| v.try_to::<Dictionary>().ok())
+                .map(|options| lire_modificateurs(&options, Modificateurs::default()))
+                .unwrap_or_default(),
+        },
+        "knn" => Algorithme::Knn { k: entier("k", 3).max(1) as usize, vote: entier("vote", 0) },
+        "clustering" => Algorithme::Clustering { k: entier("k", 3).max(1) as usize, method: entier("method", 0).max(0) as usize },
+        "bayes" => Algorithme::Bayes {
+            vote: entier("vote", 0),
+            representation: entier("representation", 0),
+            ngram: entier("ngram", 0),
+        },
+        _ => return Err(format!("Algorithme inconnu ({})", nom)),
+    };
+    let poids = reel("weight", 1.0);
+    if poids < 0.0 {
+        return Err(format!("Poids négatif pour {}", nom));
+    }
+    Ok(Membre { algorithme, poids })
+}
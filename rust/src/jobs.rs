@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::thread::{self, JoinHandle};
+
+use godot::prelude::*;
+
+use twitter_classification_core::naive::modificateurs::Modificateurs;
+use twitter_classification_core::progress::Progress;
+use twitter_classification_core::{bayes, cleandata, clustering, ensemble, knn, naive};
+
+use crate::clustering::resultat_evaluation;
+use crate::ensemble::configuration;
+use crate::naive_classification::lire_modificateurs;
+
+// Lance les traitements longs (nettoyage, évaluations) sur des threads de travail : c'est la seule façon
+// de lancer une évaluation depuis Godot. Le noeud doit être dans l'arbre de scène : les tâches sont suivies
+// dans `process`, sur le thread principal, d'où les signaux sont émis.
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct Jobs {
+    base: Base<Node>,
+    next_id: i64,
+    running: HashMap<i64, Job>,
+}
+
+struct Job {
+    progress: Progress,
+    handle: JoinHandle<JobOutput>,
+    last_state: (usize, usize),
+}
+
+// Les types Godot ne sont pas Send : les threads renvoient des valeurs rust,
+// converties sur le thread principal en ce que reçoit le signal `finished`.
+enum JobOutput {
+    CleanData(Result<String, String>),
+    Table(Result<String, String>),
+    Clustering(Result<clustering::ResultatEvaluation, String>),
+}
+
+impl JobOutput {
+    fn into_variant(self) -> Variant {
+        match self {
+            JobOutput::CleanData(Ok(path)) => GString::from(path).to_variant(),
+            JobOutput::CleanData(Err(_)) => GString::new().to_variant(),
+            JobOutput::Table(Ok(table)) => GString::from(table).to_variant(),
+            JobOutput::Table(Err(_)) => GString::from("ERREUR").to_variant(),
+            JobOutput::Clustering(result) => resultat_evaluation(result).to_variant(),
+        }
+    }
+
+    fn error(&self) -> Option<&str> {
+        match self {
+            JobOutput::CleanData(Err(e)) | JobOutput::Table(Err(e)) | JobOutput::Clustering(Err(e)) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[godot_api]
+impl INode for Jobs {
+    fn init(base: Base<Node>) -> Self {
+        Self { base, next_id: 1, running: HashMap::new() }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process(true);
+    }
+
+    fn process(&mut self, _delta: f64) {
+        self.poll();
+    }
+}
+
+#[godot_api]
+impl Jobs {
+    #[func]
+    fn start_clean_data(&mut self, path: GString) -> i64 {
+        self.start_clean(path.to_string(), None)
+    }
+
+    #[func]
+    fn start_clean_data_with_rules(&mut self, path: GString, rules_path: GString) -> i64 {
+        self.start_clean(path.to_string(), Some(rules_path.to_string()))
+    }
+
+    // `modifiers` : règles de valence, mêmes clés que Naive.set_modifiers (toutes inactives si absentes)
+    #[func]
+    fn start_naive_evaluate(&mut self, path_data: GString, path_pos: GString, path_neg: GString, weight: f64, modifiers: Dictionary, graine: i64) -> i64 {
+        let (path_data, path_pos, path_neg) = (path_data.to_string(), path_pos.to_string(), path_neg.to_string());
+        let modificateurs = lire_modificateurs(&modifiers, Modificateurs::default());
+        self.spawn(move |progress| {
+            JobOutput::Table(naive::evaluer(&path_data, &path_pos, &path_neg, weight, &modificateurs, graine, progress))
+        })
+    }
+
+    #[func]
+    fn start_knn_evaluate(&mut self, path: GString, k: i64, type_vote: i64, graine: i64) -> i64 {
+        let path = path.to_string();
+        self.spawn(move |progress| {
+            JobOutput::Table(knn::evaluer(&path, k, type_vote, graine, progress))
+        })
+    }
+
+    #[func]
+    fn start_bayes_evaluate(&mut self, path: GString, type_vote: i64, type_representation: i64, ngram_type: i64, graine: i64) -> i64 {
+        let path = path.to_string();
+        self.spawn(move |progress| {
+            JobOutput::Table(bayes::evaluer(&path, type_vote, type_representation, ngram_type, graine, progress))
+        })
+    }
+
+    #[func]
+    fn start_clustering_evaluate(&mut self, path: GString, k: i64, method: i64, graine: i64) -> i64 {
+        let path = path.to_string();
+        self.spawn(move |progress| {
+            let result = clustering::run_evaluation_pipeline(&path, k.max(1) as usize, method.max(0) as usize, graine, progress);
+            JobOutput::Clustering(result.map_err(|e| e.to_string()))
+        })
+    }
+
+    // Les membres sont lus ici, les valeurs Godot ne pouvant pas être envoyées au thread
+    #[func]
+    fn start_ensemble_evaluate(&mut self, path: GString, members: Array<Dictionary>, combination: i64, graine: i64) -> i64 {
+        let path = path.to_string();
+        let configuration = configuration(&members, combination);
+        self.spawn(move |progress| {
+            JobOutput::Table(configuration.and_then(|configuration| ensemble::evaluer(&path, &configuration, graine, progress)))
+        })
+    }
+
+    // Demande l'arrêt de la tâche, `finished` est alors émis avec un résultat nul.
+    // Renvoie false si la tâche est inconnue ou déjà terminée.
+    #[func]
+    fn cancel(&mut self, job_id: i64) -> bool {
+        match self.running.get(&job_id) {
+            Some(job) => {
+                job.progress.cancel();
+                true
+            },
+            None => false,
+        }
+    }
+
+    #[func]
+    fn is_running(&self, job_id: i64) -> bool {
+        self.running.contains_key(&job_id)
+    }
+
+    #[signal]
+    fn progress(job_id: i64, done: i64, total: i64);
+
+    #[signal]
+    fn finished(job_id: i64, result: Variant);
+
+    #[signal]
+    fn log_sent(message: GString);
+}
+
+impl Jobs {
+    fn start_clean(&mut self, path: String, rules_path: Option<String>) -> i64 {
+        self.spawn(move |progress| {
+            let mut log = |message: String| progress.log(message);
+            let result = cleandata::entry::clean_data_body(&path, rules_path.as_deref(), &mut log, progress);
+            JobOutput::CleanData(result.map_err(|e| e.to_string()))
+        })
+    }
+
+    fn spawn(&mut self, work: impl FnOnce(&Progress) -> JobOutput + Send + 'static) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let progress = Progress::default();
+        let worker_progress = progress.clone();
+        let handle = thread::spawn(move || work(&worker_progress));
+
+        self.running.insert(id, Job { progress, handle, last_state: (0, 0) });
+        id
+    }
+
+    fn poll(&mut self) {
+        // Tout rassembler d'abord : les fonctions connectées aux signaux peuvent rappeler ce noeud
+        let mut logs = vec![];
+        let mut progressions = vec![];
+        let mut over = vec![];
+
+        for (&id, job) in self.running.iter_mut() {
+            logs.extend(job.progress.take_logs());
+
+            let state = job.progress.state();
+            if state != job.last_state {
+                job.last_state = state;
+                progressions.push((id, state));
+            }
+
+            if job.handle.is_finished() {
+                over.push(id);
+            }
+        }
+
+        for message in logs {
+            self.signals().log_sent().emit(&GString::from(message));
+        }
+
+        for (id, (done, total)) in progressions {
+            self.signals().progress().emit(id, done as i64, total as i64);
+        }
+
+        for id in over {
+            let Some(job) = self.running.remove(&id) else { continue };
+            let cancelled = job.progress.is_cancelled();
+
+            // Messages envoyés entre le dernier suivi et la fin de la tâche
+            for message in job.progress.take_logs() {
+                self.signals().log_sent().emit(&GString::from(message));
+            }
+
+            let result = match job.handle.join() {
+                Ok(_) if cancelled => Variant::nil(),
+                Ok(output) => {
+                    if let Some(e) = output.error() {
+                        self.signals().log_sent().emit(&GString::from(e));
+                    }
+                    output.into_variant()
+                },
+                Err(_) => {
+                    self.signals().log_sent().emit(&GString::from(format!("La tâche {id} a paniqué")));
+                    Variant::nil()
+                },
+            };
+            self.signals().finished().emit(id, &result);
+        }
+    }
+}
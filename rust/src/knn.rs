@@ -1,9 +1,5 @@
-use std::collections::{HashSet, HashMap};
 use godot::prelude::*;
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use rand::seq::SliceRandom;
-use rand::rng;
+use twitter_classification_core::knn::{charger_donnees, classifier_tweet, proches_voisins, selection, verifier_k, TypeVote};
 
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -18,44 +14,13 @@ impl INode for Knn {
     }
 }
 
-#[derive(Debug, Clone)]
-struct TweetEtiquete {
-    contenu: String,
-    etiquette: i32, // 4=positif, 2=neutre, 0=négatif
-}
-
-#[derive(Debug, Clone, Copy)]
-enum TypeVote {
-    Majoritaire,
-    Pondere,
-}
-
-impl TypeVote {
-    fn vote(&self, proches_voisins: &[(f64, i32)]) -> Option<i32> {
-        match self {
-            TypeVote::Majoritaire => vote_majoritaire(proches_voisins),
-            TypeVote::Pondere => vote_pondere(proches_voisins),
-        }
-    }
-}
-
-impl From<i64> for TypeVote {
-    fn from(value: i64) -> Self {
-        match value {
-            1 => TypeVote::Pondere,
-            _ => TypeVote::Majoritaire,
-        }
-    }
-}
-
 #[godot_api]
 impl Knn {
     #[func]
     fn knn_execute(&mut self, path: GString, tweet_a_classifier: GString, k: i64, type_vote: i64) -> GString {
         let path_str = path.to_string();
         let tweet_str = tweet_a_classifier.to_string();
-        let k_usize = k as usize;
-        
+
         // Déterminer le type de vote
         let vote_type = TypeVote::from(type_vote);
         
@@ -67,6 +32,13 @@ impl Knn {
                 return GString::from("ERREUR");
             }
         };
+        let k_usize = match verifier_k(k, base.len()) {
+            Ok(k_usize) => k_usize,
+            Err(e) => {
+                self.signals().log_sent().emit(&GString::from(e));
+                return GString::from("ERREUR");
+            }
+        };
         
         match classifier_tweet(&tweet_str, k_usize, &base, vote_type) {
             Some(classe) => {
@@ -85,266 +57,88 @@ impl Knn {
         }
     }
 
+    /// Renvoie les k voisins qui ont décidé de la classe du tweet :
+    /// un Dictionary par voisin (texte, étiquette, distance, poids dans le vote), du plus proche au plus éloigné.
     #[func]
-    fn knn_evaluate(&mut self, path: GString, k: i64, type_vote: i64) -> GString {
-        let path_str = path.to_string();
-        let k_usize = k as usize;
-        
-        // Déterminer le type de vote
+    fn knn_explain(&mut self, path: GString, tweet_a_classifier: GString, k: i64, type_vote: i64) -> Array<Dictionary> {
+        let mut voisins = Array::<Dictionary>::new();
         let vote_type = TypeVote::from(type_vote);
 
-        // Charger les données depuis le CSV
-        let base_complete = match charger_donnees(&path_str) {
+        let base = match charger_donnees(&path.to_string()) {
             Ok(donnees) => donnees,
             Err(e) => {
                 self.signals().log_sent().emit(&GString::from(format!("Erreur chargement données: {}", e)));
-                return GString::from("ERREUR");
+                return voisins;
             }
         };
 
-        // Division stratifiée 2/3 - 1/3
-        let (base_entrainement, base_test) = diviser_donnees_stratifiee(&base_complete, 2.0/3.0);
-
-        if base_entrainement.is_empty() || base_test.is_empty() {
-            self.signals().log_sent().emit(&GString::from("Base d'entraînement ou test vide après division"));
-            return GString::from("ERREUR");
-        }
-
-        // Matrice de confusion : [réel][estimé]
-        // Indices: 0=négatif, 2=neutre, 4=positif
-        let mut matrice_confusion = [[0; 3]; 3];
-        let index_map: HashMap<i32, usize> = [(0, 0), (2, 1), (4, 2)].iter().cloned().collect();
-
-        // Évaluation sur le jeu de test
-        for tweet_test in &base_test {
-            if let Some(classe_estimee) = classifier_tweet(&tweet_test.contenu, k_usize, &base_entrainement, vote_type) {
-                if let (Some(&idx_reel), Some(&idx_estime)) = (
-                    index_map.get(&tweet_test.etiquette),
-                    index_map.get(&classe_estimee)
-                ) {
-                    matrice_confusion[idx_reel][idx_estime] += 1;
-                }
+        let k_usize = match verifier_k(k, base.len()) {
+            Ok(k_usize) => k_usize,
+            Err(e) => {
+                self.signals().log_sent().emit(&GString::from(e));
+                return voisins;
             }
-        }
-
-        // Construction du tableau de résultats
-        let resultat = format_matrice_confusion(&matrice_confusion);
-        GString::from(resultat)
-    }
-    
-    #[signal]
-    fn log_sent(message: GString);
-}
+        };
 
-fn classifier_tweet(x: &str, k: usize, base: &[TweetEtiquete], type_vote: TypeVote) -> Option<i32> {
-    if base.is_empty() || k == 0 || k > base.len() {
-        return None;
-    }
-    
-    // Étape 1: Initialiser la liste des proches voisins
-    let mut proches_voisins: Vec<(f64, i32)> = Vec::new(); // (distance, etiquette)
-    
-    // Étape 2: Parcourir tous les tweets de la base
-    for tweet in base {
-        let distance = distance_tweets(x, &tweet.contenu);
-        
-        if proches_voisins.len() < k {
-            proches_voisins.push((distance, tweet.etiquette));
-            // Trier par distance croissante
-            proches_voisins.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-        } else {
-            // Vérifier si ce tweet est plus proche que le plus éloigné actuel
-            if distance < proches_voisins.last().unwrap().0 {
-                // Remplacer le voisin le plus éloigné
-                proches_voisins.pop();
-                proches_voisins.push((distance, tweet.etiquette));
-                proches_voisins.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-            }
+        for (distance, idx) in proches_voisins(&tweet_a_classifier.to_string(), k_usize, &base) {
+            let tweet = &base[idx];
+            let mut d = Dictionary::new();
+            d.set("text", tweet.contenu.as_str());
+            d.set("label", tweet.etiquette as i64);
+            d.set("distance", distance);
+            d.set("weight", vote_type.poids(distance));
+            voisins.push(&d);
         }
+        voisins
     }
-    
-    type_vote.vote(&proches_voisins)
-}
 
-/// Vote majoritaire simple (comptage des occurrences)
-fn vote_majoritaire(proches_voisins: &[(f64, i32)]) -> Option<i32> {
-    let mut votes: HashMap<i32, usize> = HashMap::new();
-    
-    for (_, etiquette) in proches_voisins {
-        *votes.entry(*etiquette).or_insert(0) += 1;
-    }
+    /// Choix automatique de k (et du type de vote) par validation croisée stratifiée.
+    /// Renvoie la courbe métrique / k pour l'affichage et le meilleur réglage trouvé.
+    #[func]
+    fn knn_select_k(&mut self, path: GString, k_min: i64, k_max: i64, n_plis: i64, graine: i64) -> Dictionary {
+        let mut resultat = Dictionary::new();
 
-    let max_votes = votes.values().max().copied().unwrap_or(0);
-    
-    //On prend toutes les classes qui ont les votes max
-    let candidats: Vec<i32> = votes.iter()
-        .filter(|&(_, &count)| count == max_votes)
-        .map(|(&cls, _)| cls)
-        .collect();
-    
-    if candidats.len() == 1 {
-        Some(candidats[0])
-    } else {
-        // On retourne la première classe trouvée dans les proches voisins qui est dans les candidats
-        for (_, etiquette) in proches_voisins {
-            if candidats.contains(etiquette) {
-                return Some(*etiquette);
+        let base = match charger_donnees(&path.to_string()) {
+            Ok(donnees) => donnees,
+            Err(e) => {
+                self.signals().log_sent().emit(&GString::from(format!("Erreur chargement données: {}", e)));
+                resultat.set("status", "ERROR");
+                resultat.set("message", format!("Erreur chargement données: {}", e));
+                return resultat;
             }
-        }
-        None // ça ne doit pas arriver si k > 0
-    }
-}
-
-/// Vote pondéré par l'inverse de la distance
-/// Les voisins plus proches ont plus de poids
-fn vote_pondere(proches_voisins: &[(f64, i32)]) -> Option<i32> {
-    let mut votes_ponderes: HashMap<i32, f64> = HashMap::new();
-    let epsilon = 1e-5;
-
-    for (distance, etiquette) in proches_voisins {
-        // Éviter la division par zéro en ajoutant un petit epsilon
-        let poids = 1.0 / (distance + epsilon);
-
-        *votes_ponderes.entry(*etiquette).or_insert(0.0) += poids;
-    }
-    
-    votes_ponderes
-        .into_iter()
-        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-        .map(|(classe, _)| classe)
-}
+        };
 
-fn charger_donnees(chemin: &str) -> Result<Vec<TweetEtiquete>, Box<dyn std::error::Error>> {
-    let fichier = File::open(chemin)?;
-    let reader = BufReader::new(fichier);
-    let mut donnees = Vec::new();
-    
-    for (index, ligne) in reader.lines().enumerate() {
-        let ligne = ligne?;
-        // Ignorer l'en-tête
-        if index == 0 {
-            continue;
-        }
-        
-        let colonnes: Vec<&str> = ligne.split(',').collect();
-        if colonnes.len() >= 2 {
-            if let Ok(etiquette) = colonnes[0].parse::<i32>() {
-                let contenu_raw = colonnes[1..].join(",");
-                let contenu = contenu_raw.trim_matches('"').to_string();
-                donnees.push(TweetEtiquete {
-                    contenu,
-                    etiquette,
-                });
+        let courbe = match selection::balayer_k(&base, k_min.max(1) as usize, k_max.max(0) as usize, n_plis.max(2) as usize, graine) {
+            Ok(courbe) => courbe,
+            Err(e) => {
+                self.signals().log_sent().emit(&GString::from(e.clone()));
+                resultat.set("status", "ERROR");
+                resultat.set("message", e);
+                return resultat;
             }
+        };
+
+        let mut points = Array::<Dictionary>::new();
+        for point in &courbe {
+            let mut d = Dictionary::new();
+            d.set("k", point.k as i64);
+            d.set("vote", i64::from(point.vote));
+            d.set("accuracy", point.exactitude);
+            d.set("f1_macro", point.f1_macro);
+            points.push(&d);
         }
-    }
-    Ok(donnees)
-}
 
-/// Calcule la distance entre deux tweets selon la formule :
-/// D(t1, t2) = (Nombre total de mots - Nombre de mots communs) / Nombre total de mots
-fn distance_tweets(tweet1: &str, tweet2: &str) -> f64 {
-    // Tokeniser les tweets en mots (en minuscules pour ignorer la casse)
-    let mots_tweet1: HashSet<String> = tokeniser_tweet(tweet1);
-    let mots_tweet2: HashSet<String> = tokeniser_tweet(tweet2);
-    
-    // Calculer l'union des mots (nombre total de mots distincts)
-    let union_mots: HashSet<&String> = mots_tweet1.union(&mots_tweet2).collect();
-    let nombre_total_mots = union_mots.len() as f64;
-    
-    // Calculer l'intersection des mots (mots communs)
-    let intersection_mots: HashSet<&String> = mots_tweet1.intersection(&mots_tweet2).collect();
-    let nombre_mots_communs = intersection_mots.len() as f64;
-    
-    // Appliquer la formule de distance
-    if nombre_total_mots == 0.0 {
-        0.0 // Si les deux tweets sont vides, distance = 0
-    } else {
-        (nombre_total_mots - nombre_mots_communs) / nombre_total_mots
+        resultat.set("status", "OK");
+        resultat.set("curve", points);
+        if let Some(meilleur) = selection::meilleur_point(&courbe) {
+            resultat.set("best_k", meilleur.k as i64);
+            resultat.set("best_vote", i64::from(meilleur.vote));
+            resultat.set("best_accuracy", meilleur.exactitude);
+            resultat.set("best_f1_macro", meilleur.f1_macro);
+        }
+        resultat
     }
-}
-
-/// Tokenise un tweet en mots (minuscules)
-fn tokeniser_tweet(tweet: &str) -> HashSet<String> {
-    tweet
-        .split_whitespace()
-        .map(|mot| mot.to_lowercase())
-        .collect()
-}
-
-/// Division stratifiée des données (mêmes proportions de classes dans train/test)
-fn diviser_donnees_stratifiee(donnees: &[TweetEtiquete], ratio_train: f64) -> (Vec<TweetEtiquete>, Vec<TweetEtiquete>) {
-    let mut rng = rng();
     
-    // Séparer les données par classe
-    let mut par_classe: HashMap<i32, Vec<TweetEtiquete>> = HashMap::new();
-    for tweet in donnees {
-        par_classe.entry(tweet.etiquette)
-            .or_insert_with(Vec::new)
-            .push(tweet.clone());
-    }
-
-    let mut entrainement = Vec::new();
-    let mut test = Vec::new();
-
-    // Pour chaque classe, diviser selon le ratio
-    for (_, mut tweets_classe) in par_classe {
-        tweets_classe.shuffle(&mut rng);
-        let index_split = (tweets_classe.len() as f64 * ratio_train) as usize;
-        
-        entrainement.extend_from_slice(&tweets_classe[..index_split]);
-        test.extend_from_slice(&tweets_classe[index_split..]);
-    }
-
-    // Mélanger les ensembles finaux
-    entrainement.shuffle(&mut rng);
-    test.shuffle(&mut rng);
-
-    (entrainement, test)
+    #[signal]
+    fn log_sent(message: GString);
 }
-
-// Formate la matrice de confusion selon le format demandé
-fn format_matrice_confusion(matrice: &[[i32; 3]]) -> String {
-    let n_pos_reel = matrice[2][0] + matrice[2][1] + matrice[2][2]; // Réel: Positif (index 2)
-    let n_neg_reel = matrice[0][0] + matrice[0][1] + matrice[0][2]; // Réel: Négatif (index 0)
-    let n_neu_reel = matrice[1][0] + matrice[1][1] + matrice[1][2]; // Réel: Neutre (index 1)
-    
-    let n_pos_estime = matrice[0][2] + matrice[1][2] + matrice[2][2]; // Estimé: Positif
-    let n_neg_estime = matrice[0][0] + matrice[1][0] + matrice[2][0]; // Estimé: Négatif
-    let n_neu_estime = matrice[0][1] + matrice[1][1] + matrice[2][1]; // Estimé: Neutre
-    
-    let total = n_pos_reel + n_neg_reel + n_neu_reel;
-
-    format!(
-        "[table=5]\n\
-        [cell]Réel/Estimé[/cell][cell]Positive[/cell][cell]Négatif[/cell][cell]Neutre[/cell][cell]Total réel[/cell]\n\
-        [cell]Positive[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Négatif[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Neutre[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [cell]Total estimé[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell][cell]{}[/cell]\n\
-        [/table]",
-        // Ligne Positive réelle
-        matrice[2][2], // TP_pos
-        matrice[2][0], // FN_pos→neg
-        matrice[2][1], // FN_pos→neu
-        n_pos_reel,
-        
-        // Ligne Négative réelle
-        matrice[0][2], // FP_neg→pos
-        matrice[0][0], // TP_neg
-        matrice[0][1], // FN_neg→neu
-        n_neg_reel,
-        
-        // Ligne Neutre réelle
-        matrice[1][2], // FP_neu→pos
-        matrice[1][0], // FP_neu→neg
-        matrice[1][1], // TP_neu
-        n_neu_reel,
-        
-        // Totaux estimés
-        n_pos_estime,
-        n_neg_estime,
-        n_neu_estime,
-        total
-    )
-}
\ No newline at end of file
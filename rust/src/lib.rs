@@ -9,6 +9,9 @@ mod cleandata;
 mod knn;
 mod clustering;
 mod bayes;
-mod regex_ext;
-mod csv_ext;
 mod naive_classification;
+mod parallel;
+mod jobs;
+mod active_learning;
+mod agreement;
+mod ensemble;
@@ -0,0 +1,29 @@
+use godot::prelude::*;
+use twitter_classification_core::parallel;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct Parallel {
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for Parallel {
+    fn init(base: Base<Node>) -> Self {
+        Self { base }
+    }
+}
+
+#[godot_api]
+impl Parallel {
+    /// Fixe le nombre de threads des évaluations et du calcul des distances (0 ou négatif = automatique)
+    #[func]
+    fn set_thread_count(count: i64) {
+        parallel::set_nb_threads(count.max(0) as usize);
+    }
+
+    #[func]
+    fn get_thread_count() -> i64 {
+        parallel::nb_threads() as i64
+    }
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+
+use clap::Args;
+use twitter_classification_core::naive::annotation::{self, Annotation, Colonne};
+use twitter_classification_core::naive::charger_lexique;
+use twitter_classification_core::naive::lexique::Lexique;
+use twitter_classification_core::naive::modificateurs::Modificateurs;
+
+/// Modificateurs de valence du classifieur naïf, tous désactivés par défaut
+#[derive(Args, Debug)]
+pub struct ArgumentsModificateurs {
+    /// Intensifieurs (« very good »)
+    #[arg(long)]
+    intensifiers: bool,
+
+    /// Atténuateurs (« kinda good »)
+    #[arg(long)]
+    diminishers: bool,
+
+    /// Négations (« not good »)
+    #[arg(long)]
+    negation: bool,
+
+    /// Nombre de mots avant une entrée où chercher une négation
+    #[arg(long, default_value_t = 3)]
+    negation_window: usize,
+
+    /// Points d'exclamation
+    #[arg(long)]
+    exclamation: bool,
+
+    /// Entrées en majuscules
+    #[arg(long)]
+    caps: bool,
+
+    /// Émoticônes et emojis
+    #[arg(long)]
+    emoji: bool,
+}
+
+impl From<&ArgumentsModificateurs> for Modificateurs {
+    fn from(args: &ArgumentsModificateurs) -> Self {
+        Modificateurs {
+            intensifieurs: args.intensifiers,
+            attenuateurs: args.diminishers,
+            negation: args.negation,
+            fenetre_negation: args.negation_window.max(1),
+            exclamation: args.exclamation,
+            majuscules: args.caps,
+            emojis: args.emoji,
+        }
+    }
+}
+
+/// Dictionnaires et réglages du classifieur naïf
+#[derive(Args, Debug)]
+pub struct ArgumentsLexique {
+    /// Dictionnaire positif : liste de mots séparés par des virgules ou lexique pondéré (VADER, AFINN, SentiWordNet)
+    #[arg(short, long)]
+    pub positive: String,
+
+    /// Dictionnaire négatif, facultatif si le dictionnaire positif contient des scores signés
+    #[arg(short, long, default_value = "")]
+    pub negative: String,
+
+    /// Part minimale de la polarité dominante, entre 0 et 1 (1 : entrées toutes positives ou toutes négatives)
+    #[arg(short, long, default_value_t = 0.5)]
+    pub weight: f32,
+
+    #[command(flatten)]
+    pub modificateurs: ArgumentsModificateurs,
+}
+
+impl ArgumentsLexique {
+    pub fn lexique(&self) -> Result<Lexique, String> {
+        charger_lexique(&self.positive, &self.negative)
+    }
+
+    pub fn weight(&self) -> f32 {
+        self.weight.clamp(0.0, 1.0)
+    }
+}
+
+/// Annotation d'un CSV de tweets avec le classifieur naïf : `label,colonnes...` (4 = positif, 2 = neutre, 0 = négatif)
+#[derive(Args, Debug)]
+pub struct ArgumentsAnnotation {
+    /// CSV des tweets à annoter
+    #[arg(short, long)]
+    input: String,
+
+    /// CSV annoté à écrire
+    #[arg(short, long, default_value = "naive_annotation.csv")]
+    output: String,
+
+    /// Colonne du texte, par indice (à partir de 0) ou par nom avec --header (5 pour Sentiment140)
+    #[arg(short, long, default_value = "5")]
+    text_column: Colonne,
+
+    /// Colonnes recopiées après l'étiquette, séparées par des virgules (la colonne du texte par défaut)
+    #[arg(short, long, value_delimiter = ',')]
+    columns: Vec<Colonne>,
+
+    /// La première ligne de l'entrée est un en-tête
+    #[arg(long)]
+    header: bool,
+
+    #[command(flatten)]
+    lexique: ArgumentsLexique,
+}
+
+impl ArgumentsAnnotation {
+    pub fn annoter(&self) -> Result<(), Box<dyn Error>> {
+        let lexique = self.lexique.lexique()?;
+        let options = Annotation {
+            colonne_texte: self.text_column.clone(),
+            colonnes_sortie: self.columns.clone(),
+            entete: self.header,
+            weight: self.lexique.weight(),
+        };
+
+        let bilan = annotation::annoter(&self.input, &self.output, &options, &lexique, &(&self.lexique.modificateurs).into())
+            .map_err(|e| format!("Erreur annotation: {}", e))?;
+        println!(
+            "{} tweets annotés dans {} (négatifs : {}, neutres : {}, positifs : {}), {} lignes ignorées",
+            bilan.annotees, self.output, bilan.par_classe[0], bilan.par_classe[1], bilan.par_classe[2], bilan.ignorees
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use std::io::Read;
+use std::process::ExitCode;
+
+use clap::Parser;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Response, Server};
+use twitter_classification_core::modele::Modele;
+
+// Au-delà, le corps de la requête est tronqué (et le JSON refusé)
+const TAILLE_MAX_CORPS: u64 = 16 * 1024 * 1024;
+
+/// Serveur HTTP local de classification (4 = positif, 2 = neutre, 0 = négatif) répondant en JSON.
+/// Routes : GET /health, POST /classify {"text": ...}, POST /classify_batch {"texts": [...]}, POST /explain {"text": ...}
+#[derive(Parser, Debug)]
+#[command(name = "classification_server", version)]
+struct Args {
+    /// Modèle enregistré par `twitter-classification train` (ou modèle de clustering enregistré depuis Godot)
+    #[arg(short, long)]
+    model: String,
+
+    /// Adresse d'écoute
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    address: String,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut modele = match Modele::charger(&args.model) {
+        Ok(modele) => modele,
+        Err(e) => {
+            eprintln!("Erreur chargement modèle: {}", e);
+            return ExitCode::FAILURE;
+        },
+    };
+    let server = match Server::http(&args.address) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Erreur serveur: {}", e);
+            return ExitCode::FAILURE;
+        },
+    };
+    // Construit une seule fois : aucune requête ne peut échouer sur l'en-tête
+    let entete = match Header::from_bytes("Content-Type", "application/json") {
+        Ok(entete) => entete,
+        Err(()) => {
+            eprintln!("Erreur serveur: en-tête Content-Type invalide");
+            return ExitCode::FAILURE;
+        },
+    };
+    eprintln!("Modèle {} servi sur http://{}", modele.algorithme(), args.address);
+
+    // Un seul modèle, les requêtes sont traitées l'une après l'autre
+    for mut requete in server.incoming_requests() {
+        let (methode, url) = (requete.method().clone(), requete.url().to_string());
+        let (statut, corps) = reponse(&mut modele, &methode, &url, requete.as_reader());
+        let reponse = Response::from_string(corps.to_string()).with_status_code(statut).with_header(entete.clone());
+        if let Err(e) = requete.respond(reponse) {
+            eprintln!("Erreur réponse: {}", e);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// Statut HTTP et corps JSON de la réponse, les erreurs étant `{"error": message}`
+fn reponse(modele: &mut Modele, methode: &Method, url: &str, corps: &mut dyn Read) -> (u16, Value) {
+    match repondre(modele, methode, url, corps) {
+        Ok(corps) => (200, corps),
+        Err((statut, message)) => (statut, json!({ "error": message })),
+    }
+}
+
+// Corps JSON de la réponse, ou statut HTTP et message de l'erreur. Le corps de la requête n'est lu que par les routes POST.
+fn repondre(modele: &mut Modele, methode: &Method, url: &str, corps: &mut dyn Read) -> Result<Value, (u16, String)> {
+    let route = url.split('?').next().unwrap_or_default();
+
+    match (methode, route) {
+        (Method::Get, "/health") => Ok(json!({ "status": "ok", "algorithm": modele.algorithme() })),
+        (Method::Post, "/classify") => {
+            let texte = champ_texte(&lire_json(corps)?)?;
+            Ok(json!({ "text": texte, "label": modele.classifier(&texte) }))
+        },
+        (Method::Post, "/classify_batch") => {
+            let corps = lire_json(corps)?;
+            let textes = corps
+                .get("texts")
+                .and_then(Value::as_array)
+                .ok_or((400, "Champ \"texts\" (tableau de textes) attendu".to_string()))?;
+            let labels = textes
+                .iter()
+                .map(|texte| texte.as_str().map(|texte| modele.classifier(texte)))
+                .collect::<Option<Vec<i32>>>()
+                .ok_or((400, "Les éléments de \"texts\" doivent être des textes".to_string()))?;
+            Ok(json!({ "labels": labels }))
+        },
+        (Method::Post, "/explain") => {
+            let texte = champ_texte(&lire_json(corps)?)?;
+            let label = modele.classifier(&texte);
+            Ok(json!({ "text": texte, "label": label, "explanation": modele.expliquer(&texte) }))
+        },
+        (_, "/health" | "/classify" | "/classify_batch" | "/explain") => Err((405, "Méthode non autorisée".to_string())),
+        _ => Err((404, format!("Route inconnue : {}", route))),
+    }
+}
+
+fn lire_json(corps: &mut dyn Read) -> Result<Value, (u16, String)> {
+    let mut texte = String::new();
+    corps
+        .take(TAILLE_MAX_CORPS)
+        .read_to_string(&mut texte)
+        .map_err(|e| (400, format!("Corps illisible : {}", e)))?;
+    serde_json::from_str(&texte).map_err(|e| (400, format!("JSON invalide : {}", e)))
+}
+
+fn champ_texte(corps: &Value) -> Result<String, (u16, String)> {
+    corps
+        .get("text")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or((400, "Champ \"text\" attendu".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Modèle bayésien entraîné sur un petit fichier, sans passer par un modèle enregistré
+    fn modele() -> Modele {
+        let chemin = std::env::temp_dir().join(format!("{}_{}", std::process::id(), "serveur_donnees.csv"));
+        let mut contenu = String::from("label,texte\n");
+        for _ in 0..5 {
+            contenu.push_str("0,i hate this awful movie\n4,i love this great movie\n");
+        }
+        std::fs::write(&chemin, contenu).unwrap();
+        let modele = Modele::bayes(&chemin.to_string_lossy(), 0, 0, 0);
+        std::fs::remove_file(&chemin).unwrap();
+        modele.unwrap()
+    }
+
+    fn appeler(modele: &mut Modele, methode: Method, url: &str, corps: &str) -> (u16, Value) {
+        reponse(modele, &methode, url, &mut corps.as_bytes())
+    }
+
+    #[test]
+    fn classify() {
+        let mut modele = modele();
+        let (statut, corps) = appeler(&mut modele, Method::Post, "/classify", r#"{"text": "love this movie"}"#);
+        assert_eq!(statut, 200);
+        assert_eq!(corps, json!({ "text": "love this movie", "label": 4 }));
+
+        let (statut, corps) = appeler(&mut modele, Method::Post, "/classify?verbose=1", r#"{"texte": "love"}"#);
+        assert_eq!(statut, 400);
+        assert!(corps["error"].as_str().unwrap().contains("\"text\""));
+
+        let (statut, _) = appeler(&mut modele, Method::Post, "/classify", "pas du json");
+        assert_eq!(statut, 400);
+    }
+
+    #[test]
+    fn classify_batch() {
+        let mut modele = modele();
+        let (statut, corps) = appeler(&mut modele, Method::Post, "/classify_batch", r#"{"texts": ["hate it", "love it"]}"#);
+        assert_eq!(statut, 200);
+        assert_eq!(corps, json!({ "labels": [0, 4] }));
+
+        let (statut, _) = appeler(&mut modele, Method::Post, "/classify_batch", r#"{"texts": ["love it", 3]}"#);
+        assert_eq!(statut, 400);
+        let (statut, _) = appeler(&mut modele, Method::Post, "/classify_batch", r#"{"texts": "love it"}"#);
+        assert_eq!(statut, 400);
+    }
+
+    #[test]
+    fn health_et_explain() {
+        let mut modele = modele();
+        assert_eq!(appeler(&mut modele, Method::Get, "/health", ""), (200, json!({ "status": "ok", "algorithm": "bayes" })));
+        let (statut, corps) = appeler(&mut modele, Method::Post, "/explain", r#"{"text": "awful"}"#);
+        assert_eq!(statut, 200);
+        assert_eq!(corps["label"], 0);
+        assert!(corps.get("explanation").is_some());
+    }
+
+    #[test]
+    fn route_inconnue_et_methode_refusee() {
+        let mut modele = modele();
+        let (statut, corps) = appeler(&mut modele, Method::Get, "/predict", "");
+        assert_eq!(statut, 404);
+        assert_eq!(corps, json!({ "error": "Route inconnue : /predict" }));
+        assert_eq!(appeler(&mut modele, Method::Get, "/classify", "").0, 405);
+        assert_eq!(appeler(&mut modele, Method::Post, "/health", "").0, 405);
+        assert_eq!(appeler(&mut modele, Method::Delete, "/classify_batch", "").0, 405);
+    }
+}
@@ -0,0 +1,22 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use test_rust::arguments::ArgumentsAnnotation;
+
+/// Annote un CSV de tweets avec le classifieur naïf : écrit `label,colonnes...` (4 = positif, 2 = neutre, 0 = négatif)
+#[derive(Parser, Debug)]
+#[command(name = "naive_annotation", version)]
+struct Args {
+    #[command(flatten)]
+    annotation: ArgumentsAnnotation,
+}
+
+fn main() -> ExitCode {
+    match Args::parse().annotation.annoter() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,3 @@
+// Arguments partagés par les outils en ligne de commande (src/main.rs et src/bin)
+
+pub mod arguments;
@@ -1,80 +1,393 @@
-use num_traits::NumCast;
-use regex::Regex;
-use csv::{Reader, StringRecord, Writer};
-use std::env;
 use std::error::Error;
-use std::fs::{self, File};
-use std::path::PathBuf;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
 
-use crate::tools::words_dictionnary_to_reg;
+use clap::{Args, Parser, Subcommand};
+use serde_json::json;
+use test_rust::arguments::{ArgumentsAnnotation, ArgumentsLexique};
+use twitter_classification_core::cleandata::entry;
+use twitter_classification_core::modele::Modele;
+use twitter_classification_core::naive::lexique::Lexique;
+use twitter_classification_core::naive::modificateurs::Modificateurs;
+use twitter_classification_core::naive::{self, analyser_tweet, apprendre_lexique, induction};
+use twitter_classification_core::progress::Progress;
+use twitter_classification_core::validation::Rapport;
+use twitter_classification_core::{bayes, clustering, knn};
 
-mod tools;
+/// Classification de sentiment de tweets sans l'application Godot (4 = positif, 2 = neutre, 0 = négatif).
+/// Les fichiers annotés sont des CSV `label,texte` dont la première ligne, l'en-tête, est ignorée.
+#[derive(Parser, Debug)]
+#[command(name = "twitter-classification", version)]
+struct Cli {
+    #[command(subcommand)]
+    commande: Commande,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commande {
+    /// Nettoie un CSV de tweets (filtres automatiques, doublons) et écrit `label,texte`
+    Clean {
+        /// CSV à nettoyer, colonnes du texte et de l'étiquette détectées automatiquement
+        #[arg(short, long)]
+        input: String,
+
+        #[arg(short, long, default_value = "clean_data.csv")]
+        output: String,
+
+        /// Fichier de règles (TOML ou JSON) à la place des filtres automatiques
+        #[arg(short, long)]
+        rules: Option<String>,
+    },
+
+    /// Annote un CSV de tweets avec le classifieur naïf
+    Annotate(ArgumentsAnnotation),
+
+    /// Entraîne un modèle sur tout un fichier annoté et l'enregistre (JSON), ou apprend un lexique pour le classifieur naïf
+    Train {
+        /// Fichier annoté `label,texte`
+        #[arg(short, long)]
+        data: String,
+
+        /// Modèle à écrire, relu par classify --model
+        #[arg(short, long, default_value = "model.json")]
+        output: String,
+
+        #[command(subcommand)]
+        algorithme: Entrainement,
+    },
+
+    /// Évalue un classifieur sur une division stratifiée 2/3 - 1/3 d'un fichier annoté
+    Evaluate {
+        /// Fichier annoté `label,texte`
+        #[arg(short, long)]
+        data: String,
+
+        /// Graine de la division, négative pour un tirage aléatoire
+        #[arg(short, long, default_value_t = 0, allow_negative_numbers = true)]
+        seed: i64,
+
+        /// Métriques en JSON sur la sortie standard
+        #[arg(long, global = true)]
+        json: bool,
+
+        #[command(subcommand)]
+        algorithme: Algorithme,
+    },
+
+    /// Classe les tweets donnés en arguments, ou chaque ligne de l'entrée standard s'il n'y en a pas
+    Classify {
+        /// Modèle enregistré par train (ou depuis Godot pour le clustering)
+        #[arg(short, long, conflicts_with = "data")]
+        model: Option<String>,
+
+        /// Fichier annoté sur lequel entraîner l'algorithme avant de classer (inutile pour le classifieur naïf)
+        #[arg(short, long)]
+        data: Option<String>,
+
+        /// Une ligne JSON par tweet
+        #[arg(long, global = true)]
+        json: bool,
+
+        /// Tweet à classer, répétable
+        #[arg(short, long = "tweet", global = true)]
+        tweets: Vec<String>,
+
+        #[command(subcommand)]
+        algorithme: Option<Algorithme>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum Algorithme {
+    /// Dictionnaires de mots positifs et négatifs
+    Naive(ArgumentsLexique),
+    /// K plus proches voisins (distance de Jaccard)
+    Knn(ArgumentsKnn),
+    /// Bayes naïf
+    Bayes(ArgumentsBayes),
+    /// Étiquette majoritaire du cluster
+    Clustering(ArgumentsClustering),
+}
+
+#[derive(Subcommand, Debug)]
+enum Entrainement {
+    /// Apprend des lexiques positif et négatif, écrits en TSV à la place du modèle
+    Naive(ArgumentsInduction),
+    Knn(ArgumentsKnn),
+    Bayes(ArgumentsBayes),
+    Clustering(ArgumentsClustering),
+}
+
+#[derive(Args, Debug)]
+struct ArgumentsKnn {
+    #[arg(short, default_value_t = 3)]
+    k: i64,
+
+    /// 0 = majoritaire, 1 = pondéré par l'inverse de la distance
+    #[arg(long, default_value_t = 0)]
+    vote: i64,
+}
+
+#[derive(Args, Debug)]
+struct ArgumentsBayes {
+    /// Lissage : 0 = Laplace, 1 = Lidstone
+    #[arg(long, default_value_t = 0)]
+    vote: i64,
+
+    /// 0 = présence, 1 = fréquence
+    #[arg(long, default_value_t = 0)]
+    representation: i64,
 
-use crate::{regex_ext::RegexLogicalBuilder, rule_filter::RuleFilter};
+    /// 0 = unigrammes, 1 = bigrammes, 2 = les deux
+    #[arg(long, default_value_t = 0)]
+    ngram: i64,
+}
 
-mod regex_ext;
-mod rule_filter;
+#[derive(Args, Debug)]
+struct ArgumentsClustering {
+    #[arg(short, default_value_t = 3)]
+    k: usize,
 
-fn main() {
-    //let args: Vec<String> = env::args().collect();
-    //naive_annotation(&args[1], &args[2], &args[3]);
-    let test = "?[]";
-    println!("{}", RegexLogicalBuilder::protect_string(test));
+    /// 0 = average, 1 = complete, 2 = Ward, 3 = k-means, 4 = k-médoïdes CLARA
+    #[arg(long, default_value_t = 0)]
+    method: usize,
 }
 
-fn naive_annotation(data_path: &str, positive_path: &str, negative_path: &str) -> Result<String, Box<dyn Error>> {
-    let pos_reg = words_dictionnary_to_reg(&positive_path)?;
-    let neg_reg = words_dictionnary_to_reg(&negative_path)?;
+#[derive(Args, Debug)]
+struct ArgumentsInduction {
+    /// Lexique positif à écrire
+    #[arg(short, long, default_value = "learned_positive.tsv")]
+    positive: String,
+
+    /// Lexique négatif à écrire
+    #[arg(short, long, default_value = "learned_negative.tsv")]
+    negative: String,
 
-    let mut rdr = Reader::from_path(data_path)?;
-    let mut wtr = Writer::from_path("naive_annotation.csv")?;
+    /// 0 = log-odds (a priori de Dirichlet), 1 = PMI
+    #[arg(long, default_value_t = 0)]
+    method: i64,
 
-    rdr.records().into_iter().for_each(|record| {
-        let ex_record =  record.unwrap();
-        record_analysis(ex_record, &pos_reg, &neg_reg, &mut wtr, 5);
-    });
+    /// Occurrences minimales d'un mot dans les tweets positifs et négatifs
+    #[arg(long, default_value_t = 5)]
+    min_count: usize,
 
+    /// Nombre maximal de mots par polarité (0 = pas de limite)
+    #[arg(long, default_value_t = 0)]
+    top: usize,
 
-    let path = fs::canonicalize(PathBuf::from("naive_annotation.csv"))?;
-    Ok(path.display().to_string())
+    /// Dictionnaire positif dont les entrées sont toujours gardées
+    #[arg(long, default_value = "")]
+    seed_positive: String,
+
+    /// Dictionnaire négatif dont les entrées sont toujours gardées
+    #[arg(long, default_value = "")]
+    seed_negative: String,
 }
 
-fn record_analysis(record: StringRecord, pos_reg: &Vec<String>, neg_reg: &Vec<String>, wtr: &mut Writer<File>, obj_col: usize) -> Result<(), Box<dyn Error>> {
-    let obj = record.get(obj_col).ok_or(format!("No column {obj_col} found"))?;
+// Ce qui classe les tweets de classify
+enum Classifieur {
+    Modele(Modele),
+    Naive { lexique: Lexique, modificateurs: Modificateurs, weight: f32 },
+}
 
-    let mut positives: u32 = 0;
-    let mut negatives: u32 = 0;
-    obj.split(" ").into_iter().for_each(|mut word| {
-        word = word.trim();
-        if pos_reg.contains(&word.to_string()) {
-            positives += 1;
-        } else if neg_reg.contains(&word.to_string()) {
-            negatives += 1;
+impl Classifieur {
+    fn classer(&mut self, tweet: &str) -> i32 {
+        match self {
+            Classifieur::Modele(modele) => modele.classifier(tweet),
+            Classifieur::Naive { lexique, modificateurs, weight } => analyser_tweet(tweet, lexique, modificateurs, *weight),
         }
-    });
+    }
+}
+
+fn main() -> ExitCode {
+    let resultat = match Cli::parse().commande {
+        Commande::Clean { input, output, rules } => nettoyer(&input, &output, rules.as_deref()),
+        Commande::Annotate(annotation) => annotation.annoter(),
+        Commande::Train { data, output, algorithme } => entrainer(&data, &output, &algorithme),
+        Commande::Evaluate { data, seed, json, algorithme } => evaluer(&data, seed, json, &algorithme),
+        Commande::Classify { model, data, json, tweets, algorithme } => classifier(model, data, algorithme, json, tweets),
+    };
+
+    match resultat {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Erreur: {}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn nettoyer(input: &str, output: &str, rules: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let mut log = |message: String| eprintln!("{}", message.trim_end());
+    let chemin = entry::clean_data_to(input, output, rules, &mut log, &Progress::default())?;
+    println!("{}", chemin);
+    Ok(())
+}
+
+fn entrainer(data: &str, output: &str, algorithme: &Entrainement) -> Result<(), Box<dyn Error>> {
+    let modele = match algorithme {
+        Entrainement::Naive(args) => {
+            let parametres = induction::Parametres {
+                methode: induction::Methode::from(args.method),
+                min_occurrences: args.min_count.max(1),
+                nb_max: args.top,
+            };
+            let appris = apprendre_lexique(data, &args.positive, &args.negative, &parametres, &args.seed_positive, &args.seed_negative)?;
+            println!(
+                "{} mots positifs dans {}, {} mots négatifs dans {} ({} graines contredites par les données)",
+                appris.positifs.len(), args.positive, appris.negatifs.len(), args.negative, appris.conflits_graines
+            );
+            return Ok(());
+        },
+        Entrainement::Knn(args) => Modele::knn(data, args.k, args.vote)?,
+        Entrainement::Bayes(args) => Modele::bayes(data, args.vote, args.representation, args.ngram)?,
+        Entrainement::Clustering(args) => Modele::clustering(data, args.k, args.method)?,
+    };
+    modele.enregistrer(output)?;
+    println!("Modèle {} enregistré dans {}", modele.algorithme(), output);
+    Ok(())
+}
 
-    let rating = compute_polarity_with_weight(negatives, positives, 0_f32)?;
+fn evaluer(data: &str, seed: i64, json: bool, algorithme: &Algorithme) -> Result<(), Box<dyn Error>> {
+    let progress = Progress::default();
+    let (nom, matrice, metriques) = match algorithme {
+        Algorithme::Naive(args) => {
+            let modificateurs = (&args.modificateurs).into();
+            let matrice = naive::matrice_evaluation(data, &args.positive, &args.negative, args.weight() as f64, &modificateurs, seed, &progress)?;
+            ("naive", matrice, None)
+        },
+        Algorithme::Knn(args) => ("knn", knn::matrice_evaluation(data, args.k, args.vote, seed, &progress)?, None),
+        Algorithme::Bayes(args) => {
+            let matrice = bayes::matrice_evaluation(data, args.vote, args.representation, args.ngram, seed, &progress)?;
+            ("bayes", matrice, None)
+        },
+        Algorithme::Clustering(args) => {
+            let resultat = clustering::run_evaluation_pipeline(data, args.k, args.method, seed, &progress)?;
+            ("clustering", resultat.confusion, Some(resultat.metriques))
+        },
+    };
+    let rapport = Rapport::new(&matrice);
 
-    wtr.write_record(&[format!("{rating}").as_str(), obj]);
+    if json {
+        println!("{}", json!({ "algorithm": nom, "report": rapport, "clustering": metriques }));
+        return Ok(());
+    }
 
+    println!("{} : {} tweets de test", nom, rapport.total);
+    println!("exactitude {:.4}, F1 macro {:.4}", rapport.exactitude, rapport.f1_macro);
+    for classe in &rapport.classes {
+        println!(
+            "classe {} : précision {:.4}, rappel {:.4}, F1 {:.4} ({} tweets)",
+            classe.classe, classe.precision, classe.rappel, classe.f1, classe.support
+        );
+    }
+    println!("matrice de confusion (lignes : réel 0, 2, 4 ; colonnes : estimé 0, 2, 4)");
+    for ligne in &rapport.matrice {
+        println!("{:>6} {:>6} {:>6}", ligne[0], ligne[1], ligne[2]);
+    }
+    if let Some(metriques) = metriques {
+        println!(
+            "silhouette {:.4}, Davies-Bouldin {:.4}, pureté {:.4}, NMI {:.4}, ARI {:.4}",
+            metriques.silhouette, metriques.davies_bouldin, metriques.purete, metriques.nmi, metriques.ari
+        );
+    }
     Ok(())
 }
 
-// weight is between 0 and 1
-// 0 means if positives > negatives, polarity is 4, and vice versa
-// 1 means polarity is 0/4 if there is exclusively negative or positive words respectively, 2 otherwise.
-fn compute_polarity_with_weight(negatives: u32, positives: u32, weight: f32) -> Result<u32, Box<dyn Error>> {
-    let f_negatives : f32 = NumCast::from(negatives).ok_or(format!("Cannot cast {negatives} to f32"))?;
-    let f_positives : f32 = NumCast::from(positives).ok_or(format!("Cannot cast {positives} to f32"))?;
-    let f_total = f_negatives + f_positives;
-
-    //println!("{}", f_negatives);
-    //println!("{}", f_positives);
-    
-    let pos_ratio = f_positives / f_total;
-    if pos_ratio > weight || pos_ratio == 1_f32 { return Ok(4);}
-    let neg_ratio = f_negatives / f_total;
-    if neg_ratio > weight || neg_ratio == 1_f32 { return Ok(0);}
-
-    Ok(2)
-}
\ No newline at end of file
+fn classifier(model: Option<String>, data: Option<String>, algorithme: Option<Algorithme>, json: bool, tweets: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let donnees = || data.as_deref().ok_or("Fichier annoté (--data) nécessaire pour entraîner l'algorithme");
+    let mut classifieur = match (model, algorithme) {
+        (Some(model), None) => Classifieur::Modele(Modele::charger(&model)?),
+        (Some(_), Some(_)) => return Err("Un modèle enregistré et un algorithme ne peuvent pas être donnés ensemble".into()),
+        (None, None) => return Err("Modèle (--model) ou algorithme à donner".into()),
+        (None, Some(Algorithme::Naive(args))) => Classifieur::Naive {
+            lexique: args.lexique()?,
+            modificateurs: (&args.modificateurs).into(),
+            weight: args.weight(),
+        },
+        (None, Some(Algorithme::Knn(args))) => Classifieur::Modele(Modele::knn(donnees()?, args.k, args.vote)?),
+        (None, Some(Algorithme::Bayes(args))) => {
+            Classifieur::Modele(Modele::bayes(donnees()?, args.vote, args.representation, args.ngram)?)
+        },
+        (None, Some(Algorithme::Clustering(args))) => Classifieur::Modele(Modele::clustering(donnees()?, args.k, args.method)?),
+    };
+
+    let mut afficher = |tweet: &str| {
+        let label = classifieur.classer(tweet);
+        if json {
+            println!("{}", json!({ "text": tweet, "label": label }));
+        } else {
+            println!("{}\t{}", label, tweet);
+        }
+    };
+
+    if tweets.is_empty() {
+        for ligne in io::stdin().lock().lines() {
+            let ligne = ligne?;
+            if !ligne.trim().is_empty() {
+                afficher(&ligne);
+            }
+        }
+    } else {
+        tweets.iter().for_each(|tweet| afficher(tweet));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn analyser(arguments: &[&str]) -> Result<Commande, clap::Error> {
+        Cli::try_parse_from(std::iter::once("twitter-classification").chain(arguments.iter().copied())).map(|cli| cli.commande)
+    }
+
+    #[test]
+    fn definition_coherente() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn sous_commandes() {
+        assert!(matches!(
+            analyser(&["clean", "-i", "brut.csv"]),
+            Ok(Commande::Clean { output, rules: None, .. }) if output == "clean_data.csv"
+        ));
+        assert!(matches!(analyser(&["annotate", "-i", "brut.csv", "-p", "pos.txt", "--header"]), Ok(Commande::Annotate(_))));
+        assert!(matches!(
+            analyser(&["train", "-d", "data.csv", "knn", "-k", "5"]),
+            Ok(Commande::Train { algorithme: Entrainement::Knn(ArgumentsKnn { k: 5, vote: 0 }), .. })
+        ));
+        assert!(matches!(
+            analyser(&["evaluate", "-d", "data.csv", "-s", "-1", "clustering", "-k", "4", "--json"]),
+            Ok(Commande::Evaluate { seed: -1, json: true, algorithme: Algorithme::Clustering(ArgumentsClustering { k: 4, method: 0 }), .. })
+        ));
+        assert!(matches!(
+            analyser(&["classify", "-m", "model.json", "-t", "un", "-t", "deux"]),
+            Ok(Commande::Classify { model: Some(_), algorithme: None, tweets, .. }) if tweets == ["un", "deux"]
+        ));
+    }
+
+    #[test]
+    fn modificateurs_du_classifieur_naif() {
+        let Ok(Commande::Evaluate { algorithme: Algorithme::Naive(args), .. }) =
+            analyser(&["evaluate", "-d", "data.csv", "naive", "-p", "pos.txt", "--negation", "--negation-window", "0", "-w", "2"])
+        else {
+            panic!("evaluate naive non reconnu");
+        };
+        let modificateurs = Modificateurs::from(&args.modificateurs);
+        assert!(modificateurs.negation && !modificateurs.emojis);
+        assert_eq!(modificateurs.fenetre_negation, 1);
+        assert_eq!(args.weight(), 1.0);
+    }
+
+    #[test]
+    fn arguments_refuses() {
+        // Fichier obligatoire, modèle et données exclusifs, algorithme obligatoire pour evaluate
+        assert!(analyser(&["clean"]).is_err());
+        assert!(analyser(&["classify", "-m", "model.json", "-d", "data.csv"]).is_err());
+        assert!(analyser(&["evaluate", "-d", "data.csv"]).is_err());
+        assert!(analyser(&["evaluate", "-d", "data.csv", "svm"]).is_err());
+    }
+}